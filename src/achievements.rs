@@ -0,0 +1,126 @@
+use crate::*;
+
+/// Distinct tags across finished books that earn `GenreExplorer`.
+const GENRE_EXPLORER_TAGS: usize = 5;
+/// Page count of a single finished book that earns `MarathonReader`.
+const MARATHON_PAGES: u64 = 1_000;
+
+#[derive(
+    BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, Copy, PartialEq, Debug,
+)]
+#[serde(crate = "near_sdk::serde")]
+pub enum Achievement {
+    /// Finished a first book.
+    FirstBook,
+    /// Finished books across five different tags.
+    GenreExplorer,
+    /// Finished a book of at least 1,000 pages.
+    MarathonReader,
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct EarnedAchievement {
+    achievement: Achievement,
+    earned_at: U64,
+}
+
+#[near_bindgen]
+impl Contract {
+    pub fn get_achievements(&self, account_id: ValidAccountId) -> Vec<EarnedAchievement> {
+        self.achievements_by_account_id
+            .get(account_id.as_ref())
+            .unwrap_or_default()
+    }
+}
+
+impl Contract {
+    /// Awards the achievements `book`, which was just finished, unlocks. Achievements are kept
+    /// even if the book is later deleted.
+    pub(crate) fn internal_check_achievements(&mut self, account_id: &AccountId, book: &Book) {
+        let mut earned = self
+            .achievements_by_account_id
+            .get(account_id)
+            .unwrap_or_default();
+        let has = |earned: &Vec<EarnedAchievement>, achievement: Achievement| {
+            earned.iter().any(|entry| entry.achievement == achievement)
+        };
+        let mut unlocked = vec![];
+
+        if !has(&earned, Achievement::FirstBook) {
+            unlocked.push(Achievement::FirstBook);
+        }
+        if !has(&earned, Achievement::MarathonReader)
+            && book.pages.is_some_and(|pages| pages >= MARATHON_PAGES)
+        {
+            unlocked.push(Achievement::MarathonReader);
+        }
+        if !has(&earned, Achievement::GenreExplorer) && !book.tags.is_empty() {
+            // Tags are only collected until the achievement is earned.
+            let mut tags = self
+                .explored_tags_by_account_id
+                .get(account_id)
+                .unwrap_or_default();
+            for tag in &book.tags {
+                if !tags.contains(tag) {
+                    tags.push(tag.clone());
+                }
+            }
+            if tags.len() >= GENRE_EXPLORER_TAGS {
+                self.explored_tags_by_account_id.remove(account_id);
+                unlocked.push(Achievement::GenreExplorer);
+            } else {
+                self.explored_tags_by_account_id.insert(account_id, &tags);
+            }
+        }
+
+        if unlocked.is_empty() {
+            return;
+        }
+        let earned_at = env::block_timestamp().into();
+        for achievement in unlocked {
+            emit_achievement_event(account_id, achievement);
+            earned.push(EarnedAchievement {
+                achievement,
+                earned_at,
+            });
+        }
+        self.achievements_by_account_id.insert(account_id, &earned);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{get_context, get_contract, get_sample_book};
+    use near_sdk::testing_env;
+    use near_sdk::MockedBlockchain;
+    use std::convert::TryFrom;
+
+    #[test]
+    fn test_achievements() {
+        let context = get_context(vec![], false);
+        testing_env!(context);
+        let mut contract = get_contract();
+        for tags in [
+            vec!["Fantasy", "Horror"],
+            vec!["Poetry", "History", "Fantasy", "Travel"],
+        ] {
+            contract.add_book(Book {
+                status: Status::Finished,
+                tags: tags.into_iter().map(String::from).collect(),
+                ..get_sample_book()
+            });
+        }
+
+        let achievements: Vec<Achievement> = contract
+            .get_achievements(ValidAccountId::try_from("carol_near").unwrap())
+            .into_iter()
+            .map(|earned| earned.achievement)
+            .collect();
+        assert_eq!(
+            achievements,
+            vec![Achievement::FirstBook, Achievement::GenreExplorer]
+        );
+    }
+}
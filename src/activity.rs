@@ -0,0 +1,121 @@
+use crate::*;
+
+/// Number of entries kept in the contract-wide activity ring buffer.
+const RECENT_ACTIVITY_CAPACITY: u64 = 100;
+/// Number of entries kept per account for the feeds of its followers.
+const ACCOUNT_ACTIVITY_CAPACITY: usize = 10;
+
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, PartialEq, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub enum ActivityKind {
+    BookAdded,
+    BookFinished,
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct Activity {
+    pub kind: ActivityKind,
+    pub account_id: AccountId,
+    pub book_id: BookId,
+    pub title: String,
+    pub timestamp: U64,
+}
+
+#[near_bindgen]
+impl Contract {
+    /// Pages through the most recent activity entries, newest first.
+    pub fn get_recent_activity(
+        &self,
+        from_index: Option<u64>,
+        limit: Option<u64>,
+    ) -> Page<Activity> {
+        let len = self.recent_activity.len();
+        let offsets: Vec<u64> = (0..len).collect();
+
+        Page::from_vec(offsets, from_index, limit, |offset| {
+            let index = (self.recent_activity_head + len - 1 - offset) % len;
+            self.recent_activity.get(index)
+        })
+    }
+}
+
+impl Contract {
+    pub(crate) fn internal_record_activity(
+        &mut self,
+        kind: ActivityKind,
+        account_id: &AccountId,
+        book: &Book,
+    ) {
+        if !book.is_listed() {
+            return;
+        }
+        let activity = Activity {
+            kind,
+            account_id: account_id.clone(),
+            book_id: book.book_id.clone().unwrap_or_default(),
+            title: book.title.clone(),
+            timestamp: env::block_timestamp().into(),
+        };
+
+        let mut account_activity = self
+            .activity_by_account_id
+            .get(account_id)
+            .unwrap_or_default();
+        if account_activity.len() == ACCOUNT_ACTIVITY_CAPACITY {
+            account_activity.remove(0);
+        }
+        account_activity.push(activity.clone());
+        self.activity_by_account_id
+            .insert(account_id, &account_activity);
+
+        if self.recent_activity.len() < RECENT_ACTIVITY_CAPACITY {
+            self.recent_activity.push(&activity);
+        } else {
+            self.recent_activity
+                .replace(self.recent_activity_head, &activity);
+        }
+        self.recent_activity_head = (self.recent_activity_head + 1) % RECENT_ACTIVITY_CAPACITY;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{get_context, get_contract, get_sample_book};
+    use near_sdk::testing_env;
+    use near_sdk::MockedBlockchain;
+
+    #[test]
+    fn test_get_recent_activity() {
+        let context = get_context(vec![], false);
+        testing_env!(context);
+        let mut contract = get_contract();
+        let book_id = contract.add_book(get_sample_book());
+        contract.update_book(book_id.clone(), Status::Finished, None);
+
+        let activity = contract.get_recent_activity(None, None).items;
+        assert_eq!(activity.len(), 2);
+        assert_eq!(activity[0].kind, ActivityKind::BookFinished);
+        assert_eq!(activity[0].book_id, book_id);
+        assert_eq!(activity[1].kind, ActivityKind::BookAdded);
+    }
+
+    #[test]
+    fn test_recent_activity_is_bounded() {
+        let mut context = get_context(vec![], false);
+        testing_env!(context.clone());
+        let mut contract = get_contract();
+        for _ in 0..RECENT_ACTIVITY_CAPACITY + 5 {
+            context.storage_usage = env::storage_usage();
+            testing_env!(context.clone());
+            contract.add_book(get_sample_book());
+        }
+
+        let activity = contract.get_recent_activity(None, None).items;
+        assert_eq!(activity.len() as u64, RECENT_ACTIVITY_CAPACITY);
+        assert_eq!(activity[0].book_id, "105".to_string());
+        assert_eq!(activity[99].book_id, "6".to_string());
+        assert_eq!(contract.get_recent_activity(None, Some(3)).items.len(), 3);
+    }
+}
@@ -0,0 +1,235 @@
+use crate::*;
+use near_sdk::{assert_one_yocto, ext_contract, Balance, Promise};
+use std::collections::HashMap;
+
+const GAS_FOR_NFT_ON_APPROVE: Gas = 25_000_000_000_000;
+const NO_DEPOSIT: Balance = 0;
+
+/// NEP-178 approvals on one book. Approval ids keep counting up across revokes so an old id
+/// can never be reused.
+#[derive(BorshDeserialize, BorshSerialize, Default)]
+pub struct Approvals {
+    next_approval_id: u64,
+    approved_account_ids: HashMap<AccountId, u64>,
+}
+
+#[ext_contract(ext_approval_receiver)]
+pub trait NonFungibleTokenApprovalReceiver {
+    fn nft_on_approve(
+        &mut self,
+        token_id: BookId,
+        owner_id: AccountId,
+        approval_id: u64,
+        msg: String,
+    );
+}
+
+#[near_bindgen]
+impl Contract {
+    /// Lets `account_id` transfer one of the caller's books, and change its status or rating
+    /// with `update_book` and `rate_book`. With `msg`, `nft_on_approve` is called on the
+    /// approved account. The attached deposit goes to the caller's storage balance.
+    #[payable]
+    pub fn nft_approve(
+        &mut self,
+        token_id: BookId,
+        account_id: ValidAccountId,
+        msg: Option<String>,
+    ) -> Option<Promise> {
+        self.assert_can_mutate();
+        assert!(
+            env::attached_deposit() >= 1,
+            "Requires attached deposit of at least 1 yoctoNEAR"
+        );
+        let owner_id = env::predecessor_account_id();
+        let initial_storage = env::storage_usage();
+        self.assert_book_owner(&owner_id, &token_id);
+
+        let mut approvals = self.approvals_by_book_id.get(&token_id).unwrap_or_default();
+        let approval_id = approvals.next_approval_id;
+        approvals
+            .approved_account_ids
+            .insert(account_id.to_string(), approval_id);
+        approvals.next_approval_id += 1;
+        self.approvals_by_book_id.insert(&token_id, &approvals);
+        self.internal_storage_deposit(None, None);
+        self.internal_charge_storage(&owner_id, initial_storage);
+
+        msg.map(|msg| {
+            ext_approval_receiver::nft_on_approve(
+                token_id,
+                owner_id,
+                approval_id,
+                msg,
+                account_id.as_ref(),
+                NO_DEPOSIT,
+                env::prepaid_gas() - GAS_FOR_NFT_ON_APPROVE,
+            )
+        })
+    }
+
+    #[payable]
+    pub fn nft_revoke(&mut self, token_id: BookId, account_id: ValidAccountId) {
+        self.assert_can_mutate();
+        assert_one_yocto();
+        let owner_id = env::predecessor_account_id();
+        let initial_storage = env::storage_usage();
+        self.assert_book_owner(&owner_id, &token_id);
+
+        if let Some(mut approvals) = self.approvals_by_book_id.get(&token_id) {
+            approvals.approved_account_ids.remove(account_id.as_ref());
+            self.approvals_by_book_id.insert(&token_id, &approvals);
+        }
+        self.internal_charge_storage(&owner_id, initial_storage);
+    }
+
+    #[payable]
+    pub fn nft_revoke_all(&mut self, token_id: BookId) {
+        self.assert_can_mutate();
+        assert_one_yocto();
+        let owner_id = env::predecessor_account_id();
+        let initial_storage = env::storage_usage();
+        self.assert_book_owner(&owner_id, &token_id);
+
+        self.internal_clear_approvals(&token_id);
+        self.internal_charge_storage(&owner_id, initial_storage);
+    }
+
+    pub fn nft_is_approved(
+        &self,
+        token_id: BookId,
+        approved_account_id: ValidAccountId,
+        approval_id: Option<u64>,
+    ) -> bool {
+        self.approvals_by_book_id
+            .get(&token_id)
+            .and_then(|approvals| {
+                approvals
+                    .approved_account_ids
+                    .get(approved_account_id.as_ref())
+                    .copied()
+            })
+            .is_some_and(|id| approval_id.is_none_or(|approval_id| approval_id == id))
+    }
+}
+
+impl Contract {
+    /// Checks that the caller owns `book_id` or holds an approval for it, matching
+    /// `approval_id` when one is given. Returns the owner, and the caller if it acted on an
+    /// approval.
+    pub(crate) fn internal_authorize(
+        &self,
+        book_id: &BookId,
+        approval_id: Option<u64>,
+    ) -> (AccountId, Option<AccountId>) {
+        let caller_id = self.internal_caller_id();
+        let owner_id = self
+            .books
+            .get(book_id)
+            .and_then(|book| book.account_id)
+            .expect("Book does not exist");
+        if caller_id == owner_id {
+            return (owner_id, None);
+        }
+
+        let id = self
+            .approvals_by_book_id
+            .get(book_id)
+            .and_then(|approvals| approvals.approved_account_ids.get(&caller_id).copied())
+            .expect("Book does not exist");
+        assert!(
+            approval_id.is_none_or(|approval_id| approval_id == id),
+            "Approval id does not match"
+        );
+        (owner_id, Some(caller_id))
+    }
+
+    pub(crate) fn internal_approved_account_ids(
+        &self,
+        book_id: &BookId,
+    ) -> HashMap<AccountId, u64> {
+        self.approvals_by_book_id
+            .get(book_id)
+            .map(|approvals| approvals.approved_account_ids)
+            .unwrap_or_default()
+    }
+
+    /// Drops every approval on a book, e.g. once it changes hands. The approval counter is kept.
+    pub(crate) fn internal_clear_approvals(&mut self, book_id: &BookId) {
+        if let Some(mut approvals) = self.approvals_by_book_id.get(book_id) {
+            approvals.approved_account_ids.clear();
+            self.approvals_by_book_id.insert(book_id, &approvals);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{get_context, get_contract, get_sample_book};
+    use near_sdk::testing_env;
+    use near_sdk::MockedBlockchain;
+    use std::convert::TryFrom;
+
+    #[test]
+    fn test_approved_account_can_edit_and_transfer() {
+        let mut context = get_context(vec![], false);
+        testing_env!(context.clone());
+        let mut contract = get_contract();
+        let book_id = contract.add_book(get_sample_book());
+
+        context.attached_deposit = 1;
+        context.storage_usage = env::storage_usage();
+        testing_env!(context.clone());
+        let dave = ValidAccountId::try_from("dave_near").unwrap();
+        contract.nft_approve(book_id.clone(), dave.clone(), None);
+        assert!(contract.nft_is_approved(book_id.clone(), dave.clone(), Some(0)));
+
+        context.predecessor_account_id = "dave_near".to_string();
+        context.attached_deposit = 0;
+        context.storage_usage = env::storage_usage();
+        testing_env!(context.clone());
+        let book = contract
+            .update_book(book_id.clone(), Status::Read, Some(0))
+            .unwrap();
+        assert_eq!(book.account_id, Some("carol_near".to_string()));
+        assert_eq!(book.status, Status::Read);
+
+        context.attached_deposit = 1;
+        context.storage_usage = env::storage_usage();
+        testing_env!(context);
+        contract.nft_transfer(
+            ValidAccountId::try_from("erin_near").unwrap(),
+            book_id.clone(),
+            Some(0),
+            None,
+        );
+        let token = contract.nft_token(book_id.clone()).unwrap();
+        assert_eq!(token.owner_id, "erin_near");
+        assert!(token.approved_account_ids.is_empty());
+        assert!(!contract.nft_is_approved(book_id, dave, None));
+    }
+
+    #[test]
+    #[should_panic(expected = "Approval id does not match")]
+    fn test_stale_approval_id() {
+        let mut context = get_context(vec![], false);
+        testing_env!(context.clone());
+        let mut contract = get_contract();
+        let book_id = contract.add_book(get_sample_book());
+
+        context.attached_deposit = 1;
+        context.storage_usage = env::storage_usage();
+        testing_env!(context.clone());
+        let dave = ValidAccountId::try_from("dave_near").unwrap();
+        contract.nft_approve(book_id.clone(), dave.clone(), None);
+        contract.nft_revoke(book_id.clone(), dave.clone());
+        contract.nft_approve(book_id.clone(), dave, None);
+
+        context.predecessor_account_id = "dave_near".to_string();
+        context.attached_deposit = 0;
+        context.storage_usage = env::storage_usage();
+        testing_env!(context);
+        contract.rate_book(book_id, 4, Some(0));
+    }
+}
@@ -0,0 +1,129 @@
+use crate::*;
+
+#[near_bindgen]
+impl Contract {
+    /// Moves one of the caller's books out of their library into their archive. Archived books
+    /// are excluded from `get_book` and `get_books` until restored.
+    pub fn archive_book(&mut self, book_id: BookId) -> Book {
+        self.assert_can_mutate();
+        let account_id = self.internal_caller_id();
+        let initial_storage = env::storage_usage();
+        self.assert_book_owner(&account_id, &book_id);
+        self.assert_not_on_loan(&book_id);
+        self.assert_not_at_auction(&book_id);
+
+        let mut book = self.books.remove(&book_id).unwrap();
+        self.internal_remove_book_from_owner(&account_id, &book_id, &book);
+        self.internal_remove_sale(&book_id);
+        book.touch();
+        self.archived_books.insert(&book_id, &book);
+
+        let mut archived_ids = self
+            .archived_books_by_owner_id
+            .get(&account_id)
+            .unwrap_or_else(|| {
+                UnorderedSet::new(StorageKey::ArchivedBooksPerOwner {
+                    account_hash: env::sha256(account_id.as_bytes()),
+                })
+            });
+        archived_ids.insert(&book_id);
+        self.archived_books_by_owner_id
+            .insert(&account_id, &archived_ids);
+        self.internal_charge_book_update(&account_id, &book_id, initial_storage);
+
+        book
+    }
+
+    pub fn restore_book(&mut self, book_id: BookId) -> Book {
+        self.assert_can_mutate();
+        let account_id = self.internal_caller_id();
+        self.assert_book_quota(&account_id, 1);
+        let initial_storage = env::storage_usage();
+
+        let mut archived_ids = self
+            .archived_books_by_owner_id
+            .get(&account_id)
+            .expect("Archived book does not exist");
+        assert!(
+            archived_ids.remove(&book_id),
+            "Archived book does not exist"
+        );
+        self.archived_books_by_owner_id
+            .insert(&account_id, &archived_ids);
+
+        let mut book = self.archived_books.remove(&book_id).unwrap();
+        book.touch();
+        self.books.insert(&book_id, &book);
+        self.internal_add_book_to_owner(&account_id, &book_id, &book);
+        self.internal_charge_book_update(&account_id, &book_id, initial_storage);
+
+        book
+    }
+
+    pub fn get_archived_books(
+        &self,
+        account_id: ValidAccountId,
+        from_index: Option<u64>,
+        limit: Option<u64>,
+    ) -> Page<Book> {
+        match self.archived_books_by_owner_id.get(&account_id.to_string()) {
+            Some(archived_ids) => {
+                Page::from_vector(archived_ids.as_vector(), from_index, limit, |book_id| {
+                    self.archived_books
+                        .get(&book_id)
+                        .filter(|book| self.internal_is_listed(book))
+                })
+            }
+            None => Page::empty(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{get_context, get_contract, get_sample_book};
+    use near_sdk::testing_env;
+    use near_sdk::MockedBlockchain;
+    use std::convert::TryFrom;
+
+    #[test]
+    fn test_archive_and_restore_book() {
+        let context = get_context(vec![], false);
+        testing_env!(context);
+        let mut contract = get_contract();
+        let book_id = contract.add_book(get_sample_book());
+        let account_id = ValidAccountId::try_from("carol_near").unwrap();
+
+        contract.archive_book(book_id.clone());
+        assert!(contract.books.get(&book_id).is_none());
+        assert_eq!(
+            contract
+                .get_books(Some(account_id.clone()), None, None, None, None, None)
+                .items
+                .len(),
+            0
+        );
+        assert_eq!(
+            contract
+                .get_archived_books(account_id.clone(), None, None)
+                .items
+                .len(),
+            1
+        );
+
+        contract.restore_book(book_id.clone());
+        assert_eq!(contract.get_archived_books(account_id, None, None).total, 0);
+        assert_eq!(contract.get_book(book_id.clone()).book_id, Some(book_id));
+    }
+
+    #[test]
+    #[should_panic(expected = "Archived book does not exist")]
+    fn test_restore_book_not_archived() {
+        let context = get_context(vec![], false);
+        testing_env!(context);
+        let mut contract = get_contract();
+        let book_id = contract.add_book(get_sample_book());
+        contract.restore_book(book_id);
+    }
+}
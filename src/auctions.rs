@@ -0,0 +1,375 @@
+use crate::*;
+use near_sdk::json_types::U128;
+use near_sdk::{assert_one_yocto, Promise};
+
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct Bid {
+    bidder_id: AccountId,
+    amount: U128,
+}
+
+/// A timed auction of one book. The highest bid is held by the contract until the auction is
+/// settled; outbid deposits are refunded straight away. While it runs the book cannot be
+/// deleted, archived or transferred. The seller pays for the auction's storage; the contract
+/// covers the one bid it holds, so bidders never need a storage balance.
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct Auction {
+    book_id: BookId,
+    seller_id: AccountId,
+    min_bid: U128,
+    started_at: U64,
+    end_at: U64,
+    highest_bid: Option<Bid>,
+}
+
+#[near_bindgen]
+impl Contract {
+    /// Auctions one of the caller's books until `end_ts`. Requires one yoctoNEAR, like
+    /// `nft_transfer`.
+    #[payable]
+    pub fn start_auction(&mut self, book_id: BookId, min_bid: U128, end_ts: U64) -> Auction {
+        self.assert_can_mutate();
+        assert_one_yocto();
+        let seller_id = env::predecessor_account_id();
+        let initial_storage = env::storage_usage();
+        self.assert_book_owner(&seller_id, &book_id);
+        self.assert_not_on_loan(&book_id);
+        self.assert_not_at_auction(&book_id);
+        assert!(self.sales.get(&book_id).is_none(), "Book is for sale");
+        assert!(min_bid.0 > 0, "Minimum bid must be greater than zero");
+        let now = env::block_timestamp();
+        assert!(end_ts.0 > now, "Auction must end in the future");
+
+        let auction = Auction {
+            book_id: book_id.clone(),
+            seller_id: seller_id.clone(),
+            min_bid,
+            started_at: now.into(),
+            end_at: end_ts,
+            highest_bid: None,
+        };
+        self.auctions.insert(&book_id, &auction);
+        self.internal_charge_storage(&seller_id, initial_storage);
+
+        auction
+    }
+
+    /// Bids the attached deposit. It must be at least the minimum bid and more than the current
+    /// highest bid, which is refunded.
+    #[payable]
+    pub fn bid(&mut self, book_id: BookId) -> Auction {
+        self.assert_can_mutate();
+        let bidder_id = env::predecessor_account_id();
+        let amount = env::attached_deposit();
+        let mut auction = self.auctions.get(&book_id).expect("Auction does not exist");
+        assert!(
+            env::block_timestamp() < auction.end_at.0,
+            "Auction has ended"
+        );
+        assert_ne!(
+            auction.seller_id, bidder_id,
+            "Cannot bid on your own auction"
+        );
+        assert!(amount >= auction.min_bid.0, "Bid is below the minimum bid");
+        assert!(
+            self.storage_accounts.get(&bidder_id).is_some(),
+            "Account is not registered, call storage_deposit first"
+        );
+        if let Some(highest_bid) = &auction.highest_bid {
+            assert!(
+                amount > highest_bid.amount.0,
+                "Bid must be higher than the current bid"
+            );
+        }
+
+        let outbid = auction.highest_bid.replace(Bid {
+            bidder_id,
+            amount: U128(amount),
+        });
+        self.auctions.insert(&book_id, &auction);
+        if let Some(outbid) = outbid {
+            Promise::new(outbid.bidder_id).transfer(outbid.amount.0);
+        }
+
+        auction
+    }
+
+    /// Ends an auction once its end time has passed. Anyone can call it. The book goes to the
+    /// highest bidder and the bid is paid to the seller, minus any royalties. Without bids, or
+    /// if the winner has closed their storage account, lacks the storage balance for the book
+    /// or has a full library, the seller keeps the book and any bid is refunded. Returns the winner, if any.
+    pub fn settle_auction(&mut self, book_id: BookId) -> Option<AccountId> {
+        self.assert_can_mutate();
+        let mut auction = self.auctions.get(&book_id).expect("Auction does not exist");
+        assert!(
+            env::block_timestamp() >= auction.end_at.0,
+            "Auction has not ended yet"
+        );
+
+        let highest_bid = self.internal_drop_bid(&book_id, &mut auction);
+        let initial_storage = env::storage_usage();
+        self.auctions.remove(&book_id);
+        self.internal_charge_storage(&auction.seller_id, initial_storage);
+
+        let highest_bid = highest_bid?;
+        if !self.internal_can_receive_book(&highest_bid.bidder_id, &book_id) {
+            Promise::new(highest_bid.bidder_id).transfer(highest_bid.amount.0);
+            return None;
+        }
+        let payout = self.internal_payout(
+            &auction.seller_id,
+            &book_id,
+            highest_bid.amount.0,
+            MAX_ROYALTY_ACCOUNTS as u32 + 1,
+        );
+        self.internal_transfer_book(
+            &auction.seller_id,
+            &highest_bid.bidder_id,
+            &book_id,
+            None,
+            Some(format!("auction {}", highest_bid.amount.0)),
+        );
+        for (account_id, amount) in payout.payout {
+            if amount.0 > 0 {
+                Promise::new(account_id).transfer(amount.0);
+            }
+        }
+
+        Some(highest_bid.bidder_id)
+    }
+
+    pub fn get_auction(&self, book_id: BookId) -> Option<Auction> {
+        self.internal_get_listed_book(&book_id)
+            .and_then(|_| self.auctions.get(&book_id))
+    }
+
+    /// Auctions of public books, ended but unsettled ones included.
+    pub fn get_auctions(&self, from_index: Option<u64>, limit: Option<u64>) -> Page<Auction> {
+        Page::from_vector(
+            self.auctions.keys_as_vector(),
+            from_index,
+            limit,
+            |book_id| self.get_auction(book_id),
+        )
+    }
+}
+
+impl Contract {
+    pub(crate) fn assert_not_at_auction(&self, book_id: &BookId) {
+        assert!(self.auctions.get(book_id).is_none(), "Book is at auction");
+    }
+
+    /// Drops an auction without a winner, refunding the highest bid. Used when a moderator
+    /// removes the book.
+    pub(crate) fn internal_cancel_auction(&mut self, book_id: &BookId) {
+        if let Some(mut auction) = self.auctions.get(book_id) {
+            if let Some(highest_bid) = self.internal_drop_bid(book_id, &mut auction) {
+                Promise::new(highest_bid.bidder_id).transfer(highest_bid.amount.0);
+            }
+            let initial_storage = env::storage_usage();
+            self.auctions.remove(book_id);
+            self.internal_charge_storage(&auction.seller_id, initial_storage);
+        }
+    }
+
+    /// Takes the highest bid out of the auction. Its bytes were never charged to anyone, so
+    /// they are freed outside any storage measurement.
+    fn internal_drop_bid(&mut self, book_id: &BookId, auction: &mut Auction) -> Option<Bid> {
+        let highest_bid = auction.highest_bid.take();
+        if highest_bid.is_some() {
+            self.auctions.insert(book_id, auction);
+        }
+        highest_bid
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{get_context, get_contract, get_sample_book};
+    use near_sdk::testing_env;
+    use near_sdk::MockedBlockchain;
+
+    #[test]
+    fn test_auction_highest_bid_wins() {
+        let mut context = get_context(vec![], false);
+        context.attached_deposit = 1;
+        context.account_balance = 10u128.pow(24);
+        testing_env!(context.clone());
+        let mut contract = get_contract();
+        let book_id = contract.add_book(get_sample_book());
+        let end_at = context.block_timestamp + 1_000;
+        contract.start_auction(book_id.clone(), U128(100), U64(end_at));
+
+        context.predecessor_account_id = "dave_near".to_string();
+        context.attached_deposit = 100;
+        context.storage_usage = env::storage_usage();
+        testing_env!(context.clone());
+        contract.bid(book_id.clone());
+
+        context.predecessor_account_id = "erin_near".to_string();
+        context.attached_deposit = 150;
+        context.storage_usage = env::storage_usage();
+        testing_env!(context.clone());
+        contract.bid(book_id.clone());
+
+        context.block_timestamp = end_at;
+        context.attached_deposit = 0;
+        context.storage_usage = env::storage_usage();
+        testing_env!(context);
+        assert_eq!(
+            contract.settle_auction(book_id.clone()),
+            Some("erin_near".to_string())
+        );
+        assert_eq!(
+            contract.get_book(book_id.clone()).account_id,
+            Some("erin_near".to_string())
+        );
+        assert!(contract.get_auction(book_id).is_none());
+    }
+
+    #[test]
+    fn test_settle_after_bidder_unregisters() {
+        let mut context = get_context(vec![], false);
+        context.attached_deposit = 1;
+        context.account_balance = 10u128.pow(28);
+        testing_env!(context.clone());
+        let mut contract = get_contract();
+        let book_id = contract.add_book(get_sample_book());
+        let end_at = context.block_timestamp + 1_000;
+        contract.start_auction(book_id.clone(), U128(100), U64(end_at));
+
+        context.predecessor_account_id = "dave_near".to_string();
+        context.attached_deposit = 100;
+        context.storage_usage = env::storage_usage();
+        testing_env!(context.clone());
+        contract.bid(book_id.clone());
+
+        context.attached_deposit = 1;
+        context.storage_usage = env::storage_usage();
+        testing_env!(context.clone());
+        assert!(contract.storage_unregister(None));
+
+        context.predecessor_account_id = "erin_near".to_string();
+        context.attached_deposit = 150;
+        context.storage_usage = env::storage_usage();
+        testing_env!(context.clone());
+        contract.bid(book_id.clone());
+
+        context.block_timestamp = end_at;
+        context.attached_deposit = 0;
+        context.storage_usage = env::storage_usage();
+        testing_env!(context);
+        assert_eq!(
+            contract.settle_auction(book_id),
+            Some("erin_near".to_string())
+        );
+    }
+
+    #[test]
+    fn test_settle_after_winner_unregisters() {
+        let mut context = get_context(vec![], false);
+        context.attached_deposit = 1;
+        context.account_balance = 10u128.pow(28);
+        testing_env!(context.clone());
+        let mut contract = get_contract();
+        let book_id = contract.add_book(get_sample_book());
+        let end_at = context.block_timestamp + 1_000;
+        contract.start_auction(book_id.clone(), U128(100), U64(end_at));
+
+        context.predecessor_account_id = "dave_near".to_string();
+        context.attached_deposit = 100;
+        context.storage_usage = env::storage_usage();
+        testing_env!(context.clone());
+        contract.bid(book_id.clone());
+
+        context.attached_deposit = 1;
+        context.storage_usage = env::storage_usage();
+        testing_env!(context.clone());
+        assert!(contract.storage_unregister(None));
+
+        context.block_timestamp = end_at;
+        context.attached_deposit = 0;
+        context.storage_usage = env::storage_usage();
+        testing_env!(context);
+        assert_eq!(contract.settle_auction(book_id.clone()), None);
+        assert_eq!(
+            contract.get_book(book_id).account_id,
+            Some("carol_near".to_string())
+        );
+    }
+
+    #[test]
+    fn test_settle_after_winner_withdraws_storage() {
+        let mut context = get_context(vec![], false);
+        context.attached_deposit = 1;
+        context.account_balance = 10u128.pow(28);
+        testing_env!(context.clone());
+        let mut contract = get_contract();
+        let book_id = contract.add_book(get_sample_book());
+        let end_at = context.block_timestamp + 1_000;
+        contract.start_auction(book_id.clone(), U128(100), U64(end_at));
+
+        context.predecessor_account_id = "dave_near".to_string();
+        context.attached_deposit = 100;
+        context.storage_usage = env::storage_usage();
+        testing_env!(context.clone());
+        contract.bid(book_id.clone());
+
+        context.attached_deposit = 1;
+        context.storage_usage = env::storage_usage();
+        testing_env!(context.clone());
+        contract.storage_withdraw(None);
+
+        context.block_timestamp = end_at;
+        context.attached_deposit = 0;
+        context.storage_usage = env::storage_usage();
+        testing_env!(context);
+        assert_eq!(contract.settle_auction(book_id.clone()), None);
+        assert_eq!(
+            contract.get_book(book_id).account_id,
+            Some("carol_near".to_string())
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Bid must be higher than the current bid")]
+    fn test_bid_not_higher() {
+        let mut context = get_context(vec![], false);
+        context.attached_deposit = 1;
+        context.account_balance = 10u128.pow(24);
+        testing_env!(context.clone());
+        let mut contract = get_contract();
+        let book_id = contract.add_book(get_sample_book());
+        contract.start_auction(
+            book_id.clone(),
+            U128(100),
+            U64(context.block_timestamp + 1_000),
+        );
+
+        context.predecessor_account_id = "dave_near".to_string();
+        context.attached_deposit = 100;
+        context.storage_usage = env::storage_usage();
+        testing_env!(context.clone());
+        contract.bid(book_id.clone());
+        contract.bid(book_id);
+    }
+
+    #[test]
+    #[should_panic(expected = "Book is at auction")]
+    fn test_delete_book_at_auction() {
+        let mut context = get_context(vec![], false);
+        context.attached_deposit = 1;
+        testing_env!(context.clone());
+        let mut contract = get_contract();
+        let book_id = contract.add_book(get_sample_book());
+        contract.start_auction(
+            book_id.clone(),
+            U128(100),
+            U64(context.block_timestamp + 1_000),
+        );
+        contract.delete_book(book_id);
+    }
+}
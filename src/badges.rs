@@ -0,0 +1,180 @@
+use crate::*;
+use near_sdk::json_types::U128;
+use near_sdk::{ext_contract, is_promise_success};
+
+/// Finished-book counts that earn a badge.
+const MILESTONES: [u64; 3] = [10, 50, 100];
+const GAS_FOR_NFT_MINT: Gas = 20_000_000_000_000;
+const GAS_FOR_RESOLVE_BADGE: Gas = 5_000_000_000_000;
+
+/// NFT contract that mints badges. `deposit` is attached to each `nft_mint` call to cover the
+/// token's storage there.
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct BadgeContract {
+    pub contract_id: AccountId,
+    pub deposit: U128,
+}
+
+/// `token_id` stays `None` while the mint is in flight.
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct Badge {
+    pub milestone: u64,
+    pub token_id: Option<String>,
+    pub earned_at: U64,
+}
+
+#[ext_contract(ext_badge_nft)]
+pub trait BadgeNft {
+    fn nft_mint(&mut self, token_id: String, receiver_id: AccountId, token_metadata: TokenMetadata);
+}
+
+#[ext_contract(ext_badge_resolver)]
+pub trait BadgeResolver {
+    fn resolve_badge_mint(&mut self, account_id: AccountId, milestone: u64, token_id: String);
+}
+
+#[near_bindgen]
+impl Contract {
+    /// Sets or, with `None`, turns off the contract that mints milestone badges. The contract
+    /// account pays the mint deposits.
+    pub fn set_badge_contract(&mut self, badge_contract: Option<BadgeContract>) {
+        self.update_config(ConfigPatch {
+            badge_contract: Some(badge_contract),
+            ..Default::default()
+        });
+    }
+
+    pub fn get_badge_contract(&self) -> Option<BadgeContract> {
+        self.internal_config().badge_contract
+    }
+
+    pub fn get_badges(&self, account_id: ValidAccountId) -> Vec<Badge> {
+        self.badges_by_owner_id
+            .get(account_id.as_ref())
+            .unwrap_or_default()
+    }
+
+    /// Records the minted badge, or forgets it so the milestone can be earned again.
+    #[private]
+    pub fn resolve_badge_mint(&mut self, account_id: AccountId, milestone: u64, token_id: String) {
+        let mut badges = self.badges_by_owner_id.get(&account_id).unwrap_or_default();
+        if is_promise_success() {
+            if let Some(badge) = badges.iter_mut().find(|badge| badge.milestone == milestone) {
+                badge.token_id = Some(token_id);
+            }
+        } else {
+            badges.retain(|badge| badge.milestone != milestone);
+        }
+        self.badges_by_owner_id.insert(&account_id, &badges);
+    }
+}
+
+impl Contract {
+    /// Mints a badge if the account's finished count just reached a milestone it has not been
+    /// awarded yet.
+    pub(crate) fn internal_check_milestones(&mut self, account_id: &AccountId) {
+        let badge_contract = match self.internal_config().badge_contract {
+            Some(badge_contract) => badge_contract,
+            None => return,
+        };
+        let finished = self
+            .books_by_owner_status
+            .get(&(account_id.clone(), Status::Finished))
+            .map(|book_ids| book_ids.len())
+            .unwrap_or(0);
+        if !MILESTONES.contains(&finished) {
+            return;
+        }
+        let mut badges = self.badges_by_owner_id.get(account_id).unwrap_or_default();
+        if badges.iter().any(|badge| badge.milestone == finished) {
+            return;
+        }
+
+        let now = env::block_timestamp();
+        badges.push(Badge {
+            milestone: finished,
+            token_id: None,
+            earned_at: now.into(),
+        });
+        self.badges_by_owner_id.insert(account_id, &badges);
+
+        let token_id = format!("{}:{}", account_id, finished);
+        let title = format!("{} books finished", finished);
+        ext_badge_nft::nft_mint(
+            token_id.clone(),
+            account_id.clone(),
+            TokenMetadata {
+                title: Some(title),
+                description: None,
+                media: None,
+                media_hash: None,
+                copies: Some(1),
+                issued_at: Some((now / 1_000_000).to_string()),
+                expires_at: None,
+                starts_at: None,
+                updated_at: None,
+                extra: None,
+                reference: None,
+                reference_hash: None,
+            },
+            &badge_contract.contract_id,
+            badge_contract.deposit.0,
+            GAS_FOR_NFT_MINT,
+        )
+        .then(ext_badge_resolver::resolve_badge_mint(
+            account_id.clone(),
+            finished,
+            token_id,
+            &env::current_account_id(),
+            0,
+            GAS_FOR_RESOLVE_BADGE,
+        ));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{get_context, get_contract, get_sample_book};
+    use near_sdk::testing_env;
+    use near_sdk::MockedBlockchain;
+    use std::convert::TryFrom;
+
+    #[test]
+    fn test_badge_at_milestone() {
+        let mut context = get_context(vec![], false);
+        context.account_balance = 10u128.pow(24);
+        testing_env!(context.clone());
+        let mut contract = get_contract();
+        let mut config = contract.get_config();
+        config.badge_contract = Some(BadgeContract {
+            contract_id: "badges_near".to_string(),
+            deposit: U128(10u128.pow(22)),
+        });
+        contract.internal_set_config(&config);
+        let account_id = ValidAccountId::try_from("carol_near").unwrap();
+
+        for _ in 0..9 {
+            context.storage_usage = env::storage_usage();
+            testing_env!(context.clone());
+            contract.add_book(Book {
+                status: Status::Finished,
+                ..get_sample_book()
+            });
+        }
+        assert!(contract.get_badges(account_id.clone()).is_empty());
+
+        let book_id = contract.add_book(get_sample_book());
+        contract.update_book(book_id.clone(), Status::Finished, None);
+        let badges = contract.get_badges(account_id.clone());
+        assert_eq!(badges.len(), 1);
+        assert_eq!(badges[0].milestone, 10);
+        assert_eq!(badges[0].token_id, None);
+
+        contract.update_book(book_id.clone(), Status::Read, None);
+        contract.update_book(book_id, Status::Finished, None);
+        assert_eq!(contract.get_badges(account_id).len(), 1);
+    }
+}
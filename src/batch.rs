@@ -0,0 +1,140 @@
+use crate::*;
+use near_sdk::assert_one_yocto;
+
+/// One step of a `batch` call, mirroring the single-book methods.
+#[derive(Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub enum BookOp {
+    Add {
+        book: Book,
+    },
+    Update {
+        book_id: BookId,
+        status: Status,
+    },
+    Edit {
+        book_id: BookId,
+        changes: BookEdit,
+        approval_id: Option<u64>,
+    },
+    Rate {
+        book_id: BookId,
+        rating: u8,
+    },
+    Delete {
+        book_id: BookId,
+    },
+}
+
+#[near_bindgen]
+impl Contract {
+    /// Runs the operations in order within one transaction, so a failing one reverts them all.
+    /// Each operation is guarded, and rate limited, like the method it mirrors.
+    /// Returns the id of the book each operation touched. Attach exactly one yoctoNEAR when the
+    /// batch deletes books, and nothing otherwise.
+    #[payable]
+    pub fn batch(&mut self, ops: Vec<BookOp>) -> Vec<BookId> {
+        if ops.iter().any(|op| matches!(op, BookOp::Delete { .. })) {
+            assert_one_yocto();
+        } else {
+            assert_eq!(
+                env::attached_deposit(),
+                0,
+                "A batch without deletes takes no deposit"
+            );
+        }
+        ops.into_iter()
+            .map(|op| match op {
+                BookOp::Add { book } => self.add_book(book),
+                BookOp::Update { book_id, status } => {
+                    self.update_book(book_id.clone(), status, None);
+                    book_id
+                }
+                BookOp::Edit {
+                    book_id,
+                    changes,
+                    approval_id,
+                } => {
+                    self.edit_book(book_id.clone(), changes, approval_id);
+                    book_id
+                }
+                BookOp::Rate { book_id, rating } => {
+                    self.rate_book(book_id.clone(), rating, None);
+                    book_id
+                }
+                BookOp::Delete { book_id } => {
+                    self.delete_book(book_id.clone());
+                    book_id
+                }
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{get_context, get_contract, get_sample_book};
+    use near_sdk::testing_env;
+    use near_sdk::MockedBlockchain;
+    use std::convert::TryFrom;
+
+    #[test]
+    fn test_batch() {
+        let mut context = get_context(vec![], false);
+        context.attached_deposit = 1;
+        testing_env!(context);
+        let mut contract = get_contract();
+        let old_id = contract.add_book(get_sample_book());
+
+        let book_ids = contract.batch(vec![
+            BookOp::Add {
+                book: get_sample_book(),
+            },
+            BookOp::Update {
+                book_id: "2".to_string(),
+                status: Status::Finished,
+            },
+            BookOp::Rate {
+                book_id: "2".to_string(),
+                rating: 5,
+            },
+            BookOp::Delete {
+                book_id: old_id.clone(),
+            },
+        ]);
+        assert_eq!(book_ids, vec!["2", "2", "2", &old_id]);
+
+        let account_id = ValidAccountId::try_from("carol_near").unwrap();
+        assert_eq!(contract.get_books_count(account_id), 1);
+        let book = contract.get_book("2".to_string());
+        assert_eq!(book.status, Status::Finished);
+        assert_eq!(book.rating, Some(5));
+    }
+
+    #[test]
+    #[should_panic(expected = "A batch without deletes takes no deposit")]
+    fn test_batch_rejects_deposit_without_deletes() {
+        let mut context = get_context(vec![], false);
+        context.attached_deposit = 1;
+        testing_env!(context);
+        let mut contract = get_contract();
+        contract.batch(vec![BookOp::Add {
+            book: get_sample_book(),
+        }]);
+    }
+
+    #[test]
+    #[should_panic(expected = "Requires attached deposit of exactly 1 yoctoNEAR")]
+    fn test_batch_delete_requires_one_yocto() {
+        let mut context = get_context(vec![], false);
+        testing_env!(context.clone());
+        let mut contract = get_contract();
+        let book_id = contract.add_book(get_sample_book());
+
+        context.attached_deposit = 10;
+        context.storage_usage = env::storage_usage();
+        testing_env!(context);
+        contract.batch(vec![BookOp::Delete { book_id }]);
+    }
+}
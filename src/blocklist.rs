@@ -0,0 +1,57 @@
+use crate::*;
+
+#[near_bindgen]
+impl Contract {
+    /// Bars an account, e.g. a spam account, from every mutating method. Its existing books
+    /// stay until a moderator removes them.
+    pub fn block_account(&mut self, account_id: ValidAccountId) {
+        self.assert_owner();
+        self.blocked_accounts.insert(account_id.as_ref());
+    }
+
+    pub fn unblock_account(&mut self, account_id: ValidAccountId) {
+        self.assert_owner();
+        self.blocked_accounts.remove(account_id.as_ref());
+    }
+
+    pub fn is_blocked(&self, account_id: ValidAccountId) -> bool {
+        self.blocked_accounts.contains(account_id.as_ref())
+    }
+}
+
+impl Contract {
+    /// Checks both the predecessor and the signer, so a blocked account cannot get through by
+    /// relaying its calls.
+    pub(crate) fn assert_not_blocked(&self) {
+        assert!(
+            !self
+                .blocked_accounts
+                .contains(&env::predecessor_account_id())
+                && !self.blocked_accounts.contains(&env::signer_account_id()),
+            "Account is blocked"
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{get_context, get_contract, get_sample_book};
+    use near_sdk::testing_env;
+    use near_sdk::MockedBlockchain;
+    use std::convert::TryFrom;
+
+    #[test]
+    #[should_panic(expected = "Account is blocked")]
+    fn test_blocked_account_cannot_add_book() {
+        let mut context = get_context(vec![], false);
+        context.predecessor_account_id = "alice_near".to_string();
+        testing_env!(context.clone());
+        let mut contract = get_contract();
+        contract.block_account(ValidAccountId::try_from("carol_near").unwrap());
+
+        context.predecessor_account_id = "carol_near".to_string();
+        testing_env!(context);
+        contract.add_book(get_sample_book());
+    }
+}
@@ -0,0 +1,77 @@
+use crate::*;
+
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct Bookmark {
+    label: String,
+    pub(crate) page: u64,
+}
+
+#[near_bindgen]
+impl Contract {
+    /// Adds a named bookmark to one of the caller's books. Adding a bookmark with an existing
+    /// label moves it to the new page.
+    pub fn add_bookmark(&mut self, book_id: BookId, label: String, page: u64) -> Vec<Bookmark> {
+        self.assert_can_mutate();
+        let account_id = self.internal_library_owner(&book_id);
+        let initial_storage = env::storage_usage();
+
+        let mut bookmarks = self.bookmarks_by_book_id.get(&book_id).unwrap_or_default();
+        match bookmarks
+            .iter_mut()
+            .find(|bookmark| bookmark.label == label)
+        {
+            Some(bookmark) => bookmark.page = page,
+            None => bookmarks.push(Bookmark { label, page }),
+        }
+        self.bookmarks_by_book_id.insert(&book_id, &bookmarks);
+
+        let mut book = self.books.get(&book_id).unwrap();
+        self.internal_touch_book(&account_id, &mut book);
+        self.books.insert(&book_id, &book);
+        self.internal_charge_book_update(&account_id, &book_id, initial_storage);
+
+        bookmarks
+    }
+
+    pub fn get_bookmarks(&self, book_id: BookId) -> Vec<Bookmark> {
+        if self.internal_is_private(&book_id) {
+            return vec![];
+        }
+        self.bookmarks_by_book_id.get(&book_id).unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{get_context, get_contract, get_sample_book};
+    use near_sdk::testing_env;
+    use near_sdk::MockedBlockchain;
+
+    #[test]
+    fn test_add_bookmark() {
+        let context = get_context(vec![], false);
+        testing_env!(context);
+        let mut contract = get_contract();
+        let book_id = contract.add_book(get_sample_book());
+
+        contract.add_bookmark(book_id.clone(), "Brakes".to_string(), 42);
+        contract.add_bookmark(book_id.clone(), "Carburetor".to_string(), 120);
+        contract.add_bookmark(book_id.clone(), "Brakes".to_string(), 50);
+
+        let bookmarks = contract.get_bookmarks(book_id);
+        assert_eq!(bookmarks.len(), 2);
+        assert_eq!(bookmarks[0].label, "Brakes".to_string());
+        assert_eq!(bookmarks[0].page, 50);
+        assert_eq!(bookmarks[1].page, 120);
+    }
+
+    #[test]
+    #[should_panic(expected = "Book does not exist")]
+    fn test_add_bookmark_not_owner() {
+        let context = get_context(vec![], false);
+        testing_env!(context);
+        let mut contract = get_contract();
+        contract.add_bookmark("1".to_string(), "Brakes".to_string(), 42);
+    }
+}
@@ -0,0 +1,224 @@
+use crate::*;
+
+pub type BorrowRequestId = String;
+
+const MAX_PENDING_BORROW_REQUESTS: u64 = 100;
+
+/// A request to borrow someone's public book. The requester pays for its storage until the owner
+/// approves or rejects it, or the requester cancels it.
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct BorrowRequest {
+    request_id: BorrowRequestId,
+    book_id: BookId,
+    owner_id: AccountId,
+    requester_id: AccountId,
+    created_at: U64,
+}
+
+#[near_bindgen]
+impl Contract {
+    /// Asks the owner of a public book to lend it to the caller.
+    pub fn request_borrow(&mut self, book_id: BookId) -> BorrowRequestId {
+        self.assert_can_mutate();
+        let requester_id = self.internal_caller_id();
+        let initial_storage = env::storage_usage();
+        let owner_id = self
+            .internal_get_listed_book(&book_id)
+            .and_then(|book| book.account_id)
+            .expect("Book does not exist");
+        assert_ne!(owner_id, requester_id, "Cannot borrow your own book");
+        assert!(
+            !self.internal_has_borrow_request(&requester_id, &book_id),
+            "You already requested this book"
+        );
+
+        let request_id = format!("{}", self.borrow_requests_len + 1);
+        self.borrow_requests.insert(
+            &request_id,
+            &BorrowRequest {
+                request_id: request_id.clone(),
+                book_id,
+                owner_id: owner_id.clone(),
+                requester_id: requester_id.clone(),
+                created_at: env::block_timestamp().into(),
+            },
+        );
+        self.internal_index_borrow_request(&owner_id, &request_id);
+        self.internal_index_borrow_request(&requester_id, &request_id);
+        self.borrow_requests_len += 1;
+        self.internal_charge_storage(&requester_id, initial_storage);
+
+        request_id
+    }
+
+    /// Lends the requested book to the requester until `due_timestamp`.
+    pub fn approve_borrow(&mut self, request_id: BorrowRequestId, due_timestamp: U64) -> LoanView {
+        self.assert_can_mutate();
+        let account_id = self.internal_caller_id();
+        let request = self.internal_take_borrow_request(&request_id);
+        assert_eq!(
+            request.owner_id, account_id,
+            "Only the book's owner can approve a borrow request"
+        );
+        self.internal_lend_book(
+            request.owner_id,
+            request.book_id,
+            request.requester_id,
+            due_timestamp.0,
+        )
+    }
+
+    pub fn reject_borrow(&mut self, request_id: BorrowRequestId) {
+        self.assert_can_mutate();
+        let account_id = self.internal_caller_id();
+        let request = self.internal_take_borrow_request(&request_id);
+        assert_eq!(
+            request.owner_id, account_id,
+            "Only the book's owner can reject a borrow request"
+        );
+    }
+
+    pub fn cancel_borrow_request(&mut self, request_id: BorrowRequestId) {
+        self.assert_can_mutate();
+        let account_id = self.internal_caller_id();
+        let request = self.internal_take_borrow_request(&request_id);
+        assert_eq!(
+            request.requester_id, account_id,
+            "Only the requester can cancel a borrow request"
+        );
+    }
+
+    /// Pending requests the account sent or received.
+    pub fn get_borrow_requests(
+        &self,
+        account_id: ValidAccountId,
+        from_index: Option<u64>,
+        limit: Option<u64>,
+    ) -> Page<BorrowRequest> {
+        match self.borrow_requests_by_account_id.get(account_id.as_ref()) {
+            Some(request_ids) => {
+                Page::from_vector(request_ids.as_vector(), from_index, limit, |request_id| {
+                    self.borrow_requests.get(&request_id)
+                })
+            }
+            None => Page::empty(),
+        }
+    }
+}
+
+impl Contract {
+    fn internal_has_borrow_request(&self, requester_id: &AccountId, book_id: &BookId) -> bool {
+        self.borrow_requests_by_account_id
+            .get(requester_id)
+            .is_some_and(|request_ids| {
+                request_ids.iter().any(|request_id| {
+                    self.borrow_requests
+                        .get(&request_id)
+                        .is_some_and(|request| &request.book_id == book_id)
+                })
+            })
+    }
+
+    fn internal_index_borrow_request(
+        &mut self,
+        account_id: &AccountId,
+        request_id: &BorrowRequestId,
+    ) {
+        let mut request_ids = self
+            .borrow_requests_by_account_id
+            .get(account_id)
+            .unwrap_or_else(|| {
+                UnorderedSet::new(StorageKey::BorrowRequestsPerAccount {
+                    account_hash: env::sha256(account_id.as_bytes()),
+                })
+            });
+        assert!(
+            request_ids.len() < MAX_PENDING_BORROW_REQUESTS,
+            "Too many pending borrow requests"
+        );
+        request_ids.insert(request_id);
+        self.borrow_requests_by_account_id
+            .insert(account_id, &request_ids);
+    }
+
+    /// Removes a request from both queues and releases its storage back to the requester.
+    fn internal_take_borrow_request(&mut self, request_id: &BorrowRequestId) -> BorrowRequest {
+        let initial_storage = env::storage_usage();
+        let request = self
+            .borrow_requests
+            .remove(request_id)
+            .expect("Borrow request does not exist");
+        for account_id in [&request.owner_id, &request.requester_id] {
+            if let Some(mut request_ids) = self.borrow_requests_by_account_id.get(account_id) {
+                request_ids.remove(request_id);
+                if request_ids.is_empty() {
+                    self.borrow_requests_by_account_id.remove(account_id);
+                } else {
+                    self.borrow_requests_by_account_id
+                        .insert(account_id, &request_ids);
+                }
+            }
+        }
+        self.internal_charge_storage(&request.requester_id, initial_storage);
+        request
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{get_context, get_contract, get_sample_book};
+    use near_sdk::testing_env;
+    use near_sdk::MockedBlockchain;
+    use std::convert::TryFrom;
+
+    #[test]
+    fn test_approve_borrow() {
+        let mut context = get_context(vec![], false);
+        testing_env!(context.clone());
+        let mut contract = get_contract();
+        let book_id = contract.add_book(get_sample_book());
+
+        context.predecessor_account_id = "dave_near".to_string();
+        context.storage_usage = env::storage_usage();
+        testing_env!(context.clone());
+        let request_id = contract.request_borrow(book_id.clone());
+        let carol = ValidAccountId::try_from("carol_near").unwrap();
+        assert_eq!(
+            contract
+                .get_borrow_requests(carol.clone(), None, None)
+                .items
+                .len(),
+            1
+        );
+
+        context.predecessor_account_id = "carol_near".to_string();
+        context.storage_usage = env::storage_usage();
+        testing_env!(context.clone());
+        contract.approve_borrow(request_id, U64(context.block_timestamp + 1_000));
+
+        let dave = ValidAccountId::try_from("dave_near").unwrap();
+        assert_eq!(contract.get_borrow_requests(carol, None, None).total, 0);
+        assert_eq!(
+            contract.get_borrow_requests(dave.clone(), None, None).total,
+            0
+        );
+        assert_eq!(contract.get_loans(dave, None, None).items.len(), 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "Only the book's owner can reject a borrow request")]
+    fn test_reject_borrow_not_owner() {
+        let mut context = get_context(vec![], false);
+        testing_env!(context.clone());
+        let mut contract = get_contract();
+        let book_id = contract.add_book(get_sample_book());
+
+        context.predecessor_account_id = "dave_near".to_string();
+        context.storage_usage = env::storage_usage();
+        testing_env!(context);
+        let request_id = contract.request_borrow(book_id);
+        contract.reject_borrow(request_id);
+    }
+}
@@ -0,0 +1,246 @@
+use crate::*;
+
+pub type BuddyReadId = String;
+
+/// Two readers reading the same book together, each with their own copy.
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct BuddyRead {
+    starter_id: AccountId,
+    starter_book_id: BookId,
+    partner_id: AccountId,
+    /// Set once the partner links their copy with `join_buddy_read`.
+    partner_book_id: Option<BookId>,
+    started_at: U64,
+    completed_at: Option<U64>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct BuddyReaderProgress {
+    account_id: AccountId,
+    book_id: Option<BookId>,
+    /// `None` until the copy is linked, or if it was deleted or made private.
+    status: Option<Status>,
+    /// Furthest bookmarked page.
+    page: Option<u64>,
+    pages: Option<u64>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct BuddyReadView {
+    buddy_read_id: BuddyReadId,
+    readers: [BuddyReaderProgress; 2],
+    started_at: U64,
+    completed_at: Option<U64>,
+}
+
+#[near_bindgen]
+impl Contract {
+    /// Invites `partner_account` to read along with one of the caller's books. The partner
+    /// links their own copy with `join_buddy_read`.
+    pub fn start_buddy_read(
+        &mut self,
+        book_id: BookId,
+        partner_account: ValidAccountId,
+    ) -> BuddyReadId {
+        self.assert_can_mutate();
+        let account_id = self.internal_caller_id();
+        let initial_storage = env::storage_usage();
+        let partner_id: AccountId = partner_account.into();
+        assert_ne!(account_id, partner_id, "Cannot buddy read with yourself");
+        self.assert_book_owner(&account_id, &book_id);
+        self.assert_not_buddy_read(&book_id);
+
+        let buddy_read_id = format!("{}", self.buddy_reads_len + 1);
+        self.buddy_reads.insert(
+            &buddy_read_id,
+            &BuddyRead {
+                starter_id: account_id.clone(),
+                starter_book_id: book_id.clone(),
+                partner_id,
+                partner_book_id: None,
+                started_at: env::block_timestamp().into(),
+                completed_at: None,
+            },
+        );
+        self.buddy_read_ids_by_book_id
+            .insert(&book_id, &buddy_read_id);
+        self.buddy_reads_len += 1;
+        self.internal_charge_storage(&account_id, initial_storage);
+
+        buddy_read_id
+    }
+
+    pub fn join_buddy_read(&mut self, buddy_read_id: BuddyReadId, book_id: BookId) {
+        self.assert_can_mutate();
+        let account_id = self.internal_caller_id();
+        let initial_storage = env::storage_usage();
+        let mut buddy_read = self
+            .buddy_reads
+            .get(&buddy_read_id)
+            .expect("Buddy read does not exist");
+        assert_eq!(
+            buddy_read.partner_id, account_id,
+            "Only the invited partner can join this buddy read"
+        );
+        assert!(
+            buddy_read.partner_book_id.is_none(),
+            "Buddy read already joined"
+        );
+        self.assert_book_owner(&account_id, &book_id);
+        self.assert_not_buddy_read(&book_id);
+
+        buddy_read.partner_book_id = Some(book_id.clone());
+        self.buddy_reads.insert(&buddy_read_id, &buddy_read);
+        self.buddy_read_ids_by_book_id
+            .insert(&book_id, &buddy_read_id);
+        self.internal_check_buddy_read(&book_id);
+        self.internal_charge_storage(&account_id, initial_storage);
+    }
+
+    /// Both readers' progress side by side.
+    pub fn get_buddy_read(&self, buddy_read_id: BuddyReadId) -> Option<BuddyReadView> {
+        self.buddy_reads.get(&buddy_read_id).map(|buddy_read| {
+            let starter = self
+                .internal_buddy_progress(buddy_read.starter_id, Some(buddy_read.starter_book_id));
+            let partner =
+                self.internal_buddy_progress(buddy_read.partner_id, buddy_read.partner_book_id);
+            BuddyReadView {
+                buddy_read_id,
+                readers: [starter, partner],
+                started_at: buddy_read.started_at,
+                completed_at: buddy_read.completed_at,
+            }
+        })
+    }
+}
+
+impl Contract {
+    fn assert_not_buddy_read(&self, book_id: &BookId) {
+        assert!(
+            self.buddy_read_ids_by_book_id.get(book_id).is_none(),
+            "Book is already in a buddy read"
+        );
+    }
+
+    fn internal_buddy_progress(
+        &self,
+        account_id: AccountId,
+        book_id: Option<BookId>,
+    ) -> BuddyReaderProgress {
+        let book = book_id
+            .as_ref()
+            .and_then(|book_id| self.internal_get_visible_book(book_id));
+        let page = match (&book, &book_id) {
+            (Some(_), Some(book_id)) => self
+                .bookmarks_by_book_id
+                .get(book_id)
+                .and_then(|bookmarks| bookmarks.iter().map(|bookmark| bookmark.page).max()),
+            _ => None,
+        };
+        BuddyReaderProgress {
+            account_id,
+            status: book.as_ref().map(|book| book.status),
+            pages: book.and_then(|book| book.pages),
+            book_id,
+            page,
+        }
+    }
+
+    /// Marks the buddy read `book_id` belongs to as complete, and logs `buddy_read_complete`,
+    /// once both copies are finished.
+    pub(crate) fn internal_check_buddy_read(&mut self, book_id: &BookId) {
+        let buddy_read_id = match self.buddy_read_ids_by_book_id.get(book_id) {
+            Some(buddy_read_id) => buddy_read_id,
+            None => return,
+        };
+        let mut buddy_read = self.buddy_reads.get(&buddy_read_id).unwrap();
+        let partner_book_id = match &buddy_read.partner_book_id {
+            Some(partner_book_id) if buddy_read.completed_at.is_none() => partner_book_id,
+            _ => return,
+        };
+        let finished = |book_id: &BookId| {
+            self.books
+                .get(book_id)
+                .is_some_and(|book| book.status == Status::Finished)
+        };
+        if !finished(&buddy_read.starter_book_id) || !finished(partner_book_id) {
+            return;
+        }
+
+        emit_buddy_read_event(BuddyReadData {
+            buddy_read_id: &buddy_read_id,
+            account_ids: [&buddy_read.starter_id, &buddy_read.partner_id],
+            book_ids: [&buddy_read.starter_book_id, partner_book_id],
+        });
+        buddy_read.completed_at = Some(env::block_timestamp().into());
+        self.buddy_reads.insert(&buddy_read_id, &buddy_read);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{get_context, get_contract, get_sample_book};
+    use near_sdk::test_utils::get_logs;
+    use near_sdk::testing_env;
+    use near_sdk::MockedBlockchain;
+    use std::convert::TryFrom;
+
+    #[test]
+    fn test_buddy_read() {
+        let mut context = get_context(vec![], false);
+        testing_env!(context.clone());
+        let mut contract = get_contract();
+        let carol_book_id = contract.add_book(get_sample_book());
+        let buddy_read_id = contract.start_buddy_read(
+            carol_book_id.clone(),
+            ValidAccountId::try_from("dave_near").unwrap(),
+        );
+        contract.add_bookmark(carol_book_id.clone(), "Chapter 3".into(), 120);
+
+        context.predecessor_account_id = "dave_near".to_string();
+        context.storage_usage = env::storage_usage();
+        testing_env!(context.clone());
+        let dave_book_id = contract.add_book(get_sample_book());
+        contract.join_buddy_read(buddy_read_id.clone(), dave_book_id.clone());
+        contract.update_book(dave_book_id, Status::Finished, None);
+
+        let view = contract.get_buddy_read(buddy_read_id.clone()).unwrap();
+        assert_eq!(view.readers[0].page, Some(120));
+        assert_eq!(view.readers[1].status, Some(Status::Finished));
+        assert!(view.completed_at.is_none());
+
+        context.predecessor_account_id = "carol_near".to_string();
+        context.storage_usage = env::storage_usage();
+        testing_env!(context);
+        contract.update_book(carol_book_id, Status::Finished, None);
+
+        assert!(contract
+            .get_buddy_read(buddy_read_id)
+            .unwrap()
+            .completed_at
+            .is_some());
+        assert!(get_logs()
+            .iter()
+            .any(|log| log.contains(r#""event":"buddy_read_complete""#)));
+    }
+
+    #[test]
+    #[should_panic(expected = "Only the invited partner can join this buddy read")]
+    fn test_join_buddy_read_not_partner() {
+        let mut context = get_context(vec![], false);
+        testing_env!(context.clone());
+        let mut contract = get_contract();
+        let book_id = contract.add_book(get_sample_book());
+        let buddy_read_id =
+            contract.start_buddy_read(book_id, ValidAccountId::try_from("dave_near").unwrap());
+
+        context.predecessor_account_id = "erin_near".to_string();
+        context.storage_usage = env::storage_usage();
+        testing_env!(context);
+        let erin_book_id = contract.add_book(get_sample_book());
+        contract.join_buddy_read(buddy_read_id, erin_book_id);
+    }
+}
@@ -0,0 +1,149 @@
+use crate::*;
+
+pub type CertificateId = String;
+
+/// Completion record attested by the issuing account, e.g. a teacher closing out a classroom
+/// program or the organizer of a reading challenge.
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct Certificate {
+    certificate_id: CertificateId,
+    issuer: AccountId,
+    recipient: AccountId,
+    program: String,
+    criteria_hash: String,
+    issued_at: U64,
+    revoked: bool,
+}
+
+#[near_bindgen]
+impl Contract {
+    pub fn issue_certificate(
+        &mut self,
+        recipient: ValidAccountId,
+        program: String,
+        criteria_hash: String,
+    ) -> CertificateId {
+        self.assert_can_mutate();
+        let issuer = self.internal_caller_id();
+        let initial_storage = env::storage_usage();
+        let certificate_id = format!("{}", self.certificates_len + 1);
+        let recipient: AccountId = recipient.into();
+
+        let certificate = Certificate {
+            certificate_id: certificate_id.clone(),
+            issuer: issuer.clone(),
+            recipient: recipient.clone(),
+            program,
+            criteria_hash,
+            issued_at: env::block_timestamp().into(),
+            revoked: false,
+        };
+        self.certificates.insert(&certificate_id, &certificate);
+
+        let mut certificate_ids = self
+            .certificates_by_recipient_id
+            .get(&recipient)
+            .unwrap_or_else(|| {
+                UnorderedSet::new(StorageKey::CertificatesPerRecipient {
+                    account_hash: env::sha256(recipient.as_bytes()),
+                })
+            });
+        certificate_ids.insert(&certificate_id);
+        self.certificates_by_recipient_id
+            .insert(&recipient, &certificate_ids);
+
+        self.certificates_len += 1;
+        self.internal_charge_storage(&issuer, initial_storage);
+
+        certificate_id
+    }
+
+    /// Revokes a certificate. Only the issuer may revoke; the record is kept and marked revoked.
+    #[payable]
+    pub fn revoke_certificate(&mut self, certificate_id: CertificateId) -> Certificate {
+        self.assert_can_mutate();
+        assert_one_yocto();
+        let mut certificate = self
+            .certificates
+            .get(&certificate_id)
+            .expect("Certificate does not exist");
+        assert_eq!(
+            certificate.issuer,
+            self.internal_caller_id(),
+            "Only the issuer can revoke a certificate"
+        );
+
+        certificate.revoked = true;
+        self.certificates.insert(&certificate_id, &certificate);
+
+        certificate
+    }
+
+    pub fn get_certificates(
+        &self,
+        account_id: ValidAccountId,
+        from_index: Option<u64>,
+        limit: Option<u64>,
+    ) -> Page<Certificate> {
+        match self.certificates_by_recipient_id.get(account_id.as_ref()) {
+            Some(certificate_ids) => Page::from_vector(
+                certificate_ids.as_vector(),
+                from_index,
+                limit,
+                |certificate_id| self.certificates.get(&certificate_id),
+            ),
+            None => Page::empty(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{get_context, get_contract};
+    use near_sdk::testing_env;
+    use near_sdk::MockedBlockchain;
+    use std::convert::TryFrom;
+
+    #[test]
+    fn test_issue_and_revoke_certificate() {
+        let mut context = get_context(vec![], false);
+        context.attached_deposit = 1;
+        testing_env!(context);
+        let mut contract = get_contract();
+        let recipient = ValidAccountId::try_from("dave_near").unwrap();
+
+        let certificate_id = contract.issue_certificate(
+            recipient.clone(),
+            "Grade 7 summer reading".to_string(),
+            "3xHxbZx".to_string(),
+        );
+        contract.revoke_certificate(certificate_id.clone());
+
+        let certificates = contract.get_certificates(recipient, None, None).items;
+        assert_eq!(certificates.len(), 1);
+        assert_eq!(certificates[0].certificate_id, certificate_id);
+        assert_eq!(certificates[0].issuer, "carol_near".to_string());
+        assert!(certificates[0].revoked);
+    }
+
+    #[test]
+    #[should_panic(expected = "Only the issuer can revoke a certificate")]
+    fn test_revoke_certificate_not_issuer() {
+        let mut context = get_context(vec![], false);
+        context.attached_deposit = 1;
+        testing_env!(context.clone());
+        let mut contract = get_contract();
+        let certificate_id = contract.issue_certificate(
+            ValidAccountId::try_from("dave_near").unwrap(),
+            "Grade 7 summer reading".to_string(),
+            "3xHxbZx".to_string(),
+        );
+
+        context.predecessor_account_id = "dave_near".to_string();
+        context.storage_usage = env::storage_usage();
+        testing_env!(context);
+        contract.revoke_certificate(certificate_id);
+    }
+}
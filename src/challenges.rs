@@ -0,0 +1,364 @@
+use crate::*;
+use near_sdk::json_types::U128;
+use near_sdk::{Balance, Promise};
+
+pub type ChallengeId = String;
+
+/// A group challenge, e.g. "12 books in 12 months", that participants stake NEAR to join.
+/// A book counts toward it when `update_book` moves it to Finished after the participant
+/// joined and before the deadline; books added as already finished never count.
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct Challenge {
+    name: String,
+    organizer_id: AccountId,
+    target_books: u64,
+    stake: Balance,
+    starts_at: u64,
+    deadline: u64,
+    participant_ids: UnorderedSet<AccountId>,
+    /// Set by `close_challenge`.
+    finisher_ids: Option<Vec<AccountId>>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct ChallengeView {
+    challenge_id: ChallengeId,
+    name: String,
+    organizer_id: AccountId,
+    target_books: u64,
+    stake: U128,
+    starts_at: U64,
+    deadline: U64,
+    participant_count: u64,
+    finisher_ids: Option<Vec<AccountId>>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct ChallengeParticipant {
+    account_id: AccountId,
+    books_finished: u64,
+}
+
+#[near_bindgen]
+impl Contract {
+    /// Starts a challenge now, running until `deadline`. Everyone who joins stakes `stake`.
+    pub fn create_challenge(
+        &mut self,
+        name: String,
+        target_books: u64,
+        stake: U128,
+        deadline: U64,
+    ) -> ChallengeId {
+        self.assert_can_mutate();
+        let organizer_id = self.internal_caller_id();
+        let initial_storage = env::storage_usage();
+        assert!(!name.is_empty(), "Challenge name cannot be empty");
+        let max_length = self.internal_config().max_title_length;
+        assert!(
+            name.len() <= max_length as usize,
+            "Challenge name exceeds {} characters",
+            max_length
+        );
+        assert!(target_books > 0, "Target must be at least one book");
+        assert!(stake.0 > 0, "Stake must be greater than zero");
+        let now = env::block_timestamp();
+        assert!(deadline.0 > now, "Deadline must be in the future");
+
+        let challenge_id = format!("{}", self.challenges_len + 1);
+        self.challenges.insert(
+            &challenge_id,
+            &Challenge {
+                name,
+                organizer_id: organizer_id.clone(),
+                target_books,
+                stake: stake.0,
+                starts_at: now,
+                deadline: deadline.0,
+                participant_ids: UnorderedSet::new(StorageKey::ParticipantsPerChallenge {
+                    challenge_hash: env::sha256(challenge_id.as_bytes()),
+                }),
+                finisher_ids: None,
+            },
+        );
+        self.challenges_len += 1;
+        self.challenge_ids_by_deadline
+            .insert(&(deadline.0, challenge_id.clone()), &());
+        self.internal_charge_storage(&organizer_id, initial_storage);
+
+        challenge_id
+    }
+
+    /// Joins a challenge before its deadline. The attached deposit must be exactly the stake.
+    #[payable]
+    pub fn join_challenge(&mut self, challenge_id: ChallengeId) {
+        self.assert_can_mutate();
+        let account_id = env::predecessor_account_id();
+        let initial_storage = env::storage_usage();
+        let mut challenge = self
+            .challenges
+            .get(&challenge_id)
+            .expect("Challenge does not exist");
+        assert!(
+            env::block_timestamp() < challenge.deadline,
+            "Challenge has ended"
+        );
+        assert_eq!(
+            env::attached_deposit(),
+            challenge.stake,
+            "Attach exactly the challenge stake"
+        );
+        assert!(
+            challenge.participant_ids.len() < self.internal_config().max_challenge_participants,
+            "Challenge is full"
+        );
+        assert!(
+            challenge.participant_ids.insert(&account_id),
+            "Already joined this challenge"
+        );
+        self.challenges.insert(&challenge_id, &challenge);
+
+        let now = env::block_timestamp();
+        let mut joined_ids = self
+            .challenge_ids_by_account_id
+            .get(&account_id)
+            .unwrap_or_default();
+        joined_ids.retain(|joined_id| {
+            self.challenges
+                .get(joined_id)
+                .is_some_and(|joined| now < joined.deadline)
+        });
+        assert!(
+            (joined_ids.len() as u64) < self.internal_config().max_joined_challenges,
+            "Too many open challenges"
+        );
+        joined_ids.push(challenge_id);
+        self.challenge_ids_by_account_id
+            .insert(&account_id, &joined_ids);
+        self.internal_charge_storage(&account_id, initial_storage);
+    }
+
+    /// Settles a challenge after its deadline; anyone can call it. Participants who finished
+    /// `target_books` in time split the whole pool, so each gets their stake back plus a share
+    /// of the non-finishers' stakes. If nobody finished, every stake is refunded.
+    pub fn close_challenge(&mut self, challenge_id: ChallengeId) -> Vec<AccountId> {
+        self.assert_can_mutate();
+        let challenge = self
+            .challenges
+            .get(&challenge_id)
+            .expect("Challenge does not exist");
+        assert!(
+            env::block_timestamp() >= challenge.deadline,
+            "Challenge has not ended yet"
+        );
+        assert!(
+            challenge.finisher_ids.is_none(),
+            "Challenge is already closed"
+        );
+        self.internal_close_challenge(challenge_id, challenge)
+    }
+
+    pub fn get_challenge(&self, challenge_id: ChallengeId) -> Option<ChallengeView> {
+        self.challenges
+            .get(&challenge_id)
+            .map(|challenge| ChallengeView {
+                challenge_id,
+                name: challenge.name,
+                organizer_id: challenge.organizer_id,
+                target_books: challenge.target_books,
+                stake: challenge.stake.into(),
+                starts_at: challenge.starts_at.into(),
+                deadline: challenge.deadline.into(),
+                participant_count: challenge.participant_ids.len(),
+                finisher_ids: challenge.finisher_ids,
+            })
+    }
+
+    /// Participants with the number of books that count toward the challenge so far.
+    pub fn get_challenge_participants(
+        &self,
+        challenge_id: ChallengeId,
+        from_index: Option<u64>,
+        limit: Option<u64>,
+    ) -> Page<ChallengeParticipant> {
+        match self.challenges.get(&challenge_id) {
+            Some(challenge) => Page::from_vector(
+                challenge.participant_ids.as_vector(),
+                from_index,
+                limit,
+                |account_id| {
+                    Some(ChallengeParticipant {
+                        books_finished: self
+                            .challenge_progress
+                            .get(&(challenge_id.clone(), account_id.clone()))
+                            .map_or(0, |book_ids| book_ids.len() as u64),
+                        account_id,
+                    })
+                },
+            ),
+            None => Page::empty(),
+        }
+    }
+}
+
+impl Contract {
+    /// Pays out an ended challenge and takes it off the deadline queue.
+    fn internal_close_challenge(
+        &mut self,
+        challenge_id: ChallengeId,
+        mut challenge: Challenge,
+    ) -> Vec<AccountId> {
+        let participant_ids = challenge.participant_ids.to_vec();
+        let mut finisher_ids = vec![];
+        for account_id in &participant_ids {
+            let initial_storage = env::storage_usage();
+            let key = (challenge_id.clone(), account_id.clone());
+            let books_finished = self
+                .challenge_progress
+                .remove(&key)
+                .map_or(0, |book_ids| book_ids.len() as u64);
+            self.internal_charge_storage(account_id, initial_storage);
+            if books_finished >= challenge.target_books {
+                finisher_ids.push(account_id.clone());
+            }
+        }
+
+        let (winner_ids, payout) = if finisher_ids.is_empty() {
+            (&participant_ids, challenge.stake)
+        } else {
+            let pool = challenge.stake * participant_ids.len() as u128;
+            (&finisher_ids, pool / finisher_ids.len() as u128)
+        };
+        for account_id in winner_ids {
+            Promise::new(account_id.clone()).transfer(payout);
+        }
+
+        let initial_storage = env::storage_usage();
+        self.challenge_ids_by_deadline
+            .remove(&(challenge.deadline, challenge_id.clone()));
+        self.internal_charge_storage(&challenge.organizer_id, initial_storage);
+
+        challenge.finisher_ids = Some(finisher_ids.clone());
+        self.challenges.insert(&challenge_id, &challenge);
+        finisher_ids
+    }
+
+    /// Closes up to `limit` ended challenges, earliest deadline first. Returns how many closed.
+    pub(crate) fn internal_close_due_challenges(&mut self, limit: u64) -> u64 {
+        let now = env::block_timestamp();
+        let mut closed = 0;
+        while closed < limit {
+            match self.challenge_ids_by_deadline.min() {
+                Some((deadline, challenge_id)) if deadline <= now => {
+                    let challenge = self.challenges.get(&challenge_id).unwrap();
+                    self.internal_close_challenge(challenge_id, challenge);
+                    closed += 1;
+                }
+                _ => break,
+            }
+        }
+        closed
+    }
+
+    /// Counts a book `update_book` just moved to Finished toward the owner's open challenges.
+    /// Each book counts once per challenge, and progress stops growing at the target.
+    pub(crate) fn internal_record_challenge_finish(
+        &mut self,
+        account_id: &AccountId,
+        book_id: &BookId,
+    ) {
+        let now = env::block_timestamp();
+        let joined_ids = match self.challenge_ids_by_account_id.get(account_id) {
+            Some(joined_ids) => joined_ids,
+            None => return,
+        };
+        for challenge_id in joined_ids {
+            let target_books = match self.challenges.get(&challenge_id) {
+                Some(challenge) if now < challenge.deadline => challenge.target_books,
+                _ => continue,
+            };
+            let key = (challenge_id, account_id.clone());
+            let mut book_ids = self.challenge_progress.get(&key).unwrap_or_default();
+            if (book_ids.len() as u64) < target_books && !book_ids.contains(book_id) {
+                book_ids.push(book_id.clone());
+                self.challenge_progress.insert(&key, &book_ids);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{get_context, get_contract, get_sample_book};
+    use near_sdk::testing_env;
+    use near_sdk::MockedBlockchain;
+
+    const STAKE: Balance = 1_000;
+
+    #[test]
+    fn test_close_challenge() {
+        let mut context = get_context(vec![], false);
+        context.account_balance = 10u128.pow(24);
+        testing_env!(context.clone());
+        let mut contract = get_contract();
+        let deadline = context.block_timestamp + 1_000;
+        let challenge_id =
+            contract.create_challenge("One book this week".into(), 1, U128(STAKE), U64(deadline));
+
+        context.attached_deposit = STAKE;
+        for account_id in ["carol_near", "dave_near"] {
+            context.predecessor_account_id = account_id.to_string();
+            context.storage_usage = env::storage_usage();
+            testing_env!(context.clone());
+            contract.join_challenge(challenge_id.clone());
+        }
+        // Books added as already finished do not count.
+        contract.add_book(Book {
+            status: Status::Finished,
+            ..get_sample_book()
+        });
+        let book_id = contract.add_book(get_sample_book());
+        contract.update_book(book_id.clone(), Status::Finished, None);
+        contract.update_book(book_id.clone(), Status::Read, None);
+        contract.update_book(book_id, Status::Finished, None);
+
+        context.attached_deposit = 0;
+        context.block_timestamp = deadline;
+        context.storage_usage = env::storage_usage();
+        testing_env!(context);
+        let participants = contract
+            .get_challenge_participants(challenge_id.clone(), None, None)
+            .items;
+        assert_eq!(participants[1].books_finished, 1);
+        assert_eq!(
+            contract.close_challenge(challenge_id.clone()),
+            vec!["dave_near".to_string()]
+        );
+        assert!(contract
+            .get_challenge(challenge_id)
+            .unwrap()
+            .finisher_ids
+            .is_some());
+    }
+
+    #[test]
+    #[should_panic(expected = "Attach exactly the challenge stake")]
+    fn test_join_challenge_wrong_stake() {
+        let mut context = get_context(vec![], false);
+        testing_env!(context.clone());
+        let mut contract = get_contract();
+        let challenge_id = contract.create_challenge(
+            "One book this week".into(),
+            1,
+            U128(STAKE),
+            U64(context.block_timestamp + 1_000),
+        );
+
+        context.attached_deposit = STAKE - 1;
+        context.storage_usage = env::storage_usage();
+        testing_env!(context);
+        contract.join_challenge(challenge_id);
+    }
+}
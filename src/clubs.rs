@@ -0,0 +1,218 @@
+use crate::*;
+
+pub type ClubId = String;
+
+const MAX_CLUB_HISTORY: usize = 50;
+
+/// One book on a club's schedule. `ends_at` is the date the club plans to finish it by.
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct ClubRead {
+    book_id: BookId,
+    started_at: U64,
+    ends_at: Option<U64>,
+}
+
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct Club {
+    name: String,
+    organizer_id: AccountId,
+    member_ids: UnorderedSet<AccountId>,
+    current_read: Option<ClubRead>,
+    /// Past reads, oldest first. Only the last `MAX_CLUB_HISTORY` are kept.
+    history: Vec<ClubRead>,
+    created_at: U64,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct ClubView {
+    club_id: ClubId,
+    name: String,
+    organizer_id: AccountId,
+    member_count: u64,
+    current_read: Option<ClubRead>,
+    history: Vec<ClubRead>,
+    created_at: U64,
+}
+
+#[near_bindgen]
+impl Contract {
+    /// Starts a club with the caller as organizer and first member.
+    pub fn create_club(&mut self, name: String) -> ClubId {
+        self.assert_can_mutate();
+        let organizer_id = self.internal_caller_id();
+        let initial_storage = env::storage_usage();
+        assert!(!name.is_empty(), "Club name cannot be empty");
+        let max_length = self.internal_config().max_title_length;
+        assert!(
+            name.len() <= max_length as usize,
+            "Club name exceeds {} characters",
+            max_length
+        );
+
+        let club_id = format!("{}", self.clubs_len + 1);
+        let mut member_ids = UnorderedSet::new(StorageKey::MembersPerClub {
+            club_hash: env::sha256(club_id.as_bytes()),
+        });
+        member_ids.insert(&organizer_id);
+        self.clubs.insert(
+            &club_id,
+            &Club {
+                name,
+                organizer_id: organizer_id.clone(),
+                member_ids,
+                current_read: None,
+                history: vec![],
+                created_at: env::block_timestamp().into(),
+            },
+        );
+        self.clubs_len += 1;
+        self.internal_charge_storage(&organizer_id, initial_storage);
+
+        club_id
+    }
+
+    pub fn join_club(&mut self, club_id: ClubId) {
+        self.assert_can_mutate();
+        let account_id = self.internal_caller_id();
+        let initial_storage = env::storage_usage();
+        let mut club = self.clubs.get(&club_id).expect("Club does not exist");
+        club.member_ids.insert(&account_id);
+        self.clubs.insert(&club_id, &club);
+        self.internal_charge_storage(&account_id, initial_storage);
+    }
+
+    pub fn leave_club(&mut self, club_id: ClubId) {
+        self.assert_can_mutate();
+        let account_id = self.internal_caller_id();
+        let initial_storage = env::storage_usage();
+        let mut club = self.clubs.get(&club_id).expect("Club does not exist");
+        assert_ne!(
+            club.organizer_id, account_id,
+            "The organizer cannot leave their club"
+        );
+        assert!(
+            club.member_ids.remove(&account_id),
+            "Not a member of this club"
+        );
+        self.clubs.insert(&club_id, &club);
+        self.internal_charge_storage(&account_id, initial_storage);
+    }
+
+    /// Moves the club on to a new book, to be finished by `ends_at`. The previous book goes
+    /// into the club's history.
+    pub fn set_current_book(
+        &mut self,
+        club_id: ClubId,
+        book_id: BookId,
+        ends_at: Option<U64>,
+    ) -> ClubRead {
+        self.assert_can_mutate();
+        let account_id = self.internal_caller_id();
+        let initial_storage = env::storage_usage();
+        let mut club = self.clubs.get(&club_id).expect("Club does not exist");
+        assert_eq!(
+            club.organizer_id, account_id,
+            "Only the organizer can set the current book"
+        );
+        assert!(
+            self.internal_get_visible_book(&book_id).is_some(),
+            "Book does not exist"
+        );
+        let now = env::block_timestamp();
+        if let Some(ends_at) = ends_at {
+            assert!(ends_at.0 > now, "End date must be in the future");
+        }
+
+        let read = ClubRead {
+            book_id,
+            started_at: now.into(),
+            ends_at,
+        };
+        if let Some(previous) = club.current_read.replace(read.clone()) {
+            club.history.push(previous);
+            if club.history.len() > MAX_CLUB_HISTORY {
+                club.history.remove(0);
+            }
+        }
+        self.clubs.insert(&club_id, &club);
+        self.internal_charge_storage(&account_id, initial_storage);
+
+        read
+    }
+
+    pub fn get_club(&self, club_id: ClubId) -> Option<ClubView> {
+        self.clubs.get(&club_id).map(|club| ClubView {
+            club_id,
+            name: club.name,
+            organizer_id: club.organizer_id,
+            member_count: club.member_ids.len(),
+            current_read: club.current_read,
+            history: club.history,
+            created_at: club.created_at,
+        })
+    }
+
+    pub fn get_club_members(
+        &self,
+        club_id: ClubId,
+        from_index: Option<u64>,
+        limit: Option<u64>,
+    ) -> Page<AccountId> {
+        match self.clubs.get(&club_id) {
+            Some(club) => Page::from_vector(club.member_ids.as_vector(), from_index, limit, Some),
+            None => Page::empty(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{get_context, get_contract, get_sample_book};
+    use near_sdk::testing_env;
+    use near_sdk::MockedBlockchain;
+
+    #[test]
+    fn test_club() {
+        let mut context = get_context(vec![], false);
+        testing_env!(context.clone());
+        let mut contract = get_contract();
+        let first_id = contract.add_book(get_sample_book());
+        let second_id = contract.add_book(get_sample_book());
+        let club_id = contract.create_club("Tuesday readers".into());
+        contract.set_current_book(club_id.clone(), first_id.clone(), None);
+        contract.set_current_book(club_id.clone(), second_id.clone(), None);
+
+        context.predecessor_account_id = "dave_near".to_string();
+        context.storage_usage = env::storage_usage();
+        testing_env!(context);
+        contract.join_club(club_id.clone());
+
+        let club = contract.get_club(club_id.clone()).unwrap();
+        assert_eq!(club.member_count, 2);
+        assert_eq!(club.current_read.unwrap().book_id, second_id);
+        assert_eq!(club.history[0].book_id, first_id);
+        assert_eq!(
+            contract.get_club_members(club_id, None, None).items,
+            vec!["carol_near".to_string(), "dave_near".to_string()]
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Only the organizer can set the current book")]
+    fn test_set_current_book_not_organizer() {
+        let mut context = get_context(vec![], false);
+        testing_env!(context.clone());
+        let mut contract = get_contract();
+        let book_id = contract.add_book(get_sample_book());
+        let club_id = contract.create_club("Tuesday readers".into());
+
+        context.predecessor_account_id = "dave_near".to_string();
+        context.storage_usage = env::storage_usage();
+        testing_env!(context);
+        contract.join_club(club_id.clone());
+        contract.set_current_book(club_id, book_id, None);
+    }
+}
@@ -0,0 +1,179 @@
+use crate::*;
+
+pub type CommentId = String;
+
+/// A comment on a public book. `reply_to` points at the comment it answers, so clients can
+/// rebuild threads from a flat page.
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct Comment {
+    comment_id: CommentId,
+    book_id: BookId,
+    author: AccountId,
+    text: String,
+    reply_to: Option<CommentId>,
+    created_at: U64,
+}
+
+#[near_bindgen]
+impl Contract {
+    /// Comments on a public book, or replies to another comment on the same book.
+    pub fn add_comment(
+        &mut self,
+        book_id: BookId,
+        text: String,
+        reply_to: Option<CommentId>,
+    ) -> CommentId {
+        self.assert_can_mutate();
+        let author = self.internal_caller_id();
+        let initial_storage = env::storage_usage();
+        assert!(
+            self.internal_get_listed_book(&book_id).is_some(),
+            "Book does not exist"
+        );
+        let max_length = self.internal_config().max_description_length;
+        assert!(
+            text.len() <= max_length as usize,
+            "Comment exceeds {} characters",
+            max_length
+        );
+        if let Some(parent_id) = &reply_to {
+            assert!(
+                self.comments
+                    .get(parent_id)
+                    .is_some_and(|parent| parent.book_id == book_id),
+                "Comment to reply to does not exist"
+            );
+        }
+
+        let comment_id = format!("{}", self.comments_len + 1);
+        self.comments.insert(
+            &comment_id,
+            &Comment {
+                comment_id: comment_id.clone(),
+                book_id: book_id.clone(),
+                author: author.clone(),
+                text,
+                reply_to,
+                created_at: env::block_timestamp().into(),
+            },
+        );
+
+        let mut comment_ids = self.comments_by_book_id.get(&book_id).unwrap_or_else(|| {
+            UnorderedSet::new(StorageKey::CommentsPerBook {
+                book_hash: env::sha256(book_id.as_bytes()),
+            })
+        });
+        comment_ids.insert(&comment_id);
+        self.comments_by_book_id.insert(&book_id, &comment_ids);
+
+        self.comments_len += 1;
+        self.internal_charge_storage(&author, initial_storage);
+
+        comment_id
+    }
+
+    /// Deletes a comment. Its author, the book's owner and moderators can delete it. Replies
+    /// are kept and still point at the deleted comment.
+    pub fn delete_comment(&mut self, comment_id: CommentId) {
+        self.assert_can_mutate();
+        let caller_id = env::predecessor_account_id();
+        let initial_storage = env::storage_usage();
+        let comment = self
+            .comments
+            .get(&comment_id)
+            .expect("Comment does not exist");
+        let book_owner_id = self
+            .books
+            .get(&comment.book_id)
+            .and_then(|book| book.account_id);
+        assert!(
+            caller_id == comment.author
+                || book_owner_id.as_ref() == Some(&caller_id)
+                || caller_id == self.owner_id
+                || self.internal_has_role(&caller_id, Role::Moderator),
+            "Only the author, the book owner or a moderator can delete a comment"
+        );
+
+        self.comments.remove(&comment_id);
+        if let Some(mut comment_ids) = self.comments_by_book_id.get(&comment.book_id) {
+            comment_ids.remove(&comment_id);
+            if comment_ids.is_empty() {
+                self.comments_by_book_id.remove(&comment.book_id);
+            } else {
+                self.comments_by_book_id
+                    .insert(&comment.book_id, &comment_ids);
+            }
+        }
+        self.internal_charge_storage(&comment.author, initial_storage);
+    }
+
+    pub fn get_comments(
+        &self,
+        book_id: BookId,
+        from_index: Option<u64>,
+        limit: Option<u64>,
+    ) -> Page<Comment> {
+        match self.comments_by_book_id.get(&book_id) {
+            Some(comment_ids) => {
+                Page::from_vector(comment_ids.as_vector(), from_index, limit, |comment_id| {
+                    self.comments.get(&comment_id)
+                })
+            }
+            None => Page::empty(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{get_context, get_contract, get_sample_book};
+    use near_sdk::testing_env;
+    use near_sdk::MockedBlockchain;
+
+    #[test]
+    fn test_comments() {
+        let mut context = get_context(vec![], false);
+        testing_env!(context.clone());
+        let mut contract = get_contract();
+        let book_id = contract.add_book(get_sample_book());
+
+        context.predecessor_account_id = "dave_near".to_string();
+        context.storage_usage = env::storage_usage();
+        testing_env!(context.clone());
+        let comment_id = contract.add_comment(book_id.clone(), "Worth it?".into(), None);
+
+        context.predecessor_account_id = "erin_near".to_string();
+        context.storage_usage = env::storage_usage();
+        testing_env!(context.clone());
+        contract.add_comment(book_id.clone(), "Yes".into(), Some(comment_id.clone()));
+
+        let comments = contract.get_comments(book_id.clone(), None, None).items;
+        assert_eq!(comments.len(), 2);
+        assert_eq!(comments[1].reply_to, Some(comment_id.clone()));
+
+        context.predecessor_account_id = "carol_near".to_string();
+        context.storage_usage = env::storage_usage();
+        testing_env!(context);
+        contract.delete_comment(comment_id);
+        assert_eq!(contract.get_comments(book_id, None, None).total, 1);
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Only the author, the book owner or a moderator can delete a comment"
+    )]
+    fn test_delete_comment_not_allowed() {
+        let mut context = get_context(vec![], false);
+        testing_env!(context.clone());
+        let mut contract = get_contract();
+        let book_id = contract.add_book(get_sample_book());
+        let comment_id = contract.add_comment(book_id, "First".into(), None);
+
+        context.predecessor_account_id = "dave_near".to_string();
+        context.storage_usage = env::storage_usage();
+        testing_env!(context);
+        contract.delete_comment(comment_id);
+    }
+}
@@ -0,0 +1,114 @@
+use crate::duplicates::normalize_isbn;
+use crate::*;
+
+/// Running rating totals for one work across every library that owns it.
+#[derive(BorshDeserialize, BorshSerialize, Default)]
+pub struct RatingCounters {
+    rating_sum: u64,
+    rating_count: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct CommunityRating {
+    catalog_id: String,
+    average_rating: Option<f64>,
+    rating_count: u64,
+}
+
+/// Books are matched to a work by ISBN, ignoring hyphens, spaces and case.
+fn catalog_id_of(book: &Book) -> Option<String> {
+    book.isbn
+        .as_deref()
+        .map(normalize_isbn)
+        .filter(|isbn| !isbn.is_empty())
+}
+
+#[near_bindgen]
+impl Contract {
+    /// Average of the ratings every owner gave a work, looked up by its ISBN.
+    pub fn get_community_rating(&self, catalog_id: String) -> CommunityRating {
+        let catalog_id = normalize_isbn(&catalog_id);
+        let counters = self
+            .ratings_by_catalog_id
+            .get(&catalog_id)
+            .unwrap_or_default();
+        CommunityRating {
+            catalog_id,
+            average_rating: average_rating(counters.rating_sum, counters.rating_count),
+            rating_count: counters.rating_count,
+        }
+    }
+}
+
+impl Contract {
+    pub(crate) fn internal_add_community_rating(&mut self, book: &Book) {
+        if let (Some(catalog_id), Some(rating)) = (catalog_id_of(book), book.rating) {
+            let mut counters = self
+                .ratings_by_catalog_id
+                .get(&catalog_id)
+                .unwrap_or_default();
+            counters.rating_sum += rating as u64;
+            counters.rating_count += 1;
+            self.ratings_by_catalog_id.insert(&catalog_id, &counters);
+        }
+    }
+
+    pub(crate) fn internal_remove_community_rating(&mut self, book: &Book) {
+        if let (Some(catalog_id), Some(rating)) = (catalog_id_of(book), book.rating) {
+            if let Some(mut counters) = self.ratings_by_catalog_id.get(&catalog_id) {
+                if counters.rating_count <= 1 {
+                    self.ratings_by_catalog_id.remove(&catalog_id);
+                } else {
+                    counters.rating_sum -= rating as u64;
+                    counters.rating_count -= 1;
+                    self.ratings_by_catalog_id.insert(&catalog_id, &counters);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{get_context, get_contract, get_sample_book};
+    use near_sdk::testing_env;
+    use near_sdk::MockedBlockchain;
+
+    #[test]
+    fn test_community_rating() {
+        let mut context = get_context(vec![], false);
+        testing_env!(context.clone());
+        let mut contract = get_contract();
+        let book_id = contract.add_book(Book {
+            isbn: Some("978-0-13-110362-7".to_string()),
+            rating: Some(4),
+            ..get_sample_book()
+        });
+
+        context.predecessor_account_id = "dave_near".to_string();
+        context.storage_usage = env::storage_usage();
+        testing_env!(context.clone());
+        contract.add_book(Book {
+            isbn: Some("9780131103627".to_string()),
+            ..get_sample_book()
+        });
+        let dave_book_id = contract.add_book(Book {
+            isbn: Some("9780131103627".to_string()),
+            ..get_sample_book()
+        });
+        contract.rate_book(dave_book_id, 1, None);
+
+        let rating = contract.get_community_rating("978-0131103627".to_string());
+        assert_eq!(rating.rating_count, 2);
+        assert_eq!(rating.average_rating, Some(2.5));
+
+        context.predecessor_account_id = "carol_near".to_string();
+        context.storage_usage = env::storage_usage();
+        testing_env!(context);
+        contract.rate_book(book_id, 2, None);
+        let rating = contract.get_community_rating("9780131103627".to_string());
+        assert_eq!(rating.average_rating, Some(1.5));
+    }
+}
@@ -0,0 +1,242 @@
+use crate::duplicates::{normalize_isbn, normalize_title};
+use crate::*;
+use std::collections::HashSet;
+
+/// One of `account_a`'s books and, if `account_b` has it too, their copy.
+#[derive(Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct BookMatch {
+    pub book: Book,
+    pub matching_book: Option<Book>,
+}
+
+/// Finished books read per account when comparing readers, so each candidate costs a bounded
+/// amount of gas.
+const MAX_PROFILE_BOOKS: usize = 200;
+
+#[derive(Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct SimilarReader {
+    pub account_id: AccountId,
+    pub shared_books: u64,
+    pub shared_tags: u64,
+    pub score: u64,
+}
+
+/// Two books match when both have the same ISBN or, failing that, the same normalized title.
+/// `compare_libraries` only considers books with the same title ignoring case.
+struct MatchKey {
+    title: String,
+    isbn: Option<String>,
+}
+
+impl MatchKey {
+    fn of(book: &Book) -> Self {
+        Self {
+            title: normalize_title(&book.title),
+            isbn: book.isbn.as_deref().map(normalize_isbn),
+        }
+    }
+
+    fn matches(&self, other: &MatchKey) -> bool {
+        match (&self.isbn, &other.isbn) {
+            (Some(isbn), Some(other_isbn)) => isbn == other_isbn,
+            _ => self.title == other.title,
+        }
+    }
+}
+
+#[near_bindgen]
+impl Contract {
+    /// Pages through `account_a`'s books, pairing each with `account_b`'s copy if they have
+    /// one. Candidates are looked up through `account_b`'s title index, so each page costs the
+    /// same however large either library is. Swap the accounts to list `account_b`'s books.
+    pub fn compare_libraries(
+        &self,
+        account_a: ValidAccountId,
+        account_b: ValidAccountId,
+        from_index: Option<u64>,
+        limit: Option<u64>,
+    ) -> Page<BookMatch> {
+        match self.books_by_owner_id.get(account_a.as_ref()) {
+            Some(book_ids) => {
+                Page::from_vector(book_ids.as_vector(), from_index, limit, |book_id| {
+                    let book = self.internal_get_listed_book(&book_id)?;
+                    let key = MatchKey::of(&book);
+                    let matching_book = self
+                        .internal_books_titled(account_b.as_ref(), &book.title)
+                        .into_iter()
+                        .filter_map(|other_id| self.internal_get_listed_book(&other_id))
+                        .find(|other| key.matches(&MatchKey::of(other)));
+                    Some(BookMatch {
+                        book,
+                        matching_book,
+                    })
+                })
+            }
+            None => Page::empty(),
+        }
+    }
+
+    /// Scores one page of other readers by how many finished books and tags on finished books
+    /// they share with `account_id`, best match first. A shared book counts twice as much as a
+    /// shared tag. `from_index` and `limit` page through candidate accounts, so follow
+    /// `next_cursor` to scan more of them; readers with nothing in common are left out.
+    pub fn find_similar_readers(
+        &self,
+        account_id: ValidAccountId,
+        from_index: Option<u64>,
+        limit: Option<u64>,
+    ) -> Page<SimilarReader> {
+        let account_id: AccountId = account_id.into();
+        let (keys, tags) = self.internal_finished_profile(&account_id);
+        if keys.is_empty() {
+            return Page::empty();
+        }
+
+        let mut page = Page::from_vector(
+            self.books_by_owner_id.keys_as_vector(),
+            from_index,
+            limit,
+            |other_id| {
+                if other_id == account_id {
+                    return None;
+                }
+                let (other_keys, other_tags) = self.internal_finished_profile(&other_id);
+                let shared_books = keys
+                    .iter()
+                    .filter(|key| other_keys.iter().any(|other| key.matches(other)))
+                    .count() as u64;
+                let shared_tags = tags.intersection(&other_tags).count() as u64;
+                let score = shared_books * 2 + shared_tags;
+                if score == 0 {
+                    return None;
+                }
+                Some(SimilarReader {
+                    account_id: other_id,
+                    shared_books,
+                    shared_tags,
+                    score,
+                })
+            },
+        );
+        page.items.sort_by_key(|reader| cmp::Reverse(reader.score));
+        page
+    }
+}
+
+impl Contract {
+    /// Match keys and lowercase tags of up to `MAX_PROFILE_BOOKS` of an account's finished
+    /// books.
+    fn internal_finished_profile(
+        &self,
+        account_id: &AccountId,
+    ) -> (Vec<MatchKey>, HashSet<String>) {
+        let books: Vec<Book> = self
+            .books_by_owner_status
+            .get(&(account_id.clone(), Status::Finished))
+            .map(|book_ids| {
+                book_ids
+                    .iter()
+                    .take(MAX_PROFILE_BOOKS)
+                    .filter_map(|book_id| self.internal_get_listed_book(&book_id))
+                    .collect()
+            })
+            .unwrap_or_default();
+        let tags = books
+            .iter()
+            .flat_map(|book| book.tags.iter().map(|tag| tag.to_lowercase()))
+            .collect();
+        (books.iter().map(MatchKey::of).collect(), tags)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{get_context, get_contract, get_sample_book};
+    use near_sdk::testing_env;
+    use near_sdk::MockedBlockchain;
+    use std::convert::TryFrom;
+
+    #[test]
+    fn test_compare_libraries() {
+        let mut context = get_context(vec![], false);
+        testing_env!(context.clone());
+        let mut contract = get_contract();
+        let shared_id = contract.add_book(get_sample_book());
+        let isbn_id = contract.add_book(Book {
+            title: "The Hobbit".to_string(),
+            isbn: Some("978-0-261-10221-7".to_string()),
+            ..get_sample_book()
+        });
+
+        context.predecessor_account_id = "dave_near".to_string();
+        context.storage_usage = env::storage_usage();
+        testing_env!(context);
+        contract.add_book(Book {
+            title: "motorcycle mechanics 101".to_string(),
+            ..get_sample_book()
+        });
+        contract.add_book(Book {
+            title: "The Hobbit".to_string(),
+            isbn: Some("0261102214".to_string()),
+            ..get_sample_book()
+        });
+
+        let matches = contract
+            .compare_libraries(
+                ValidAccountId::try_from("carol_near").unwrap(),
+                ValidAccountId::try_from("dave_near").unwrap(),
+                None,
+                None,
+            )
+            .items;
+        assert_eq!(matches.len(), 2);
+        assert_eq!(matches[0].book.book_id, Some(shared_id));
+        assert!(matches[0].matching_book.is_some());
+        assert_eq!(matches[1].book.book_id, Some(isbn_id));
+        assert!(matches[1].matching_book.is_none());
+    }
+
+    #[test]
+    fn test_find_similar_readers() {
+        let mut context = get_context(vec![], false);
+        testing_env!(context.clone());
+        let mut contract = get_contract();
+        let finished = |title: &str, tag: &str| Book {
+            title: title.to_string(),
+            status: Status::Finished,
+            tags: vec![tag.to_string()],
+            ..get_sample_book()
+        };
+        contract.add_book(finished("Dune", "sci-fi"));
+        contract.add_book(finished("Emma", "classics"));
+
+        for (account_id, books) in [
+            (
+                "dave_near",
+                vec![finished("Dune", "sci-fi"), finished("Emma", "classics")],
+            ),
+            ("erin_near", vec![finished("Hyperion", "Sci-Fi")]),
+            ("frank_near", vec![finished("Ulysses", "modernism")]),
+        ] {
+            context.predecessor_account_id = account_id.to_string();
+            context.storage_usage = env::storage_usage();
+            testing_env!(context.clone());
+            for book in books {
+                contract.add_book(book);
+            }
+        }
+
+        let readers = contract
+            .find_similar_readers(ValidAccountId::try_from("carol_near").unwrap(), None, None)
+            .items;
+        assert_eq!(readers.len(), 2);
+        assert_eq!(readers[0].account_id, "dave_near");
+        assert_eq!(readers[0].shared_books, 2);
+        assert_eq!(readers[0].score, 6);
+        assert_eq!(readers[1].account_id, "erin_near");
+        assert_eq!(readers[1].score, 1);
+    }
+}
@@ -0,0 +1,338 @@
+use crate::*;
+
+const MAX_FEE_BPS: u32 = 10_000;
+const MINUTE: u64 = 60 * 1_000_000_000;
+
+/// Contract-wide settings, given to `new` and changed through `update_config`, which the
+/// owner-only setters call with a one-field patch. Fields left out of the JSON take their
+/// default.
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone)]
+#[serde(crate = "near_sdk::serde", default)]
+pub struct Config {
+    /// Share of each tip the contract keeps, in basis points.
+    pub tip_fee_bps: u32,
+    /// Cap on mutating calls per account; `None` for no cap.
+    pub rate_limit: Option<RateLimit>,
+    /// Cap on books in each library; `None` for no cap.
+    pub max_books_per_account: Option<u64>,
+    /// Reward for finishing a book; `None` for no reward.
+    pub finish_reward: Option<FinishReward>,
+    pub max_title_length: u32,
+    pub max_description_length: u32,
+    pub max_image_uri_length: u32,
+    /// Reports that hide a book or review from public views; `None` to never hide.
+    pub report_hide_threshold: Option<u64>,
+    /// Receives the stakes on missed reading goals; `None` keeps them in the contract.
+    pub goal_stake_beneficiary: Option<AccountId>,
+    /// Gas a paginated view may burn before it stops and hands back a cursor.
+    pub view_gas_budget: Gas,
+    /// How long a deletion or transfer can be undone, in nanoseconds.
+    pub undo_window: U64,
+    /// How long a guardian recovery waits before it can finish, in nanoseconds.
+    pub recovery_timelock: U64,
+    /// Bytes the minimum storage deposit has to cover.
+    pub min_storage_bytes: u64,
+    /// Participants per challenge. Keep it low enough for `close_challenge` to pay them all
+    /// out in one call.
+    pub max_challenge_participants: u64,
+    /// Open challenges an account can be in at once, which bounds the work of finishing a book.
+    pub max_joined_challenges: u64,
+    /// Cap on the royalties of one book, in basis points.
+    pub max_total_royalty_bps: u32,
+    /// Contract that mints milestone badges; `None` for no badges.
+    pub badge_contract: Option<BadgeContract>,
+    /// Agent that runs `finalize_period`; `None` for no agent.
+    pub cron_config: Option<CronConfig>,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            tip_fee_bps: 0,
+            rate_limit: None,
+            max_books_per_account: None,
+            finish_reward: None,
+            max_title_length: 256,
+            max_description_length: 4096,
+            max_image_uri_length: 512,
+            report_hide_threshold: None,
+            goal_stake_beneficiary: None,
+            view_gas_budget: DEFAULT_VIEW_GAS_BUDGET,
+            undo_window: U64(10 * MINUTE),
+            recovery_timelock: U64(3 * 24 * 60 * MINUTE),
+            min_storage_bytes: 2_000,
+            max_challenge_participants: 100,
+            max_joined_challenges: 10,
+            max_total_royalty_bps: 5_000,
+            badge_contract: None,
+            cron_config: None,
+        }
+    }
+}
+
+impl Config {
+    pub(crate) fn assert_valid(&self) {
+        assert!(
+            self.tip_fee_bps <= MAX_FEE_BPS,
+            "Fee cannot exceed {} basis points",
+            MAX_FEE_BPS
+        );
+        if let Some(rate_limit) = &self.rate_limit {
+            assert!(
+                rate_limit.max_writes > 0 && rate_limit.window_blocks > 0,
+                "Rate limit must allow at least one write per window"
+            );
+        }
+        assert!(
+            self.max_books_per_account != Some(0),
+            "Book quota must allow at least one book"
+        );
+        assert!(
+            self.report_hide_threshold != Some(0),
+            "Report threshold must be at least one report"
+        );
+        assert!(
+            self.max_title_length > 0
+                && self.max_description_length > 0
+                && self.max_image_uri_length > 0,
+            "Field lengths must be positive"
+        );
+        assert!(self.view_gas_budget > 0, "View gas budget must be positive");
+        assert!(
+            self.max_challenge_participants > 0 && self.max_joined_challenges > 0,
+            "Challenge limits must be positive"
+        );
+        assert!(
+            self.max_total_royalty_bps <= MAX_FEE_BPS,
+            "Royalty cap cannot exceed {} basis points",
+            MAX_FEE_BPS
+        );
+        if let Some(cron_config) = &self.cron_config {
+            assert!(cron_config.period.0 > 0, "Period must be positive");
+        }
+    }
+}
+
+/// Changes to apply with `update_config`. Fields left out are kept; for the optional settings
+/// `null` turns the setting off.
+#[derive(Serialize, Deserialize, Default)]
+#[serde(crate = "near_sdk::serde", default)]
+pub struct ConfigPatch {
+    pub tip_fee_bps: Option<u32>,
+    #[serde(
+        deserialize_with = "deserialize_some",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub rate_limit: Option<Option<RateLimit>>,
+    #[serde(
+        deserialize_with = "deserialize_some",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub max_books_per_account: Option<Option<u64>>,
+    #[serde(
+        deserialize_with = "deserialize_some",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub finish_reward: Option<Option<FinishReward>>,
+    pub max_title_length: Option<u32>,
+    pub max_description_length: Option<u32>,
+    pub max_image_uri_length: Option<u32>,
+    #[serde(
+        deserialize_with = "deserialize_some",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub report_hide_threshold: Option<Option<u64>>,
+    #[serde(
+        deserialize_with = "deserialize_some",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub goal_stake_beneficiary: Option<Option<AccountId>>,
+    pub view_gas_budget: Option<Gas>,
+    pub undo_window: Option<U64>,
+    pub recovery_timelock: Option<U64>,
+    pub min_storage_bytes: Option<u64>,
+    pub max_challenge_participants: Option<u64>,
+    pub max_joined_challenges: Option<u64>,
+    pub max_total_royalty_bps: Option<u32>,
+    #[serde(
+        deserialize_with = "deserialize_some",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub badge_contract: Option<Option<BadgeContract>>,
+    #[serde(
+        deserialize_with = "deserialize_some",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub cron_config: Option<Option<CronConfig>>,
+}
+
+/// Tells a field set to `null` apart from a field left out, which `default` makes `None`.
+fn deserialize_some<'de, T, D>(deserializer: D) -> Result<Option<T>, D::Error>
+where
+    T: Deserialize<'de>,
+    D: near_sdk::serde::Deserializer<'de>,
+{
+    T::deserialize(deserializer).map(Some)
+}
+
+impl Config {
+    pub(crate) fn apply(&mut self, patch: ConfigPatch) {
+        if let Some(tip_fee_bps) = patch.tip_fee_bps {
+            self.tip_fee_bps = tip_fee_bps;
+        }
+        if let Some(rate_limit) = patch.rate_limit {
+            self.rate_limit = rate_limit;
+        }
+        if let Some(max_books_per_account) = patch.max_books_per_account {
+            self.max_books_per_account = max_books_per_account;
+        }
+        if let Some(finish_reward) = patch.finish_reward {
+            self.finish_reward = finish_reward;
+        }
+        if let Some(max_title_length) = patch.max_title_length {
+            self.max_title_length = max_title_length;
+        }
+        if let Some(max_description_length) = patch.max_description_length {
+            self.max_description_length = max_description_length;
+        }
+        if let Some(max_image_uri_length) = patch.max_image_uri_length {
+            self.max_image_uri_length = max_image_uri_length;
+        }
+        if let Some(report_hide_threshold) = patch.report_hide_threshold {
+            self.report_hide_threshold = report_hide_threshold;
+        }
+        if let Some(goal_stake_beneficiary) = patch.goal_stake_beneficiary {
+            self.goal_stake_beneficiary = goal_stake_beneficiary;
+        }
+        if let Some(view_gas_budget) = patch.view_gas_budget {
+            self.view_gas_budget = view_gas_budget;
+        }
+        if let Some(undo_window) = patch.undo_window {
+            self.undo_window = undo_window;
+        }
+        if let Some(recovery_timelock) = patch.recovery_timelock {
+            self.recovery_timelock = recovery_timelock;
+        }
+        if let Some(min_storage_bytes) = patch.min_storage_bytes {
+            self.min_storage_bytes = min_storage_bytes;
+        }
+        if let Some(max_challenge_participants) = patch.max_challenge_participants {
+            self.max_challenge_participants = max_challenge_participants;
+        }
+        if let Some(max_joined_challenges) = patch.max_joined_challenges {
+            self.max_joined_challenges = max_joined_challenges;
+        }
+        if let Some(max_total_royalty_bps) = patch.max_total_royalty_bps {
+            self.max_total_royalty_bps = max_total_royalty_bps;
+        }
+        if let Some(badge_contract) = patch.badge_contract {
+            self.badge_contract = badge_contract;
+        }
+        if let Some(cron_config) = patch.cron_config {
+            self.cron_config = cron_config;
+        }
+    }
+}
+
+#[near_bindgen]
+impl Contract {
+    /// Hands ownership to another account, e.g. a DAO.
+    pub fn set_owner(&mut self, owner_id: ValidAccountId) {
+        self.assert_owner();
+        self.owner_id = owner_id.into();
+    }
+
+    pub fn get_owner(&self) -> AccountId {
+        self.owner_id.clone()
+    }
+
+    pub fn get_config(&self) -> Config {
+        self.internal_config()
+    }
+
+    pub fn update_config(&mut self, patch: ConfigPatch) -> Config {
+        self.assert_owner();
+        let mut config = self.internal_config();
+        config.apply(patch);
+        self.internal_set_config(&config);
+        config
+    }
+}
+
+impl Contract {
+    pub(crate) fn internal_config(&self) -> Config {
+        self.config.get().unwrap()
+    }
+
+    pub(crate) fn internal_set_config(&mut self, config: &Config) {
+        config.assert_valid();
+        self.config.set(config);
+    }
+
+    /// Owner-only methods also accept the contract itself, which is how approved proposals
+    /// call them. With a council set, that is the only way in.
+    pub(crate) fn assert_owner(&self) {
+        let predecessor_id = env::predecessor_account_id();
+        if predecessor_id == env::current_account_id() {
+            return;
+        }
+        assert!(
+            self.council.is_none(),
+            "Owner actions must go through an approved proposal"
+        );
+        assert_eq!(
+            predecessor_id, self.owner_id,
+            "Only the owner can call this method"
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{get_context, get_new_contract};
+    use near_sdk::testing_env;
+    use near_sdk::MockedBlockchain;
+    use std::convert::TryFrom;
+
+    #[test]
+    fn test_new() {
+        let context = get_context(vec![], false);
+        testing_env!(context);
+        let contract = get_new_contract();
+        assert_eq!(contract.get_owner(), "alice_near");
+        assert_eq!(contract.get_config().tip_fee_bps, 0);
+    }
+
+    #[test]
+    fn test_update_config() {
+        let mut context = get_context(vec![], false);
+        context.predecessor_account_id = "alice_near".to_string();
+        testing_env!(context);
+        let mut contract = get_new_contract();
+        contract.set_max_books_per_account(Some(100));
+
+        let patch: ConfigPatch = near_sdk::serde_json::from_str(
+            r#"{"tip_fee_bps": 250, "max_books_per_account": null}"#,
+        )
+        .unwrap();
+        let config = contract.update_config(patch);
+        assert_eq!(config.tip_fee_bps, 250);
+        assert_eq!(config.max_books_per_account, None);
+        assert_eq!(config.max_title_length, 256);
+    }
+
+    #[test]
+    #[should_panic(expected = "Fee cannot exceed 10000 basis points")]
+    fn test_new_invalid_config() {
+        let context = get_context(vec![], false);
+        testing_env!(context);
+        Contract::new(
+            ValidAccountId::try_from("alice_near").unwrap(),
+            Config {
+                tip_fee_bps: 10_001,
+                ..Default::default()
+            },
+        );
+    }
+}
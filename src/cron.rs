@@ -0,0 +1,118 @@
+use crate::*;
+
+/// Account allowed to run `finalize_period`, e.g. a croncat agent, and the period length in
+/// nanoseconds.
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct CronConfig {
+    pub agent_id: AccountId,
+    pub period: U64,
+}
+
+#[near_bindgen]
+impl Contract {
+    /// Sets or, with `None`, removes the scheduled finalization agent.
+    pub fn set_cron_config(&mut self, cron_config: Option<CronConfig>) {
+        self.update_config(ConfigPatch {
+            cron_config: Some(cron_config),
+            ..Default::default()
+        });
+    }
+
+    pub fn get_cron_config(&self) -> Option<CronConfig> {
+        self.internal_config().cron_config
+    }
+
+    /// Closes out the periods that ended since the last run: finalizes up to `limit` expired
+    /// deletions, closes up to `limit` ended challenges and settles up to `limit` goal stakes
+    /// whose year is over. Each step resumes where the previous run stopped. Only the
+    /// configured agent may call it, at most once per period. Returns the index of the period
+    /// that is now current.
+    pub fn finalize_period(&mut self, limit: Option<u64>) -> u64 {
+        self.assert_can_mutate();
+        let cron_config = self
+            .internal_config()
+            .cron_config
+            .expect("No cron agent configured");
+        assert_eq!(
+            env::predecessor_account_id(),
+            cron_config.agent_id,
+            "Only the cron agent can finalize a period"
+        );
+        let period = env::block_timestamp() / cron_config.period.0;
+        assert!(
+            self.last_finalized_period
+                .is_none_or(|last_period| last_period < period),
+            "Period is already finalized"
+        );
+
+        self.finalize_actions(limit);
+        let limit = limit.unwrap_or(u64::MAX);
+        self.internal_close_due_challenges(limit);
+        self.internal_settle_due_goal_stakes(limit);
+        self.last_finalized_period = Some(period);
+        period
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{get_context, get_contract};
+    use near_sdk::json_types::U128;
+    use near_sdk::testing_env;
+    use near_sdk::MockedBlockchain;
+    use std::convert::TryFrom;
+
+    #[test]
+    #[should_panic(expected = "Period is already finalized")]
+    fn test_finalize_period_once_per_period() {
+        let mut context = get_context(vec![], false);
+        context.predecessor_account_id = "cron_near".to_string();
+        context.block_timestamp = 250;
+        testing_env!(context);
+        let mut contract = get_contract();
+        let mut config = contract.get_config();
+        config.cron_config = Some(CronConfig {
+            agent_id: "cron_near".to_string(),
+            period: U64(100),
+        });
+        contract.internal_set_config(&config);
+
+        assert_eq!(contract.finalize_period(None), 2);
+        contract.finalize_period(None);
+    }
+
+    #[test]
+    fn test_finalize_period_settles_challenges_and_stakes() {
+        let mut context = get_context(vec![], false);
+        context.account_balance = 10u128.pow(24);
+        context.attached_deposit = 1_000;
+        testing_env!(context.clone());
+        let mut contract = get_contract();
+        let mut config = contract.get_config();
+        config.cron_config = Some(CronConfig {
+            agent_id: "cron_near".to_string(),
+            period: U64(100),
+        });
+        config.goal_stake_beneficiary = Some("charity_near".to_string());
+        contract.internal_set_config(&config);
+        let year = year_of(env::block_timestamp());
+        contract.stake_on_goal(year, 1);
+        let deadline = context.block_timestamp + 1_000;
+        let challenge_id =
+            contract.create_challenge("One book".into(), 1, U128(1_000), U64(deadline));
+        contract.join_challenge(challenge_id.clone());
+
+        context.predecessor_account_id = "cron_near".to_string();
+        context.attached_deposit = 0;
+        context.block_timestamp += 366 * 24 * 60 * 60 * 1_000_000_000;
+        context.storage_usage = env::storage_usage();
+        testing_env!(context);
+        contract.finalize_period(None);
+
+        let carol = ValidAccountId::try_from("carol_near").unwrap();
+        assert!(contract.get_goal_stake(carol, year).is_none());
+        assert_eq!(contract.challenge_ids_by_deadline.len(), 0);
+    }
+}
@@ -0,0 +1,141 @@
+use crate::*;
+
+const MAX_GRADE: u8 = 12;
+
+/// Reading level and curriculum units a teacher assigns to a book in their classroom library.
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct Curriculum {
+    lexile: Option<u32>,
+    grade: Option<u8>,
+    units: Vec<String>,
+}
+
+#[near_bindgen]
+impl Contract {
+    pub fn set_curriculum(&mut self, book_id: BookId, curriculum: Curriculum) -> Curriculum {
+        self.assert_can_mutate();
+        let account_id = self.internal_library_owner(&book_id);
+        let initial_storage = env::storage_usage();
+        if let Some(grade) = curriculum.grade {
+            assert!(grade <= MAX_GRADE, "Grade must be at most {}", MAX_GRADE);
+        }
+
+        self.curriculum_by_book_id.insert(&book_id, &curriculum);
+
+        let mut book = self.books.get(&book_id).unwrap();
+        self.internal_touch_book(&account_id, &mut book);
+        self.books.insert(&book_id, &book);
+        self.internal_charge_book_update(&account_id, &book_id, initial_storage);
+
+        curriculum
+    }
+
+    pub fn get_curriculum(&self, book_id: BookId) -> Option<Curriculum> {
+        if self.internal_is_private(&book_id) {
+            return None;
+        }
+        self.curriculum_by_book_id.get(&book_id)
+    }
+
+    /// Lists an account's books matching the given grade and/or curriculum unit.
+    pub fn get_books_by_curriculum(
+        &self,
+        account_id: ValidAccountId,
+        grade: Option<u8>,
+        unit: Option<String>,
+        from_index: Option<u64>,
+        limit: Option<u64>,
+    ) -> Page<Book> {
+        let book_ids = match self.books_by_owner_id.get(&account_id.to_string()) {
+            Some(book_ids) => book_ids,
+            None => return Page::empty(),
+        };
+
+        let matching: Vec<BookId> = book_ids
+            .iter()
+            .filter(|book_id| {
+                self.curriculum_by_book_id
+                    .get(book_id)
+                    .map(|curriculum| {
+                        grade.is_none_or(|grade| curriculum.grade == Some(grade))
+                            && unit
+                                .as_ref()
+                                .is_none_or(|unit| curriculum.units.contains(unit))
+                    })
+                    .unwrap_or(false)
+            })
+            .collect();
+
+        Page::from_vec(matching, from_index, limit, |book_id| {
+            self.internal_get_listed_book(&book_id)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{get_context, get_contract, get_sample_book};
+    use near_sdk::testing_env;
+    use near_sdk::MockedBlockchain;
+    use std::convert::TryFrom;
+
+    #[test]
+    fn test_get_books_by_curriculum() {
+        let context = get_context(vec![], false);
+        testing_env!(context);
+        let mut contract = get_contract();
+        let first_id = contract.add_book(get_sample_book());
+        let second_id = contract.add_book(get_sample_book());
+        contract.add_book(get_sample_book());
+
+        contract.set_curriculum(
+            first_id.clone(),
+            Curriculum {
+                lexile: Some(950),
+                grade: Some(7),
+                units: vec!["unit 3".to_string()],
+            },
+        );
+        contract.set_curriculum(
+            second_id,
+            Curriculum {
+                lexile: None,
+                grade: Some(7),
+                units: vec!["unit 1".to_string()],
+            },
+        );
+
+        let account_id = ValidAccountId::try_from("carol_near").unwrap();
+        let books = contract.get_books_by_curriculum(
+            account_id.clone(),
+            Some(7),
+            Some("unit 3".to_string()),
+            None,
+            None,
+        );
+        assert_eq!(books.total, 1);
+        assert_eq!(books.items[0].book_id, Some(first_id));
+
+        let books = contract.get_books_by_curriculum(account_id, Some(7), None, None, None);
+        assert_eq!(books.total, 2);
+    }
+
+    #[test]
+    #[should_panic(expected = "Grade must be at most 12")]
+    fn test_set_curriculum_invalid_grade() {
+        let context = get_context(vec![], false);
+        testing_env!(context);
+        let mut contract = get_contract();
+        let book_id = contract.add_book(get_sample_book());
+        contract.set_curriculum(
+            book_id,
+            Curriculum {
+                lexile: None,
+                grade: Some(13),
+                units: vec![],
+            },
+        );
+    }
+}
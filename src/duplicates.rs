@@ -0,0 +1,97 @@
+use crate::*;
+
+/// Lowercases a title and drops punctuation and repeated whitespace, so "The Hobbit" and
+/// "the hobbit." compare equal.
+pub(crate) fn normalize_title(title: &str) -> String {
+    title
+        .split_whitespace()
+        .map(|word| {
+            word.chars()
+                .filter(|c| c.is_alphanumeric())
+                .flat_map(char::to_lowercase)
+                .collect::<String>()
+        })
+        .filter(|word| !word.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Strips the hyphens and spaces ISBNs are often written with.
+pub(crate) fn normalize_isbn(isbn: &str) -> String {
+    isbn.chars()
+        .filter(|c| c.is_ascii_alphanumeric())
+        .map(|c| c.to_ascii_uppercase())
+        .collect()
+}
+
+#[near_bindgen]
+impl Contract {
+    /// Lists the account's books that look like the given one, by normalized title or matching
+    /// ISBN, so clients can warn before adding the same book twice.
+    pub fn find_duplicates(
+        &self,
+        account_id: ValidAccountId,
+        title: String,
+        isbn: Option<String>,
+    ) -> Vec<Book> {
+        let title = normalize_title(&title);
+        let isbn = isbn.as_deref().map(normalize_isbn);
+        match self.books_by_owner_id.get(account_id.as_ref()) {
+            Some(book_ids) => book_ids
+                .iter()
+                .filter_map(|book_id| self.internal_get_listed_book(&book_id))
+                .filter(|book| {
+                    normalize_title(&book.title) == title
+                        || matches!(
+                            (&isbn, &book.isbn),
+                            (Some(isbn), Some(book_isbn)) if &normalize_isbn(book_isbn) == isbn
+                        )
+                })
+                .collect(),
+            None => vec![],
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{get_context, get_contract, get_sample_book};
+    use near_sdk::testing_env;
+    use near_sdk::MockedBlockchain;
+    use std::convert::TryFrom;
+
+    #[test]
+    fn test_find_duplicates() {
+        let context = get_context(vec![], false);
+        testing_env!(context);
+        let mut contract = get_contract();
+        let book_id = contract.add_book(get_sample_book());
+        let isbn_id = contract.add_book(Book {
+            title: "The Hobbit".to_string(),
+            isbn: Some("978-0-261-10221-7".to_string()),
+            ..get_sample_book()
+        });
+
+        let account_id = ValidAccountId::try_from("carol_near").unwrap();
+        let duplicates = contract.find_duplicates(
+            account_id.clone(),
+            "  motorcycle mechanics, 101!".to_string(),
+            None,
+        );
+        assert_eq!(duplicates.len(), 1);
+        assert_eq!(duplicates[0].book_id, Some(book_id));
+
+        let duplicates = contract.find_duplicates(
+            account_id.clone(),
+            "There and Back Again".to_string(),
+            Some("9780261102217".to_string()),
+        );
+        assert_eq!(duplicates.len(), 1);
+        assert_eq!(duplicates[0].book_id, Some(isbn_id));
+
+        assert!(contract
+            .find_duplicates(account_id, "Dune".to_string(), None)
+            .is_empty());
+    }
+}
@@ -0,0 +1,115 @@
+use crate::*;
+
+/// Descriptive fields to change on a book; fields left out are kept. Status, rating and dates
+/// have their own methods.
+#[derive(Serialize, Deserialize, Default, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct BookEdit {
+    pub title: Option<String>,
+    pub description: Option<String>,
+    pub images: Option<Vec<String>>,
+    pub pages: Option<u64>,
+    pub author: Option<String>,
+    pub author_account_id: Option<AccountId>,
+    pub format: Option<Format>,
+    pub language: Option<String>,
+    pub isbn: Option<String>,
+    pub tags: Option<Vec<String>>,
+}
+
+#[near_bindgen]
+impl Contract {
+    /// Changes a book's descriptive fields. The owner, an editor of the library or an account
+    /// approved with `nft_approve` can call it.
+    pub fn edit_book(
+        &mut self,
+        book_id: BookId,
+        changes: BookEdit,
+        approval_id: Option<u64>,
+    ) -> Book {
+        self.assert_can_mutate();
+        let account_id = self.internal_authorize_edit(&book_id, approval_id);
+        let initial_storage = env::storage_usage();
+
+        let mut book = self.books.get(&book_id).unwrap();
+        self.internal_remove_book_from_owner(&account_id, &book_id, &book);
+        book.title = changes.title.unwrap_or(book.title);
+        book.description = changes.description.unwrap_or(book.description);
+        book.images = changes.images.unwrap_or(book.images);
+        book.pages = changes.pages.or(book.pages);
+        book.author = changes.author.or(book.author);
+        book.author_account_id = changes.author_account_id.or(book.author_account_id);
+        book.format = changes.format.or(book.format);
+        book.language = changes.language.or(book.language);
+        book.isbn = changes.isbn.or(book.isbn);
+        book.tags = changes.tags.unwrap_or(book.tags);
+        book.assert_valid(&self.internal_config());
+        book.touch();
+        self.books.insert(&book_id, &book);
+        self.internal_add_book_to_owner(&account_id, &book_id, &book);
+        self.internal_charge_book_update(&account_id, &book_id, initial_storage);
+
+        book
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{get_context, get_contract, get_sample_book};
+    use near_sdk::testing_env;
+    use near_sdk::MockedBlockchain;
+    use std::convert::TryFrom;
+
+    #[test]
+    fn test_edit_book_reindexes_title() {
+        let context = get_context(vec![], false);
+        testing_env!(context);
+        let mut contract = get_contract();
+        let book_id = contract.add_book(get_sample_book());
+
+        let book = contract.edit_book(
+            book_id.clone(),
+            BookEdit {
+                title: Some("Dune".to_string()),
+                ..Default::default()
+            },
+            None,
+        );
+        assert_eq!(book.title, "Dune");
+        assert_eq!(book.pages, Some(320));
+
+        let account_id = ValidAccountId::try_from("carol_near").unwrap();
+        let found = contract.search_books(account_id, "dune".to_string(), None, None);
+        assert_eq!(found.items[0].book_id, Some(book_id));
+    }
+
+    #[test]
+    fn test_approved_account_edits_book() {
+        let mut context = get_context(vec![], false);
+        context.attached_deposit = 10u128.pow(24);
+        testing_env!(context.clone());
+        let mut contract = get_contract();
+        let book_id = contract.add_book(get_sample_book());
+        contract.nft_approve(
+            book_id.clone(),
+            ValidAccountId::try_from("dave_near").unwrap(),
+            None,
+        );
+
+        context.predecessor_account_id = "dave_near".to_string();
+        context.attached_deposit = 0;
+        context.storage_usage = env::storage_usage();
+        testing_env!(context);
+        let book = contract.edit_book(
+            book_id,
+            BookEdit {
+                title: Some("Dune".to_string()),
+                ..Default::default()
+            },
+            Some(0),
+        );
+        assert_eq!(book.title, "Dune");
+        assert_eq!(book.account_id, Some("carol_near".to_string()));
+    }
+}
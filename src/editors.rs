@@ -0,0 +1,134 @@
+use crate::*;
+
+#[near_bindgen]
+impl Contract {
+    /// Lets `account_id`, e.g. a family account or a librarian bot, add books to the caller's
+    /// library and change the books in it. Editors cannot delete, transfer or approve books.
+    pub fn grant_editor(&mut self, account_id: ValidAccountId) {
+        self.assert_can_mutate();
+        let owner_id = self.internal_caller_id();
+        let initial_storage = env::storage_usage();
+        let mut editor_ids = self.editors_by_owner_id.get(&owner_id).unwrap_or_default();
+        if !editor_ids.contains(account_id.as_ref()) {
+            editor_ids.push(account_id.into());
+            self.editors_by_owner_id.insert(&owner_id, &editor_ids);
+        }
+        self.internal_charge_storage(&owner_id, initial_storage);
+    }
+
+    pub fn revoke_editor(&mut self, account_id: ValidAccountId) {
+        self.assert_can_mutate();
+        let owner_id = self.internal_caller_id();
+        let initial_storage = env::storage_usage();
+        let mut editor_ids = self.editors_by_owner_id.get(&owner_id).unwrap_or_default();
+        editor_ids.retain(|id| id != account_id.as_ref());
+        if editor_ids.is_empty() {
+            self.editors_by_owner_id.remove(&owner_id);
+        } else {
+            self.editors_by_owner_id.insert(&owner_id, &editor_ids);
+        }
+        self.internal_charge_storage(&owner_id, initial_storage);
+    }
+
+    pub fn get_editors(&self, account_id: ValidAccountId) -> Vec<AccountId> {
+        self.editors_by_owner_id
+            .get(account_id.as_ref())
+            .unwrap_or_default()
+    }
+
+    /// Adds a book to `account_id`'s library, which the caller must be an editor of. The
+    /// library owner pays for the storage.
+    pub fn add_book_for(&mut self, account_id: ValidAccountId, book: Book) -> BookId {
+        self.assert_can_mutate();
+        let caller_id = self.internal_caller_id();
+        assert!(
+            self.internal_is_editor(account_id.as_ref(), &caller_id),
+            "Not an editor of this library"
+        );
+        self.internal_add_book(account_id.as_ref(), book)
+    }
+}
+
+impl Contract {
+    pub(crate) fn internal_is_editor(&self, owner_id: &AccountId, account_id: &AccountId) -> bool {
+        self.editors_by_owner_id
+            .get(owner_id)
+            .is_some_and(|editor_ids| editor_ids.contains(account_id))
+    }
+
+    /// Returns the owner of `book_id` if the caller is that owner or one of their editors.
+    pub(crate) fn internal_library_owner(&self, book_id: &BookId) -> AccountId {
+        let caller_id = self.internal_caller_id();
+        let owner_id = self
+            .books
+            .get(book_id)
+            .and_then(|book| book.account_id)
+            .expect("Book does not exist");
+        assert!(
+            caller_id == owner_id || self.internal_is_editor(&owner_id, &caller_id),
+            "Book does not exist"
+        );
+        owner_id
+    }
+
+    /// Like `internal_authorize`, but also lets editors of the owner's library through.
+    /// Returns the owner.
+    pub(crate) fn internal_authorize_edit(
+        &self,
+        book_id: &BookId,
+        approval_id: Option<u64>,
+    ) -> AccountId {
+        let caller_id = self.internal_caller_id();
+        if let Some(owner_id) = self.books.get(book_id).and_then(|book| book.account_id) {
+            if self.internal_is_editor(&owner_id, &caller_id) {
+                return owner_id;
+            }
+        }
+        self.internal_authorize(book_id, approval_id).0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{get_context, get_contract, get_sample_book};
+    use near_sdk::testing_env;
+    use near_sdk::MockedBlockchain;
+    use std::convert::TryFrom;
+
+    #[test]
+    fn test_editor_adds_and_updates() {
+        let mut context = get_context(vec![], false);
+        testing_env!(context.clone());
+        let mut contract = get_contract();
+        contract.grant_editor(ValidAccountId::try_from("dave_near").unwrap());
+
+        context.predecessor_account_id = "dave_near".to_string();
+        context.storage_usage = env::storage_usage();
+        testing_env!(context);
+        let carol = ValidAccountId::try_from("carol_near").unwrap();
+        let book_id = contract.add_book_for(carol.clone(), get_sample_book());
+        let book = contract
+            .update_book(book_id.clone(), Status::Read, None)
+            .unwrap();
+        assert_eq!(book.account_id, Some("carol_near".to_string()));
+        contract.add_bookmark(book_id, "Chapter 1".to_string(), 12);
+        assert_eq!(contract.get_books_count(carol), 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "Book does not exist")]
+    fn test_editor_cannot_delete() {
+        let mut context = get_context(vec![], false);
+        context.attached_deposit = 1;
+        testing_env!(context.clone());
+        let mut contract = get_contract();
+        let book_id = contract.add_book(get_sample_book());
+        contract.grant_editor(ValidAccountId::try_from("dave_near").unwrap());
+
+        context.predecessor_account_id = "dave_near".to_string();
+        context.storage_usage = env::storage_usage();
+        testing_env!(context);
+        contract.delete_book(book_id);
+    }
+}
@@ -0,0 +1,127 @@
+use crate::*;
+use near_sdk::json_types::U128;
+
+/// Most tokens one NEP-181 call returns, so a page always fits in a view call.
+const MAX_TOKENS_LIMIT: u64 = 100;
+
+#[near_bindgen]
+impl Contract {
+    pub fn nft_total_supply(&self) -> U128 {
+        U128(self.books.len() as u128)
+    }
+
+    /// NEP-181 listing of every book token. Each index is one token, so pages line up with
+    /// `nft_total_supply`; books that are not public come back without metadata. At most
+    /// 100 tokens per call.
+    pub fn nft_tokens(&self, from_index: Option<U128>, limit: Option<u64>) -> Vec<Token> {
+        let books = self.books.values_as_vector();
+        let (start, end) = tokens_range(from_index, limit, books.len());
+        (start..end)
+            .filter_map(|index| books.get(index))
+            .map(|book| self.internal_enumerated_token(book))
+            .collect()
+    }
+
+    pub fn nft_supply_for_owner(&self, account_id: ValidAccountId) -> U128 {
+        U128(self.get_books_count(account_id) as u128)
+    }
+
+    /// Same as `nft_tokens` over one owner's books, lining up with `nft_supply_for_owner`.
+    pub fn nft_tokens_for_owner(
+        &self,
+        account_id: ValidAccountId,
+        from_index: Option<U128>,
+        limit: Option<u64>,
+    ) -> Vec<Token> {
+        match self.books_by_owner_id.get(account_id.as_ref()) {
+            Some(book_ids) => {
+                let book_ids = book_ids.as_vector();
+                let (start, end) = tokens_range(from_index, limit, book_ids.len());
+                (start..end)
+                    .filter_map(|index| book_ids.get(index))
+                    .filter_map(|book_id| self.books.get(&book_id))
+                    .map(|book| self.internal_enumerated_token(book))
+                    .collect()
+            }
+            None => vec![],
+        }
+    }
+}
+
+impl Contract {
+    /// A book as NEP-181 enumeration returns it: in full when listed, otherwise only its id
+    /// and owner.
+    fn internal_enumerated_token(&self, book: Book) -> Token {
+        let listed = self.internal_is_listed(&book);
+        let mut token = self.internal_token(book);
+        if !listed {
+            token.metadata = None;
+        }
+        token
+    }
+}
+
+fn tokens_range(from_index: Option<U128>, limit: Option<u64>, total: u64) -> (u64, u64) {
+    let limit = cmp::min(limit.unwrap_or(MAX_TOKENS_LIMIT), MAX_TOKENS_LIMIT);
+    assert_ne!(limit, 0, "Cannot provide limit of 0.");
+    let start = cmp::min(
+        from_index.map_or(0, |from_index| from_index.0 as u64),
+        total,
+    );
+    (start, cmp::min(total, start + limit))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{get_context, get_contract, get_sample_book};
+    use near_sdk::testing_env;
+    use near_sdk::MockedBlockchain;
+    use std::convert::TryFrom;
+
+    #[test]
+    fn test_enumeration() {
+        let mut context = get_context(vec![], false);
+        testing_env!(context.clone());
+        let mut contract = get_contract();
+        contract.add_book(get_sample_book());
+        contract.add_book(get_sample_book());
+
+        context.predecessor_account_id = "dave_near".to_string();
+        context.storage_usage = env::storage_usage();
+        testing_env!(context);
+        let dave_book_id = contract.add_book(get_sample_book());
+
+        assert_eq!(contract.nft_total_supply().0, 3);
+        assert_eq!(contract.nft_tokens(Some(U128(1)), Some(1)).len(), 1);
+
+        let dave = ValidAccountId::try_from("dave_near").unwrap();
+        assert_eq!(contract.nft_supply_for_owner(dave.clone()).0, 1);
+        let tokens = contract.nft_tokens_for_owner(dave, None, None);
+        assert_eq!(tokens[0].token_id, dave_book_id);
+    }
+
+    #[test]
+    fn test_enumeration_keeps_private_books_in_place() {
+        let context = get_context(vec![], false);
+        testing_env!(context);
+        let mut contract = get_contract();
+        contract.add_book(get_sample_book());
+        let private_book_id = contract.add_book(Book {
+            visibility: Visibility::Private,
+            ..get_sample_book()
+        });
+        contract.add_book(get_sample_book());
+
+        let carol = ValidAccountId::try_from("carol_near").unwrap();
+        let tokens = contract.nft_tokens_for_owner(carol.clone(), None, None);
+        assert_eq!(tokens.len() as u128, contract.nft_supply_for_owner(carol).0);
+        assert_eq!(tokens[1].token_id, private_book_id);
+        assert!(tokens[1].metadata.is_none());
+        assert!(tokens[2].metadata.is_some());
+        assert_eq!(
+            contract.nft_tokens(Some(U128(1)), Some(1))[0].token_id,
+            private_book_id
+        );
+    }
+}
@@ -0,0 +1,360 @@
+use crate::*;
+use near_sdk::json_types::U128;
+use near_sdk::serde_json;
+
+const EVENT_STANDARD: &str = "book_tracker";
+const NFT_EVENT_STANDARD: &str = "nep171";
+const EVENT_VERSION: &str = "1.0.0";
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Debug)]
+#[serde(crate = "near_sdk::serde", rename_all = "snake_case")]
+pub enum BookEventKind {
+    BookAdd,
+    BookUpdate,
+    BookDelete,
+}
+
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+struct BookEventData<'a> {
+    account_id: &'a AccountId,
+    book_ids: &'a [BookId],
+    #[serde(skip_serializing_if = "Option::is_none")]
+    storage: Option<StorageChange>,
+}
+
+/// Bytes a call added to or freed from an account's storage, and the storage balance that
+/// locked or released. Book and NFT events carry the change of the call that logged them.
+#[derive(Serialize, Clone, Copy, PartialEq, Debug)]
+#[serde(crate = "near_sdk::serde", rename_all = "snake_case")]
+pub enum StorageChange {
+    Charged { bytes: u64, amount: U128 },
+    Released { bytes: u64, amount: U128 },
+}
+
+/// NEP-171 event payloads. Optional fields are left out of the log when unset.
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde", tag = "event", content = "data")]
+pub(crate) enum NftEvent<'a> {
+    #[serde(rename = "nft_mint")]
+    Mint([NftMintData<'a>; 1]),
+    #[serde(rename = "nft_transfer")]
+    Transfer([NftTransferData<'a>; 1]),
+    #[serde(rename = "nft_burn")]
+    Burn([NftMintData<'a>; 1]),
+}
+
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+pub(crate) struct NftMintData<'a> {
+    pub owner_id: &'a AccountId,
+    pub token_ids: &'a [BookId],
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub storage: Option<StorageChange>,
+}
+
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+pub(crate) struct NftTransferData<'a> {
+    pub old_owner_id: &'a AccountId,
+    pub new_owner_id: &'a AccountId,
+    pub token_ids: &'a [BookId],
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub authorized_id: Option<&'a AccountId>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub memo: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub old_owner_storage: Option<StorageChange>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub new_owner_storage: Option<StorageChange>,
+}
+
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+pub(crate) struct TipData<'a> {
+    pub account_id: &'a AccountId,
+    pub counterparty_id: &'a AccountId,
+    pub book_id: &'a BookId,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub review_id: Option<&'a ReviewId>,
+    pub amount: U128,
+}
+
+/// Tips are logged once for each side so both accounts can find them by `account_id`. Review
+/// tips also carry the `review_id`.
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde", tag = "event", content = "data")]
+pub(crate) enum TipEvent<'a> {
+    #[serde(rename = "tip_sent")]
+    Sent([TipData<'a>; 1]),
+    #[serde(rename = "tip_received")]
+    Received([TipData<'a>; 1]),
+}
+
+/// Logged for calls that change storage without logging a book or NFT event.
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+struct StorageData<'a> {
+    account_id: &'a AccountId,
+    bytes: u64,
+    amount: U128,
+}
+
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde", tag = "event", content = "data")]
+enum StorageEvent<'a> {
+    #[serde(rename = "storage_charged")]
+    Charged([StorageData<'a>; 1]),
+    #[serde(rename = "storage_released")]
+    Released([StorageData<'a>; 1]),
+}
+
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+pub(crate) struct ModerationData<'a> {
+    pub moderator_id: &'a AccountId,
+    pub account_id: &'a AccountId,
+    pub book_id: &'a BookId,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub field: Option<RedactableField>,
+    pub reason: &'a str,
+}
+
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde", tag = "event", content = "data")]
+pub(crate) enum ModerationEvent<'a> {
+    #[serde(rename = "book_removed")]
+    BookRemoved([ModerationData<'a>; 1]),
+    #[serde(rename = "field_redacted")]
+    FieldRedacted([ModerationData<'a>; 1]),
+}
+
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+pub(crate) struct BuddyReadData<'a> {
+    pub buddy_read_id: &'a BuddyReadId,
+    pub account_ids: [&'a AccountId; 2],
+    pub book_ids: [&'a BookId; 2],
+}
+
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde", tag = "event", content = "data")]
+enum BuddyReadEvent<'a> {
+    #[serde(rename = "buddy_read_complete")]
+    Complete([BuddyReadData<'a>; 1]),
+}
+
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+struct AchievementData<'a> {
+    account_id: &'a AccountId,
+    achievement: Achievement,
+}
+
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde", tag = "event", content = "data")]
+enum AchievementEvent<'a> {
+    #[serde(rename = "achievement_earned")]
+    Earned([AchievementData<'a>; 1]),
+}
+
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+pub(crate) struct WishlistFulfilledData<'a> {
+    pub account_id: &'a AccountId,
+    pub fulfiller_id: &'a AccountId,
+    pub item_id: &'a WishlistItemId,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub amount: Option<U128>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub book_id: Option<&'a BookId>,
+}
+
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde", tag = "event", content = "data")]
+enum WishlistEvent<'a> {
+    #[serde(rename = "wishlist_fulfilled")]
+    Fulfilled([WishlistFulfilledData<'a>; 1]),
+}
+
+/// NEP-297 event log wrapper.
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+struct EventLog<E: Serialize> {
+    standard: &'static str,
+    version: &'static str,
+    #[serde(flatten)]
+    event: E,
+}
+
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+struct BookEvent<'a> {
+    event: BookEventKind,
+    data: [BookEventData<'a>; 1],
+}
+
+fn emit_event<E: Serialize>(standard: &'static str, event: E) {
+    let log = EventLog {
+        standard,
+        version: EVENT_VERSION,
+        event,
+    };
+    env::log(format!("EVENT_JSON:{}", serde_json::to_string(&log).unwrap()).as_bytes());
+}
+
+/// Logs an `EVENT_JSON:` line so indexers can follow library changes without polling.
+pub(crate) fn emit_book_event(
+    kind: BookEventKind,
+    account_id: &AccountId,
+    book_ids: &[BookId],
+    storage: Option<StorageChange>,
+) {
+    emit_event(
+        EVENT_STANDARD,
+        BookEvent {
+            event: kind,
+            data: [BookEventData {
+                account_id,
+                book_ids,
+                storage,
+            }],
+        },
+    );
+}
+
+pub(crate) fn emit_tip_event(event: TipEvent) {
+    emit_event(EVENT_STANDARD, event);
+}
+
+pub(crate) fn emit_storage_event(account_id: &AccountId, change: StorageChange) {
+    let event = match change {
+        StorageChange::Charged { bytes, amount } => StorageEvent::Charged([StorageData {
+            account_id,
+            bytes,
+            amount,
+        }]),
+        StorageChange::Released { bytes, amount } => StorageEvent::Released([StorageData {
+            account_id,
+            bytes,
+            amount,
+        }]),
+    };
+    emit_event(EVENT_STANDARD, event);
+}
+
+pub(crate) fn emit_moderation_event(event: ModerationEvent) {
+    emit_event(EVENT_STANDARD, event);
+}
+
+pub(crate) fn emit_buddy_read_event(data: BuddyReadData) {
+    emit_event(EVENT_STANDARD, BuddyReadEvent::Complete([data]));
+}
+
+pub(crate) fn emit_achievement_event(account_id: &AccountId, achievement: Achievement) {
+    emit_event(
+        EVENT_STANDARD,
+        AchievementEvent::Earned([AchievementData {
+            account_id,
+            achievement,
+        }]),
+    );
+}
+
+pub(crate) fn emit_wishlist_event(data: WishlistFulfilledData) {
+    emit_event(EVENT_STANDARD, WishlistEvent::Fulfilled([data]));
+}
+
+/// Logs a NEP-171 event so NFT indexers can follow books as tokens.
+pub(crate) fn emit_nft_event(event: NftEvent) {
+    emit_event(NFT_EVENT_STANDARD, event);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{get_context, get_contract, get_sample_book};
+    use near_sdk::test_utils::get_logs;
+    use near_sdk::testing_env;
+    use near_sdk::MockedBlockchain;
+
+    fn event_logs() -> Vec<serde_json::Value> {
+        get_logs()
+            .iter()
+            .filter_map(|log| log.strip_prefix("EVENT_JSON:"))
+            .map(|log| serde_json::from_str::<serde_json::Value>(log).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn test_book_events() {
+        let mut context = get_context(vec![], false);
+        context.attached_deposit = 1;
+        testing_env!(context);
+        let mut contract = get_contract();
+        let book_id = contract.add_book(get_sample_book());
+        contract.update_book(book_id.clone(), Status::Read, None);
+        contract.delete_book(book_id);
+
+        let logs = event_logs();
+        let events: Vec<(&str, &str)> = logs
+            .iter()
+            .map(|log| {
+                (
+                    log["standard"].as_str().unwrap(),
+                    log["event"].as_str().unwrap(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            events,
+            vec![
+                ("book_tracker", "book_add"),
+                ("nep171", "nft_mint"),
+                ("book_tracker", "book_update"),
+                ("book_tracker", "book_delete"),
+                ("nep171", "nft_burn"),
+            ]
+        );
+        assert_eq!(logs[0]["data"][0]["account_id"], "carol_near");
+        assert_eq!(logs[0]["data"][0]["book_ids"], serde_json::json!(["1"]));
+        assert_eq!(logs[1]["data"][0]["owner_id"], "carol_near");
+        assert_eq!(logs[1]["data"][0]["token_ids"], serde_json::json!(["1"]));
+
+        // Book and NFT events carry the storage change instead of a separate storage event.
+        let charged = &logs[0]["data"][0]["storage"]["charged"];
+        let bytes = charged["bytes"].as_u64().unwrap();
+        assert!(bytes > 0);
+        assert_eq!(
+            charged["amount"],
+            (bytes as u128 * env::storage_byte_cost()).to_string()
+        );
+        assert_eq!(logs[1]["data"][0]["storage"], logs[0]["data"][0]["storage"]);
+        assert!(logs[3]["data"][0]["storage"].is_object());
+        assert_eq!(logs[4]["data"][0]["storage"], logs[3]["data"][0]["storage"]);
+    }
+
+    #[test]
+    fn test_storage_events() {
+        let mut context = get_context(vec![], false);
+        testing_env!(context.clone());
+        let mut contract = get_contract();
+        let book_id = contract.add_book(get_sample_book());
+
+        context.storage_usage = env::storage_usage();
+        testing_env!(context);
+        contract.add_review(book_id, "Clear and practical".to_string(), 4);
+
+        let logs = event_logs();
+        assert_eq!(logs.len(), 1);
+        assert_eq!(logs[0]["event"], "storage_charged");
+        let data = &logs[0]["data"][0];
+        assert_eq!(data["account_id"], "carol_near");
+        let bytes = data["bytes"].as_u64().unwrap();
+        assert!(bytes > 0);
+        assert_eq!(
+            data["amount"],
+            (bytes as u128 * env::storage_byte_cost()).to_string()
+        );
+    }
+}
@@ -0,0 +1,92 @@
+use crate::*;
+
+/// A book together with everything attached to it, as returned by `export_library`. The shelf
+/// is the book's `status`.
+#[derive(Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct ExportedBook {
+    pub book: Book,
+    pub archived: bool,
+    pub bookmarks: Vec<Bookmark>,
+    pub curriculum: Option<Curriculum>,
+    pub related: Vec<(BookId, RelationKind)>,
+}
+
+#[near_bindgen]
+impl Contract {
+    /// Exports an account's whole library, archived books included, so it can be backed up or
+    /// migrated off-chain. Large libraries come back in chunks; keep calling with
+    /// `next_cursor` until it is `None`.
+    pub fn export_library(
+        &self,
+        account_id: ValidAccountId,
+        from_index: Option<u64>,
+        limit: Option<u64>,
+    ) -> Page<ExportedBook> {
+        let account_id: AccountId = account_id.into();
+        let book_ids = self.books_by_owner_id.get(&account_id);
+        let archived_ids = self.archived_books_by_owner_id.get(&account_id);
+        let books_len = book_ids.as_ref().map_or(0, |book_ids| book_ids.len());
+        let archived_len = archived_ids.as_ref().map_or(0, |book_ids| book_ids.len());
+
+        // Live books come first, then archived ones.
+        Page::from_indexed(books_len + archived_len, from_index, limit, |index| {
+            let (book, archived) = if index < books_len {
+                let book_id = book_ids.as_ref()?.as_vector().get(index)?;
+                (self.books.get(&book_id)?, false)
+            } else {
+                let book_id = archived_ids.as_ref()?.as_vector().get(index - books_len)?;
+                (self.archived_books.get(&book_id)?, true)
+            };
+            if !self.internal_is_listed(&book) {
+                return None;
+            }
+            Some(self.internal_export_book(book, archived))
+        })
+    }
+}
+
+impl Contract {
+    pub(crate) fn internal_export_book(&self, book: Book, archived: bool) -> ExportedBook {
+        let book_id = book.book_id.clone().unwrap();
+        ExportedBook {
+            bookmarks: self.bookmarks_by_book_id.get(&book_id).unwrap_or_default(),
+            curriculum: self.curriculum_by_book_id.get(&book_id),
+            related: self.get_related_books(book_id),
+            book,
+            archived,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{get_context, get_contract, get_sample_book};
+    use near_sdk::testing_env;
+    use near_sdk::MockedBlockchain;
+    use std::convert::TryFrom;
+
+    #[test]
+    fn test_export_library() {
+        let context = get_context(vec![], false);
+        testing_env!(context);
+        let mut contract = get_contract();
+        let book_id = contract.add_book(get_sample_book());
+        let archived_id = contract.add_book(get_sample_book());
+        contract.add_bookmark(book_id.clone(), "Chapter 1".to_string(), 12);
+        contract.archive_book(archived_id.clone());
+
+        let account_id = ValidAccountId::try_from("carol_near").unwrap();
+        let page = contract.export_library(account_id.clone(), None, Some(1));
+        assert_eq!(page.total, 2);
+        assert_eq!(page.items[0].book.book_id, Some(book_id));
+        assert!(!page.items[0].archived);
+        assert_eq!(page.items[0].bookmarks.len(), 1);
+
+        let page = contract.export_library(account_id, page.next_cursor, Some(1));
+        assert_eq!(page.items[0].book.book_id, Some(archived_id));
+        assert!(page.items[0].archived);
+        assert_eq!(page.next_cursor, None);
+    }
+}
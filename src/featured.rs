@@ -0,0 +1,70 @@
+use crate::*;
+
+#[near_bindgen]
+impl Contract {
+    /// Adds a book to the featured list shown to all readers. Owner or curators only.
+    pub fn feature_book(&mut self, book_id: BookId) {
+        self.assert_role(Role::Curator);
+        assert!(self.books.get(&book_id).is_some(), "Book does not exist");
+        self.featured_book_ids.insert(&book_id);
+    }
+
+    pub fn unfeature_book(&mut self, book_id: BookId) {
+        self.assert_role(Role::Curator);
+        self.featured_book_ids.remove(&book_id);
+    }
+
+    /// Lists the featured books in the order they were featured. Books that were deleted or
+    /// archived since are skipped.
+    pub fn get_featured_books(&self, from_index: Option<u64>, limit: Option<u64>) -> Page<Book> {
+        Page::from_vector(
+            self.featured_book_ids.as_vector(),
+            from_index,
+            limit,
+            |book_id| self.internal_get_listed_book(&book_id),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{get_context, get_contract, get_sample_book};
+    use near_sdk::testing_env;
+    use near_sdk::MockedBlockchain;
+    use std::convert::TryFrom;
+
+    #[test]
+    fn test_curator_features_book() {
+        let mut context = get_context(vec![], false);
+        testing_env!(context.clone());
+        let mut contract = get_contract();
+        let book_id = contract.add_book(get_sample_book());
+
+        context.predecessor_account_id = "alice_near".to_string();
+        context.storage_usage = env::storage_usage();
+        testing_env!(context.clone());
+        contract.add_role(
+            ValidAccountId::try_from("dave_near").unwrap(),
+            Role::Curator,
+        );
+
+        context.predecessor_account_id = "dave_near".to_string();
+        context.storage_usage = env::storage_usage();
+        testing_env!(context);
+        contract.feature_book(book_id.clone());
+
+        let featured = contract.get_featured_books(None, None).items;
+        assert_eq!(featured[0].book_id, Some(book_id));
+    }
+
+    #[test]
+    #[should_panic(expected = "Only the owner or a curator can call this method")]
+    fn test_feature_book_not_curator() {
+        let context = get_context(vec![], false);
+        testing_env!(context);
+        let mut contract = get_contract();
+        let book_id = contract.add_book(get_sample_book());
+        contract.feature_book(book_id);
+    }
+}
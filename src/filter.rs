@@ -0,0 +1,284 @@
+use crate::*;
+
+/// Criteria for `get_books`. Every field that is set must match; text fields compare
+/// case-insensitively. Only `tag`, and `status` on an owner's listing, are indexed; a filter
+/// must set one of them, and the other fields narrow the books it finds.
+#[derive(Serialize, Deserialize, Clone, Default)]
+#[serde(crate = "near_sdk::serde")]
+pub struct BookFilter {
+    pub status: Option<Status>,
+    pub tag: Option<String>,
+    pub author: Option<String>,
+    pub format: Option<Format>,
+    pub language: Option<String>,
+    pub rating_min: Option<u8>,
+}
+
+fn matches_text(value: &Option<String>, wanted: &Option<String>) -> bool {
+    match wanted {
+        Some(wanted) => value
+            .as_ref()
+            .is_some_and(|value| value.eq_ignore_ascii_case(wanted)),
+        None => true,
+    }
+}
+
+impl BookFilter {
+    pub(crate) fn matches(&self, book: &Book) -> bool {
+        self.status.is_none_or(|status| book.status == status)
+            && self.format.is_none_or(|format| book.format == Some(format))
+            && self
+                .rating_min
+                .is_none_or(|rating_min| book.rating.is_some_and(|rating| rating >= rating_min))
+            && matches_text(&book.author, &self.author)
+            && matches_text(&book.language, &self.language)
+            && self.tag.as_ref().is_none_or(|tag| {
+                book.tags
+                    .iter()
+                    .any(|book_tag| book_tag.eq_ignore_ascii_case(tag))
+            })
+    }
+}
+
+impl Contract {
+    /// A page of listed books matching `filter`, in insertion order. Candidates come from the
+    /// tag or per-status index and are read lazily under the view gas budget, so `total` counts
+    /// the books scanned rather than the matches.
+    pub(crate) fn internal_filter_books(
+        &self,
+        account_id: Option<AccountId>,
+        filter: &BookFilter,
+        from_index: Option<u64>,
+        limit: Option<u64>,
+        order: SortOrder,
+    ) -> Page<Book> {
+        assert!(
+            filter.tag.is_some() || (account_id.is_some() && filter.status.is_some()),
+            "Filter needs a tag, or a status together with account_id"
+        );
+        self.internal_scan_books(account_id, filter, from_index, limit, order)
+    }
+
+    /// Every listed book matching `filter`, for sorting. Panics rather than sort a partial
+    /// list when the candidates do not fit in the view gas budget.
+    pub(crate) fn internal_filter_books_to_sort(
+        &self,
+        account_id: Option<AccountId>,
+        filter: &BookFilter,
+    ) -> Vec<Book> {
+        let page = self.internal_scan_books(account_id, filter, None, None, SortOrder::Asc);
+        assert!(
+            !page.has_more,
+            "Too many books to sort, narrow the listing with account_id or a status filter"
+        );
+        page.items
+    }
+
+    /// Same as `internal_filter_books` without requiring an indexed field: lists with no index
+    /// to start from scan the owner's or every book.
+    fn internal_scan_books(
+        &self,
+        account_id: Option<AccountId>,
+        filter: &BookFilter,
+        from_index: Option<u64>,
+        limit: Option<u64>,
+        order: SortOrder,
+    ) -> Page<Book> {
+        let tag = filter.tag.as_ref().map(|tag| tag.to_lowercase());
+        let book_ids = match (account_id, tag, filter.status) {
+            (Some(account_id), Some(tag), _) => self.books_by_owner_tag.get(&(account_id, tag)),
+            (None, Some(tag), _) => self.books_by_tag.get(&tag),
+            (Some(account_id), None, Some(status)) => {
+                self.books_by_owner_status.get(&(account_id, status))
+            }
+            (Some(account_id), None, None) => self.books_by_owner_id.get(&account_id),
+            (None, None, _) => {
+                return Page::from_vector_ordered(
+                    self.books.values_as_vector(),
+                    from_index,
+                    limit,
+                    order,
+                    |book| {
+                        Some(book)
+                            .filter(|book| self.internal_is_listed(book) && filter.matches(book))
+                    },
+                )
+            }
+        };
+        match book_ids {
+            Some(book_ids) => Page::from_vector_ordered(
+                book_ids.as_vector(),
+                from_index,
+                limit,
+                order,
+                |book_id| {
+                    self.internal_get_listed_book(&book_id)
+                        .filter(|book| filter.matches(book))
+                },
+            ),
+            None => Page::empty(),
+        }
+    }
+
+    pub(crate) fn internal_index_tags(
+        &mut self,
+        account_id: &AccountId,
+        book_id: &BookId,
+        tags: &[String],
+    ) {
+        for tag in tags.iter().map(|tag| tag.to_lowercase()) {
+            let tag_hash = env::sha256(tag.as_bytes());
+            let mut book_ids = self.books_by_tag.get(&tag).unwrap_or_else(|| {
+                UnorderedSet::new(StorageKey::BooksPerTag {
+                    tag_hash: tag_hash.clone(),
+                })
+            });
+            book_ids.insert(book_id);
+            self.books_by_tag.insert(&tag, &book_ids);
+
+            let key = (account_id.clone(), tag);
+            let mut book_ids = self.books_by_owner_tag.get(&key).unwrap_or_else(|| {
+                UnorderedSet::new(StorageKey::BooksPerOwnerTag {
+                    account_hash: env::sha256(account_id.as_bytes()),
+                    tag_hash,
+                })
+            });
+            book_ids.insert(book_id);
+            self.books_by_owner_tag.insert(&key, &book_ids);
+        }
+    }
+
+    pub(crate) fn internal_unindex_tags(
+        &mut self,
+        account_id: &AccountId,
+        book_id: &BookId,
+        tags: &[String],
+    ) {
+        for tag in tags.iter().map(|tag| tag.to_lowercase()) {
+            if let Some(mut book_ids) = self.books_by_tag.get(&tag) {
+                book_ids.remove(book_id);
+                if book_ids.is_empty() {
+                    self.books_by_tag.remove(&tag);
+                } else {
+                    self.books_by_tag.insert(&tag, &book_ids);
+                }
+            }
+            let key = (account_id.clone(), tag);
+            if let Some(mut book_ids) = self.books_by_owner_tag.get(&key) {
+                book_ids.remove(book_id);
+                if book_ids.is_empty() {
+                    self.books_by_owner_tag.remove(&key);
+                } else {
+                    self.books_by_owner_tag.insert(&key, &book_ids);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{get_context, get_contract, get_sample_book};
+    use near_sdk::testing_env;
+    use near_sdk::MockedBlockchain;
+    use std::convert::TryFrom;
+
+    #[test]
+    fn test_get_books_with_filter() {
+        let context = get_context(vec![], false);
+        testing_env!(context);
+        let mut contract = get_contract();
+        let wanted_id = contract.add_book(Book {
+            status: Status::Finished,
+            format: Some(Format::Audiobook),
+            tags: vec!["Sci-Fi".to_string()],
+            rating: Some(4),
+            ..get_sample_book()
+        });
+        contract.add_book(Book {
+            status: Status::Finished,
+            format: Some(Format::Print),
+            tags: vec!["sci-fi".to_string()],
+            ..get_sample_book()
+        });
+        contract.add_book(Book {
+            format: Some(Format::Audiobook),
+            tags: vec!["sci-fi".to_string()],
+            ..get_sample_book()
+        });
+
+        let filter = BookFilter {
+            status: Some(Status::Finished),
+            tag: Some("sci-fi".to_string()),
+            format: Some(Format::Audiobook),
+            ..Default::default()
+        };
+        let page = contract.get_books(
+            Some(ValidAccountId::try_from("carol_near").unwrap()),
+            None,
+            None,
+            None,
+            None,
+            Some(filter.clone()),
+        );
+        assert_eq!(page.items.len(), 1);
+        assert_eq!(page.items[0].book_id, Some(wanted_id));
+
+        let page = contract.get_books(None, None, None, None, None, Some(filter));
+        assert_eq!(page.items.len(), 1);
+        assert_eq!(page.total, 3);
+
+        let filter = BookFilter {
+            tag: Some("SCI-FI".to_string()),
+            rating_min: Some(5),
+            ..Default::default()
+        };
+        let page = contract.get_books(None, None, None, None, None, Some(filter));
+        assert!(page.items.is_empty());
+        assert_eq!(page.total, 3);
+    }
+
+    #[test]
+    fn test_tag_index_follows_edits() {
+        let context = get_context(vec![], false);
+        testing_env!(context);
+        let mut contract = get_contract();
+        let book_id = contract.add_book(Book {
+            tags: vec!["sci-fi".to_string()],
+            ..get_sample_book()
+        });
+        contract.add_book(get_sample_book());
+        contract.edit_book(
+            book_id.clone(),
+            BookEdit {
+                tags: Some(vec!["Fantasy".to_string()]),
+                ..Default::default()
+            },
+            None,
+        );
+
+        let filter_by = |tag: &str| BookFilter {
+            tag: Some(tag.to_string()),
+            ..Default::default()
+        };
+        let page = contract.get_books(None, None, None, None, None, Some(filter_by("sci-fi")));
+        assert_eq!(page.total, 0);
+        let page = contract.get_books(None, None, None, None, None, Some(filter_by("fantasy")));
+        assert_eq!(page.total, 1);
+        assert_eq!(page.items[0].book_id, Some(book_id));
+    }
+
+    #[test]
+    #[should_panic(expected = "Filter needs a tag, or a status together with account_id")]
+    fn test_filter_needs_indexed_field() {
+        let context = get_context(vec![], false);
+        testing_env!(context);
+        let contract = get_contract();
+        let filter = BookFilter {
+            rating_min: Some(4),
+            ..Default::default()
+        };
+        contract.get_books(None, None, None, None, None, Some(filter));
+    }
+}
@@ -0,0 +1,171 @@
+use crate::*;
+
+const MAX_FOLLOWING: u64 = 200;
+
+#[near_bindgen]
+impl Contract {
+    pub fn follow(&mut self, account_id: ValidAccountId) {
+        self.assert_can_mutate();
+        let follower_id = self.internal_caller_id();
+        let initial_storage = env::storage_usage();
+        let account_id: AccountId = account_id.into();
+        assert_ne!(follower_id, account_id, "Cannot follow yourself");
+
+        let mut following = self
+            .following_by_account_id
+            .get(&follower_id)
+            .unwrap_or_else(|| {
+                UnorderedSet::new(StorageKey::FollowingPerAccount {
+                    account_hash: env::sha256(follower_id.as_bytes()),
+                })
+            });
+        if following.insert(&account_id) {
+            assert!(
+                following.len() <= MAX_FOLLOWING,
+                "Cannot follow more than {} accounts",
+                MAX_FOLLOWING
+            );
+            self.following_by_account_id
+                .insert(&follower_id, &following);
+
+            let mut followers = self
+                .followers_by_account_id
+                .get(&account_id)
+                .unwrap_or_else(|| {
+                    UnorderedSet::new(StorageKey::FollowersPerAccount {
+                        account_hash: env::sha256(account_id.as_bytes()),
+                    })
+                });
+            followers.insert(&follower_id);
+            self.followers_by_account_id.insert(&account_id, &followers);
+        }
+        self.internal_charge_storage(&follower_id, initial_storage);
+    }
+
+    pub fn unfollow(&mut self, account_id: ValidAccountId) {
+        self.assert_can_mutate();
+        let follower_id = self.internal_caller_id();
+        let initial_storage = env::storage_usage();
+        let account_id: AccountId = account_id.into();
+
+        if let Some(mut following) = self.following_by_account_id.get(&follower_id) {
+            if following.remove(&account_id) {
+                if following.is_empty() {
+                    self.following_by_account_id.remove(&follower_id);
+                } else {
+                    self.following_by_account_id
+                        .insert(&follower_id, &following);
+                }
+                if let Some(mut followers) = self.followers_by_account_id.get(&account_id) {
+                    followers.remove(&follower_id);
+                    if followers.is_empty() {
+                        self.followers_by_account_id.remove(&account_id);
+                    } else {
+                        self.followers_by_account_id.insert(&account_id, &followers);
+                    }
+                }
+            }
+        }
+        self.internal_charge_storage(&follower_id, initial_storage);
+    }
+
+    pub fn get_following(
+        &self,
+        account_id: ValidAccountId,
+        from_index: Option<u64>,
+        limit: Option<u64>,
+    ) -> Page<AccountId> {
+        match self.following_by_account_id.get(account_id.as_ref()) {
+            Some(following) => Page::from_vector(following.as_vector(), from_index, limit, Some),
+            None => Page::empty(),
+        }
+    }
+
+    pub fn get_followers(
+        &self,
+        account_id: ValidAccountId,
+        from_index: Option<u64>,
+        limit: Option<u64>,
+    ) -> Page<AccountId> {
+        match self.followers_by_account_id.get(account_id.as_ref()) {
+            Some(followers) => Page::from_vector(followers.as_vector(), from_index, limit, Some),
+            None => Page::empty(),
+        }
+    }
+
+    /// Recent public activity of the accounts `account_id` follows, newest first.
+    pub fn get_following_activity(
+        &self,
+        account_id: ValidAccountId,
+        limit: Option<u64>,
+    ) -> Vec<Activity> {
+        let limit = limit.unwrap_or(20);
+        assert_ne!(limit, 0, "Cannot provide limit of 0.");
+        let mut feed: Vec<Activity> = self
+            .following_by_account_id
+            .get(account_id.as_ref())
+            .map(|following| {
+                following
+                    .iter()
+                    .flat_map(|followed_id| {
+                        self.activity_by_account_id
+                            .get(&followed_id)
+                            .unwrap_or_default()
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        feed.sort_by_key(|activity| cmp::Reverse(activity.timestamp.0));
+        feed.truncate(limit as usize);
+        feed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{get_context, get_contract, get_sample_book};
+    use near_sdk::testing_env;
+    use near_sdk::MockedBlockchain;
+    use std::convert::TryFrom;
+
+    #[test]
+    fn test_following_activity() {
+        let mut context = get_context(vec![], false);
+        testing_env!(context.clone());
+        let mut contract = get_contract();
+        let dave = ValidAccountId::try_from("dave_near").unwrap();
+        let erin = ValidAccountId::try_from("erin_near").unwrap();
+        contract.follow(dave.clone());
+        contract.follow(erin.clone());
+
+        context.predecessor_account_id = "dave_near".to_string();
+        context.block_timestamp = 10;
+        context.storage_usage = env::storage_usage();
+        testing_env!(context.clone());
+        contract.add_book(get_sample_book());
+
+        context.predecessor_account_id = "erin_near".to_string();
+        context.block_timestamp = 20;
+        context.storage_usage = env::storage_usage();
+        testing_env!(context.clone());
+        contract.add_book(get_sample_book());
+
+        let carol = ValidAccountId::try_from("carol_near").unwrap();
+        let feed = contract.get_following_activity(carol.clone(), None);
+        assert_eq!(feed.len(), 2);
+        assert_eq!(feed[0].account_id, "erin_near");
+        assert_eq!(
+            contract.get_followers(dave.clone(), None, None).items,
+            vec!["carol_near"]
+        );
+
+        context.predecessor_account_id = "carol_near".to_string();
+        context.storage_usage = env::storage_usage();
+        testing_env!(context);
+        contract.unfollow(erin);
+        let feed = contract.get_following_activity(carol, None);
+        assert_eq!(feed.len(), 1);
+        assert_eq!(feed[0].account_id, "dave_near");
+    }
+}
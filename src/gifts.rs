@@ -0,0 +1,192 @@
+use crate::*;
+
+pub type GiftId = String;
+
+const MAX_INBOX_GIFTS: u64 = 100;
+
+/// A copy of one of the sender's books, with their notes, tags and cover, waiting for the
+/// recipient to accept it. The sender pays for its storage until then.
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct Gift {
+    gift_id: GiftId,
+    sender_id: AccountId,
+    book: Book,
+    message: Option<String>,
+    created_at: U64,
+}
+
+#[near_bindgen]
+impl Contract {
+    /// Sends a copy of one of the caller's books to another account. The caller keeps their
+    /// own book; the copy is taken now, so later edits are not included.
+    pub fn gift_book(
+        &mut self,
+        book_id: BookId,
+        to_account: ValidAccountId,
+        message: Option<String>,
+    ) -> GiftId {
+        self.assert_can_mutate();
+        let sender_id = self.internal_caller_id();
+        let initial_storage = env::storage_usage();
+        let recipient_id: AccountId = to_account.into();
+        assert_ne!(sender_id, recipient_id, "Cannot gift a book to yourself");
+        self.assert_book_owner(&sender_id, &book_id);
+        if let Some(message) = &message {
+            let max_length = self.internal_config().max_description_length;
+            assert!(
+                message.len() <= max_length as usize,
+                "Message exceeds {} characters",
+                max_length
+            );
+        }
+
+        let mut inbox = self
+            .gifts_by_account_id
+            .get(&recipient_id)
+            .unwrap_or_else(|| {
+                UnorderedSet::new(StorageKey::GiftsPerAccount {
+                    account_hash: env::sha256(recipient_id.as_bytes()),
+                })
+            });
+        assert!(inbox.len() < MAX_INBOX_GIFTS, "Gift inbox is full");
+
+        let book = self.books.get(&book_id).unwrap();
+        let gift_id = format!("{}", self.gifts_len + 1);
+        self.gifts.insert(
+            &gift_id,
+            &Gift {
+                gift_id: gift_id.clone(),
+                sender_id: sender_id.clone(),
+                book: Book {
+                    book_id: None,
+                    account_id: None,
+                    status: Status::List,
+                    rating: None,
+                    created_at: None,
+                    updated_at: None,
+                    finished_at: None,
+                    finished_at_self_reported: false,
+                    visibility: Visibility::default(),
+                    ..book
+                },
+                message,
+                created_at: env::block_timestamp().into(),
+            },
+        );
+        inbox.insert(&gift_id);
+        self.gifts_by_account_id.insert(&recipient_id, &inbox);
+
+        self.gifts_len += 1;
+        self.internal_charge_storage(&sender_id, initial_storage);
+
+        gift_id
+    }
+
+    /// Adds the gifted book to the caller's List shelf and returns its id.
+    pub fn accept_gift(&mut self, gift_id: GiftId) -> BookId {
+        self.assert_can_mutate();
+        let account_id = self.internal_caller_id();
+        let gift = self.internal_take_gift(&account_id, &gift_id);
+        self.internal_add_book(&account_id, gift.book)
+    }
+
+    pub fn decline_gift(&mut self, gift_id: GiftId) {
+        self.assert_can_mutate();
+        let account_id = self.internal_caller_id();
+        self.internal_take_gift(&account_id, &gift_id);
+    }
+
+    pub fn get_gifts(
+        &self,
+        account_id: ValidAccountId,
+        from_index: Option<u64>,
+        limit: Option<u64>,
+    ) -> Page<Gift> {
+        match self.gifts_by_account_id.get(account_id.as_ref()) {
+            Some(inbox) => Page::from_vector(inbox.as_vector(), from_index, limit, |gift_id| {
+                self.gifts.get(&gift_id)
+            }),
+            None => Page::empty(),
+        }
+    }
+}
+
+impl Contract {
+    /// Removes a gift from the recipient's inbox and releases its storage back to the sender.
+    fn internal_take_gift(&mut self, recipient_id: &AccountId, gift_id: &GiftId) -> Gift {
+        let initial_storage = env::storage_usage();
+        let mut inbox = self
+            .gifts_by_account_id
+            .get(recipient_id)
+            .expect("Gift does not exist");
+        assert!(inbox.remove(gift_id), "Gift does not exist");
+        if inbox.is_empty() {
+            self.gifts_by_account_id.remove(recipient_id);
+        } else {
+            self.gifts_by_account_id.insert(recipient_id, &inbox);
+        }
+
+        let gift = self.gifts.remove(gift_id).unwrap();
+        self.internal_charge_storage(&gift.sender_id, initial_storage);
+        gift
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{get_context, get_contract, get_sample_book};
+    use near_sdk::testing_env;
+    use near_sdk::MockedBlockchain;
+    use std::convert::TryFrom;
+
+    #[test]
+    fn test_accept_gift() {
+        let mut context = get_context(vec![], false);
+        testing_env!(context.clone());
+        let mut contract = get_contract();
+        let book_id = contract.add_book(Book {
+            tags: vec!["DIY".to_string()],
+            rating: Some(5),
+            ..get_sample_book()
+        });
+        let gift_id = contract.gift_book(
+            book_id.clone(),
+            ValidAccountId::try_from("dave_near").unwrap(),
+            Some("Happy birthday".into()),
+        );
+
+        context.predecessor_account_id = "dave_near".to_string();
+        context.storage_usage = env::storage_usage();
+        testing_env!(context);
+        let new_id = contract.accept_gift(gift_id);
+
+        let book = contract.get_book(new_id);
+        assert_eq!(book.account_id, Some("dave_near".to_string()));
+        assert_eq!(book.tags, vec!["DIY".to_string()]);
+        assert_eq!(book.rating, None);
+        assert_eq!(
+            contract.get_book(book_id).account_id,
+            Some("carol_near".to_string())
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Book does not exist")]
+    fn test_gift_someone_elses_book() {
+        let mut context = get_context(vec![], false);
+        testing_env!(context.clone());
+        let mut contract = get_contract();
+        let book_id = contract.add_book(get_sample_book());
+
+        context.predecessor_account_id = "dave_near".to_string();
+        context.storage_usage = env::storage_usage();
+        testing_env!(context);
+        contract.gift_book(
+            book_id,
+            ValidAccountId::try_from("erin_near").unwrap(),
+            None,
+        );
+    }
+}
@@ -0,0 +1,289 @@
+use crate::*;
+use near_sdk::json_types::U128;
+use near_sdk::Promise;
+
+/// NEAR staked on a reading goal. Refunded if the goal is met, otherwise sent to the configured
+/// beneficiary once the year is over.
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct GoalStake {
+    target_books: u64,
+    amount: U128,
+    staked_at: U64,
+    /// Books moved to Finished with `update_book` since staking, up to `target_books`.
+    finished_book_ids: Vec<BookId>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct GoalProgress {
+    year: u32,
+    target_books: u64,
+    completed: u64,
+    /// Capped at 100 once the goal is met.
+    percentage: u8,
+}
+
+#[near_bindgen]
+impl Contract {
+    /// Sets the caller's reading challenge for `year`. Progress counts the books they finish
+    /// that year, including finish dates backfilled later.
+    pub fn set_reading_goal(&mut self, year: u32, target_books: u64) -> GoalProgress {
+        self.assert_can_mutate();
+        let account_id = self.internal_caller_id();
+        self.internal_set_reading_goal(account_id, year, target_books)
+    }
+
+    /// Sets the caller's goal for `year` like `set_reading_goal` and stakes the attached
+    /// deposit on it. Only books moved to Finished with `update_book` during that year after
+    /// staking count; books added as already finished don't.
+    #[payable]
+    pub fn stake_on_goal(&mut self, year: u32, target_books: u64) -> GoalStake {
+        self.assert_can_mutate();
+        let account_id = env::predecessor_account_id();
+        let amount = env::attached_deposit();
+        assert!(amount > 0, "Requires attached deposit");
+        assert!(
+            self.internal_config().goal_stake_beneficiary.is_some(),
+            "No goal stake beneficiary is configured"
+        );
+        assert!(
+            year >= year_of(env::block_timestamp()),
+            "Cannot stake on a past year"
+        );
+        self.internal_set_reading_goal(account_id.clone(), year, target_books);
+
+        let initial_storage = env::storage_usage();
+        let stake = GoalStake {
+            target_books,
+            amount: U128(amount),
+            staked_at: env::block_timestamp().into(),
+            finished_book_ids: vec![],
+        };
+        self.goal_stakes.insert(&(account_id.clone(), year), &stake);
+        self.goal_stakes_by_year
+            .insert(&(year, account_id.clone()), &());
+        self.internal_charge_storage(&account_id, initial_storage);
+
+        stake
+    }
+
+    /// Settles a stake once its year is over. Anyone can call it. Returns whether the goal was
+    /// met and the stake refunded. A missed stake is refunded too if the beneficiary has since
+    /// been removed from the config.
+    pub fn finalize_goal_stake(&mut self, account_id: ValidAccountId, year: u32) -> bool {
+        self.assert_can_mutate();
+        let account_id: AccountId = account_id.into();
+        assert!(
+            year < year_of(env::block_timestamp()),
+            "The goal's year is not over yet"
+        );
+        self.internal_finalize_goal_stake(account_id, year)
+    }
+
+    pub fn get_goal_stake(&self, account_id: ValidAccountId, year: u32) -> Option<GoalStake> {
+        self.goal_stakes.get(&(account_id.into(), year))
+    }
+
+    pub fn get_goal_progress(&self, account_id: ValidAccountId, year: u32) -> Option<GoalProgress> {
+        let account_id: AccountId = account_id.into();
+        self.reading_goals
+            .get(&(account_id.clone(), year))
+            .map(|target_books| self.internal_goal_progress(account_id, year, target_books))
+    }
+}
+
+impl Contract {
+    fn internal_finalize_goal_stake(&mut self, account_id: AccountId, year: u32) -> bool {
+        let initial_storage = env::storage_usage();
+        let stake = self
+            .goal_stakes
+            .remove(&(account_id.clone(), year))
+            .expect("Goal is not staked");
+        self.goal_stakes_by_year.remove(&(year, account_id.clone()));
+        self.internal_charge_storage(&account_id, initial_storage);
+
+        let met = stake.finished_book_ids.len() as u64 >= stake.target_books;
+        let receiver_id = match self.internal_config().goal_stake_beneficiary {
+            Some(beneficiary_id) if !met => beneficiary_id,
+            _ => account_id,
+        };
+        Promise::new(receiver_id).transfer(stake.amount.0);
+        met
+    }
+
+    /// Settles up to `limit` stakes whose year is over, oldest year first. Returns how many
+    /// were settled.
+    pub(crate) fn internal_settle_due_goal_stakes(&mut self, limit: u64) -> u64 {
+        let current_year = year_of(env::block_timestamp());
+        let mut settled = 0;
+        while settled < limit {
+            match self.goal_stakes_by_year.min() {
+                Some((year, account_id)) if year < current_year => {
+                    self.internal_finalize_goal_stake(account_id, year);
+                    settled += 1;
+                }
+                _ => break,
+            }
+        }
+        settled
+    }
+
+    fn internal_set_reading_goal(
+        &mut self,
+        account_id: AccountId,
+        year: u32,
+        target_books: u64,
+    ) -> GoalProgress {
+        let initial_storage = env::storage_usage();
+        assert!(target_books > 0, "Target must be at least one book");
+        assert!(
+            self.goal_stakes.get(&(account_id.clone(), year)).is_none(),
+            "Goal is staked and cannot be changed"
+        );
+
+        self.reading_goals
+            .insert(&(account_id.clone(), year), &target_books);
+        self.internal_charge_storage(&account_id, initial_storage);
+
+        self.internal_goal_progress(account_id, year, target_books)
+    }
+
+    /// Counts a book `update_book` just moved to Finished toward the owner's stake on the
+    /// current year. Each book counts once.
+    pub(crate) fn internal_record_goal_stake_finish(
+        &mut self,
+        account_id: &AccountId,
+        book_id: &BookId,
+    ) {
+        let key = (account_id.clone(), year_of(env::block_timestamp()));
+        if let Some(mut stake) = self.goal_stakes.get(&key) {
+            if (stake.finished_book_ids.len() as u64) < stake.target_books
+                && !stake.finished_book_ids.contains(book_id)
+            {
+                stake.finished_book_ids.push(book_id.clone());
+                self.goal_stakes.insert(&key, &stake);
+            }
+        }
+    }
+
+    fn internal_goal_progress(
+        &self,
+        account_id: AccountId,
+        year: u32,
+        target_books: u64,
+    ) -> GoalProgress {
+        let completed = self
+            .finished_by_owner_year
+            .get(&(account_id, year))
+            .map_or(0, |counters| counters.finished);
+        GoalProgress {
+            year,
+            target_books,
+            completed,
+            percentage: (completed * 100 / target_books).min(100) as u8,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{get_context, get_contract, get_sample_book};
+    use near_sdk::testing_env;
+    use near_sdk::MockedBlockchain;
+    use std::convert::TryFrom;
+
+    #[test]
+    fn test_goal_progress() {
+        let context = get_context(vec![], false);
+        testing_env!(context);
+        let mut contract = get_contract();
+        let year = year_of(env::block_timestamp());
+        contract.set_reading_goal(year, 4);
+        let book_id = contract.add_book(get_sample_book());
+        contract.update_book(book_id, Status::Finished, None);
+
+        let progress = contract
+            .get_goal_progress(ValidAccountId::try_from("carol_near").unwrap(), year)
+            .unwrap();
+        assert_eq!(progress.completed, 1);
+        assert_eq!(progress.percentage, 25);
+    }
+
+    #[test]
+    fn test_goal_stake_refunded() {
+        let mut context = get_context(vec![], false);
+        context.account_balance = 10u128.pow(24);
+        context.attached_deposit = 1_000;
+        testing_env!(context.clone());
+        let mut contract = get_contract();
+        let mut config = contract.get_config();
+        config.goal_stake_beneficiary = Some("charity_near".to_string());
+        contract.internal_set_config(&config);
+        let year = year_of(env::block_timestamp());
+        contract.stake_on_goal(year, 1);
+        let book_id = contract.add_book(get_sample_book());
+        contract.update_book(book_id, Status::Finished, None);
+
+        context.attached_deposit = 0;
+        context.block_timestamp += 366 * 24 * 60 * 60 * 1_000_000_000;
+        context.storage_usage = env::storage_usage();
+        testing_env!(context);
+        let carol = ValidAccountId::try_from("carol_near").unwrap();
+        assert!(contract.finalize_goal_stake(carol.clone(), year));
+        assert!(contract.get_goal_stake(carol, year).is_none());
+    }
+
+    #[test]
+    fn test_goal_stake_missed() {
+        let mut context = get_context(vec![], false);
+        context.account_balance = 10u128.pow(24);
+        context.attached_deposit = 1_000;
+        testing_env!(context.clone());
+        let mut contract = get_contract();
+        let mut config = contract.get_config();
+        config.goal_stake_beneficiary = Some("charity_near".to_string());
+        contract.internal_set_config(&config);
+        let year = year_of(env::block_timestamp());
+        contract.stake_on_goal(year, 1);
+        // Books added as already finished do not count.
+        contract.add_book(Book {
+            status: Status::Finished,
+            ..get_sample_book()
+        });
+
+        context.attached_deposit = 0;
+        context.block_timestamp += 366 * 24 * 60 * 60 * 1_000_000_000;
+        context.storage_usage = env::storage_usage();
+        testing_env!(context);
+        assert!(
+            !contract.finalize_goal_stake(ValidAccountId::try_from("carol_near").unwrap(), year)
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Goal is staked and cannot be changed")]
+    fn test_change_staked_goal() {
+        let mut context = get_context(vec![], false);
+        context.attached_deposit = 1_000;
+        testing_env!(context);
+        let mut contract = get_contract();
+        let mut config = contract.get_config();
+        config.goal_stake_beneficiary = Some("charity_near".to_string());
+        contract.internal_set_config(&config);
+        let year = year_of(env::block_timestamp());
+        contract.stake_on_goal(year, 5);
+        contract.set_reading_goal(year, 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "No goal stake beneficiary is configured")]
+    fn test_stake_without_beneficiary() {
+        let mut context = get_context(vec![], false);
+        context.attached_deposit = 1_000;
+        testing_env!(context);
+        let mut contract = get_contract();
+        contract.stake_on_goal(year_of(env::block_timestamp()), 1);
+    }
+}
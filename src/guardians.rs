@@ -0,0 +1,302 @@
+use crate::*;
+
+const MAX_GUARDIANS: usize = 10;
+
+/// Accounts that can together move a library to a recovery account.
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct Guardians {
+    pub guardian_ids: Vec<AccountId>,
+    pub threshold: u32,
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct Recovery {
+    pub recovery_account_id: AccountId,
+    pub approvals: Vec<AccountId>,
+    pub started_at: U64,
+}
+
+#[near_bindgen]
+impl Contract {
+    /// Names the caller's guardians, replacing any earlier ones; an empty list removes them.
+    /// Requires one yoctoNEAR so a function-call access key cannot change them.
+    #[payable]
+    pub fn set_guardians(&mut self, guardian_ids: Vec<ValidAccountId>, threshold: u32) {
+        self.assert_can_mutate();
+        assert_one_yocto();
+        let account_id = env::predecessor_account_id();
+        let initial_storage = env::storage_usage();
+
+        if guardian_ids.is_empty() {
+            self.guardians_by_owner_id.remove(&account_id);
+        } else {
+            let mut guardians = Guardians {
+                guardian_ids: guardian_ids.into_iter().map(|id| id.into()).collect(),
+                threshold,
+            };
+            guardians.guardian_ids.sort();
+            guardians.guardian_ids.dedup();
+            assert!(
+                guardians.guardian_ids.len() <= MAX_GUARDIANS,
+                "At most {} guardians",
+                MAX_GUARDIANS
+            );
+            assert!(
+                !guardians.guardian_ids.contains(&account_id),
+                "Cannot be your own guardian"
+            );
+            assert!(
+                threshold > 0 && threshold as usize <= guardians.guardian_ids.len(),
+                "Threshold must be between 1 and the number of guardians"
+            );
+            self.guardians_by_owner_id.insert(&account_id, &guardians);
+        }
+        self.internal_charge_storage(&account_id, initial_storage);
+    }
+
+    pub fn get_guardians(&self, account_id: ValidAccountId) -> Option<Guardians> {
+        self.guardians_by_owner_id.get(account_id.as_ref())
+    }
+
+    /// Starts moving `account_id`'s library to `recovery_account_id`, counting as the calling
+    /// guardian's approval. Replaces a recovery in progress that only its starter has approved,
+    /// so a single guardian cannot hold the account's recovery hostage.
+    pub fn start_recovery(
+        &mut self,
+        account_id: ValidAccountId,
+        recovery_account_id: ValidAccountId,
+    ) {
+        self.assert_can_mutate();
+        let guardian_id = env::predecessor_account_id();
+        let account_id: AccountId = account_id.into();
+        self.assert_guardian(&account_id, &guardian_id);
+        assert!(
+            self.recoveries
+                .get(&account_id)
+                .is_none_or(|recovery| recovery.approvals.len() < 2),
+            "Recovery is already approved by other guardians"
+        );
+        let initial_storage = env::storage_usage();
+
+        self.recoveries.insert(
+            &account_id,
+            &Recovery {
+                recovery_account_id: recovery_account_id.into(),
+                approvals: vec![guardian_id],
+                started_at: env::block_timestamp().into(),
+            },
+        );
+        self.internal_charge_storage(&account_id, initial_storage);
+    }
+
+    pub fn approve_recovery(&mut self, account_id: ValidAccountId) {
+        self.assert_can_mutate();
+        let guardian_id = env::predecessor_account_id();
+        let account_id: AccountId = account_id.into();
+        self.assert_guardian(&account_id, &guardian_id);
+        let initial_storage = env::storage_usage();
+
+        let mut recovery = self
+            .recoveries
+            .get(&account_id)
+            .expect("No recovery in progress");
+        if !recovery.approvals.contains(&guardian_id) {
+            recovery.approvals.push(guardian_id);
+            self.recoveries.insert(&account_id, &recovery);
+        }
+        self.internal_charge_storage(&account_id, initial_storage);
+    }
+
+    /// Stops a recovery of the caller's library, e.g. one started without their consent.
+    #[payable]
+    pub fn cancel_recovery(&mut self) {
+        self.assert_can_mutate();
+        assert_one_yocto();
+        let account_id = env::predecessor_account_id();
+        let initial_storage = env::storage_usage();
+        self.recoveries
+            .remove(&account_id)
+            .expect("No recovery in progress");
+        self.internal_charge_storage(&account_id, initial_storage);
+    }
+
+    /// Moves up to `limit` of the library's books to the recovery account once the timelock
+    /// has passed and enough of the current guardians approve. Anyone can call it, again while
+    /// `has_more` is set; the recovery ends once the whole library has moved.
+    pub fn finish_recovery(
+        &mut self,
+        account_id: ValidAccountId,
+        limit: Option<u64>,
+    ) -> TransferProgress {
+        self.assert_can_mutate();
+        let account_id: AccountId = account_id.into();
+        let recovery = self
+            .recoveries
+            .get(&account_id)
+            .expect("No recovery in progress");
+        assert!(
+            env::block_timestamp()
+                >= recovery.started_at.0 + self.internal_config().recovery_timelock.0,
+            "Recovery is still timelocked"
+        );
+        let guardians = self
+            .guardians_by_owner_id
+            .get(&account_id)
+            .expect("Account has no guardians");
+        let approvals = recovery
+            .approvals
+            .iter()
+            .filter(|guardian_id| guardians.guardian_ids.contains(guardian_id))
+            .count();
+        assert!(
+            approvals >= guardians.threshold as usize,
+            "Recovery needs {} approvals",
+            guardians.threshold
+        );
+
+        let progress =
+            self.internal_transfer_library(&account_id, &recovery.recovery_account_id, limit);
+        if !progress.has_more {
+            let initial_storage = env::storage_usage();
+            self.recoveries.remove(&account_id);
+            self.internal_charge_storage(&account_id, initial_storage);
+        }
+        progress
+    }
+
+    pub fn get_recovery(&self, account_id: ValidAccountId) -> Option<Recovery> {
+        self.recoveries.get(account_id.as_ref())
+    }
+}
+
+impl Contract {
+    fn assert_guardian(&self, account_id: &AccountId, guardian_id: &AccountId) {
+        let is_guardian = self
+            .guardians_by_owner_id
+            .get(account_id)
+            .is_some_and(|guardians| guardians.guardian_ids.contains(guardian_id));
+        assert!(
+            is_guardian,
+            "Only a guardian of the account can call this method"
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{get_context, get_contract, get_sample_book};
+    use near_sdk::testing_env;
+    use near_sdk::MockedBlockchain;
+    use near_sdk::VMContext;
+    use std::convert::TryFrom;
+
+    fn start_recovery(context: &mut VMContext) -> Contract {
+        context.attached_deposit = 1;
+        testing_env!(context.clone());
+        let mut contract = get_contract();
+        contract.add_book(get_sample_book());
+        contract.set_guardians(
+            vec![
+                ValidAccountId::try_from("dave_near").unwrap(),
+                ValidAccountId::try_from("erin_near").unwrap(),
+            ],
+            2,
+        );
+
+        context.predecessor_account_id = "dave_near".to_string();
+        context.storage_usage = env::storage_usage();
+        testing_env!(context.clone());
+        contract.start_recovery(
+            ValidAccountId::try_from("carol_near").unwrap(),
+            ValidAccountId::try_from("frank_near").unwrap(),
+        );
+        contract
+    }
+
+    #[test]
+    fn test_recovery() {
+        let mut context = get_context(vec![], false);
+        let mut contract = start_recovery(&mut context);
+
+        context.predecessor_account_id = "carol_near".to_string();
+        context.storage_usage = env::storage_usage();
+        testing_env!(context.clone());
+        contract.add_book(get_sample_book());
+
+        context.predecessor_account_id = "erin_near".to_string();
+        context.block_timestamp = contract.get_config().recovery_timelock.0;
+        context.storage_usage = env::storage_usage();
+        testing_env!(context);
+        let carol = ValidAccountId::try_from("carol_near").unwrap();
+        contract.approve_recovery(carol.clone());
+        let progress = contract.finish_recovery(carol.clone(), Some(1));
+        assert_eq!(progress.moved, 1);
+        assert!(progress.has_more);
+        assert!(contract.get_recovery(carol.clone()).is_some());
+
+        let progress = contract.finish_recovery(carol.clone(), Some(1));
+        assert_eq!(progress.moved, 1);
+        assert!(!progress.has_more);
+        assert_eq!(contract.get_books_count(carol.clone()), 0);
+        assert_eq!(
+            contract.get_books_count(ValidAccountId::try_from("frank_near").unwrap()),
+            2
+        );
+        assert!(contract.get_recovery(carol).is_none());
+    }
+
+    #[test]
+    fn test_replace_unapproved_recovery() {
+        let mut context = get_context(vec![], false);
+        let mut contract = start_recovery(&mut context);
+
+        context.predecessor_account_id = "erin_near".to_string();
+        context.storage_usage = env::storage_usage();
+        testing_env!(context.clone());
+        let carol = ValidAccountId::try_from("carol_near").unwrap();
+        contract.start_recovery(
+            carol.clone(),
+            ValidAccountId::try_from("erin_near").unwrap(),
+        );
+        let recovery = contract.get_recovery(carol.clone()).unwrap();
+        assert_eq!(recovery.recovery_account_id, "erin_near".to_string());
+        assert_eq!(recovery.approvals, vec!["erin_near".to_string()]);
+
+        context.predecessor_account_id = "dave_near".to_string();
+        context.storage_usage = env::storage_usage();
+        testing_env!(context);
+        contract.approve_recovery(carol.clone());
+        assert_eq!(contract.get_recovery(carol).unwrap().approvals.len(), 2);
+    }
+
+    #[test]
+    #[should_panic(expected = "Recovery is already approved by other guardians")]
+    fn test_cannot_replace_approved_recovery() {
+        let mut context = get_context(vec![], false);
+        let mut contract = start_recovery(&mut context);
+
+        context.predecessor_account_id = "erin_near".to_string();
+        context.storage_usage = env::storage_usage();
+        testing_env!(context);
+        let carol = ValidAccountId::try_from("carol_near").unwrap();
+        contract.approve_recovery(carol.clone());
+        contract.start_recovery(carol, ValidAccountId::try_from("erin_near").unwrap());
+    }
+
+    #[test]
+    #[should_panic(expected = "Recovery is still timelocked")]
+    fn test_recovery_timelocked() {
+        let mut context = get_context(vec![], false);
+        let mut contract = start_recovery(&mut context);
+
+        context.predecessor_account_id = "erin_near".to_string();
+        context.storage_usage = env::storage_usage();
+        testing_env!(context);
+        let carol = ValidAccountId::try_from("carol_near").unwrap();
+        contract.approve_recovery(carol.clone());
+        contract.finish_recovery(carol, None);
+    }
+}
@@ -0,0 +1,160 @@
+use crate::*;
+
+const LEADERBOARD_SIZE: usize = 100;
+const DEFAULT_LEADERBOARD_LIMIT: u64 = 10;
+
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, Copy)]
+#[serde(crate = "near_sdk::serde")]
+pub enum LeaderboardPeriod {
+    AllTime,
+    /// Books finished in a calendar year (UTC).
+    Year(u32),
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Clone, Copy)]
+pub enum LeaderboardMetric {
+    BooksFinished,
+    PagesRead,
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct LeaderboardEntry {
+    account_id: AccountId,
+    score: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct Leaderboard {
+    books_finished: Vec<LeaderboardEntry>,
+    pages_read: Vec<LeaderboardEntry>,
+}
+
+#[near_bindgen]
+impl Contract {
+    /// Top readers by books finished and by pages read, highest first; ties keep whoever got
+    /// there first.
+    pub fn get_leaderboard(&self, period: LeaderboardPeriod, limit: Option<u64>) -> Leaderboard {
+        let limit = limit.unwrap_or(DEFAULT_LEADERBOARD_LIMIT) as usize;
+        let top = |metric: LeaderboardMetric| {
+            let mut entries = self.leaderboards.get(&(period, metric)).unwrap_or_default();
+            entries.truncate(limit);
+            entries
+        };
+        Leaderboard {
+            books_finished: top(LeaderboardMetric::BooksFinished),
+            pages_read: top(LeaderboardMetric::PagesRead),
+        }
+    }
+}
+
+impl Contract {
+    /// Re-ranks `account_id` on the all-time boards and on `year`'s, after a finished book was
+    /// added to or removed from its stats. Each board keeps its top `LEADERBOARD_SIZE`
+    /// readers; a reader who dropped off comes back the next time their stats change.
+    pub(crate) fn internal_update_leaderboards(
+        &mut self,
+        account_id: &AccountId,
+        year: Option<u32>,
+    ) {
+        let finished = self
+            .books_by_owner_status
+            .get(&(account_id.clone(), Status::Finished))
+            .map_or(0, |book_ids| book_ids.len());
+        let pages_read = self
+            .counters_by_owner_id
+            .get(account_id)
+            .map_or(0, |counters| counters.pages_read);
+        self.internal_rank(LeaderboardPeriod::AllTime, account_id, finished, pages_read);
+
+        if let Some(year) = year {
+            let counters = self
+                .finished_by_owner_year
+                .get(&(account_id.clone(), year))
+                .unwrap_or_default();
+            self.internal_rank(
+                LeaderboardPeriod::Year(year),
+                account_id,
+                counters.finished,
+                counters.pages_read,
+            );
+        }
+    }
+
+    fn internal_rank(
+        &mut self,
+        period: LeaderboardPeriod,
+        account_id: &AccountId,
+        finished: u64,
+        pages_read: u64,
+    ) {
+        for (metric, score) in [
+            (LeaderboardMetric::BooksFinished, finished),
+            (LeaderboardMetric::PagesRead, pages_read),
+        ] {
+            let key = (period, metric);
+            let mut entries = self.leaderboards.get(&key).unwrap_or_default();
+            entries.retain(|entry| &entry.account_id != account_id);
+            if score > 0 {
+                entries.push(LeaderboardEntry {
+                    account_id: account_id.clone(),
+                    score,
+                });
+                entries.sort_by_key(|entry| cmp::Reverse(entry.score));
+                entries.truncate(LEADERBOARD_SIZE);
+            }
+            if entries.is_empty() {
+                self.leaderboards.remove(&key);
+            } else {
+                self.leaderboards.insert(&key, &entries);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{get_context, get_contract, get_sample_book};
+    use near_sdk::testing_env;
+    use near_sdk::MockedBlockchain;
+
+    #[test]
+    fn test_leaderboard() {
+        let mut context = get_context(vec![], false);
+        testing_env!(context.clone());
+        let mut contract = get_contract();
+        let finished_book = || Book {
+            status: Status::Finished,
+            pages: Some(100),
+            ..get_sample_book()
+        };
+        contract.add_book(finished_book());
+        let book_id = contract.add_book(finished_book());
+
+        context.predecessor_account_id = "dave_near".to_string();
+        context.storage_usage = env::storage_usage();
+        testing_env!(context.clone());
+        contract.add_book(Book {
+            pages: Some(500),
+            ..finished_book()
+        });
+
+        let leaderboard = contract.get_leaderboard(LeaderboardPeriod::AllTime, None);
+        assert_eq!(leaderboard.books_finished[0].account_id, "carol_near");
+        assert_eq!(leaderboard.books_finished[0].score, 2);
+        assert_eq!(leaderboard.pages_read[0].account_id, "dave_near");
+
+        context.predecessor_account_id = "carol_near".to_string();
+        context.storage_usage = env::storage_usage();
+        testing_env!(context);
+        contract.update_book(book_id, Status::Read, None);
+        let year = year_of(env::block_timestamp());
+        let leaderboard = contract.get_leaderboard(LeaderboardPeriod::Year(year), Some(1));
+        assert_eq!(leaderboard.books_finished.len(), 1);
+        assert_eq!(leaderboard.books_finished[0].account_id, "dave_near");
+        assert_eq!(leaderboard.books_finished[0].score, 1);
+        assert_eq!(leaderboard.pages_read[0].score, 500);
+    }
+}
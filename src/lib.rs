@@ -1,15 +1,155 @@
 use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
-use near_sdk::collections::{UnorderedMap, UnorderedSet};
-use near_sdk::json_types::ValidAccountId;
+use near_sdk::collections::{LazyOption, TreeMap, UnorderedMap, UnorderedSet, Vector};
+use near_sdk::json_types::{ValidAccountId, U64};
 use near_sdk::serde::{Deserialize, Serialize};
-use near_sdk::{env, near_bindgen, setup_alloc, AccountId, BorshStorageKey};
+use near_sdk::{
+    assert_one_yocto, env, near_bindgen, setup_alloc, AccountId, Balance, BorshStorageKey, Gas,
+    PanicOnDefault,
+};
 use std::cmp;
 
+pub use crate::achievements::*;
+pub use crate::activity::*;
+pub use crate::approvals::*;
+pub use crate::auctions::*;
+pub use crate::badges::*;
+pub use crate::batch::*;
+pub use crate::bookmarks::*;
+pub use crate::borrow_requests::*;
+pub use crate::buddy_reads::*;
+pub use crate::certificates::*;
+pub use crate::challenges::*;
+pub use crate::clubs::*;
+pub use crate::comments::*;
+pub use crate::community::*;
+pub use crate::compare::*;
+pub use crate::config::*;
+pub use crate::cron::*;
+pub use crate::curriculum::*;
+pub use crate::edit::*;
+pub use crate::events::*;
+pub use crate::export::*;
+pub use crate::filter::*;
+pub use crate::gifts::*;
+pub use crate::goals::*;
+pub use crate::guardians::*;
+pub use crate::leaderboards::*;
+pub use crate::loans::*;
+pub use crate::marketplace::*;
+pub use crate::merkle::*;
+pub use crate::metadata::*;
+pub use crate::moderation::*;
+pub use crate::nft::*;
+pub use crate::owners::*;
+pub use crate::pagination::*;
+pub use crate::profiles::*;
+pub use crate::proposals::*;
+pub use crate::quotes::*;
+pub use crate::rate_limit::*;
+pub use crate::recommendations::*;
+pub use crate::related::*;
+pub use crate::reports::*;
+pub use crate::reviews::*;
+pub use crate::rewards::*;
+pub use crate::roles::*;
+pub use crate::royalties::*;
+pub use crate::shared_shelves::*;
+pub use crate::snapshots::*;
+pub use crate::sort::*;
+pub use crate::source::*;
+pub use crate::stats::*;
+pub use crate::storage::*;
+pub use crate::summary::*;
+pub use crate::swaps::*;
+pub use crate::transfer::*;
+pub use crate::undo::*;
+pub use crate::visibility::*;
+pub use crate::wipe::*;
+pub use crate::wishlist::*;
+
+mod achievements;
+mod activity;
+mod approvals;
+mod archive;
+mod auctions;
+mod badges;
+mod batch;
+mod blocklist;
+mod bookmarks;
+mod borrow_requests;
+mod buddy_reads;
+mod certificates;
+mod challenges;
+mod clubs;
+mod comments;
+mod community;
+mod compare;
+mod config;
+mod cron;
+mod curriculum;
+mod duplicates;
+mod edit;
+mod editors;
+mod enumeration;
+mod events;
+mod export;
+mod featured;
+mod filter;
+mod follows;
+mod gifts;
+mod goals;
+mod guardians;
+mod leaderboards;
+mod loans;
+mod marketplace;
+mod merkle;
+mod metadata;
+mod migration;
+mod moderation;
+mod nft;
+mod owners;
+mod pagination;
+mod pause;
+mod profiles;
+mod proposals;
+mod quota;
+mod quotes;
+mod rate_limit;
+mod recommendations;
+mod related;
+mod reports;
+mod reviews;
+mod rewards;
+mod roles;
+mod royalties;
+mod search;
+mod sessions;
+mod shared_shelves;
+mod sharing;
+mod snapshots;
+mod sort;
+mod source;
+mod stats;
+mod storage;
+mod summary;
+mod swaps;
+mod sync;
+mod tips;
+mod transfer;
+mod undo;
+mod visibility;
+mod wipe;
+mod wishlist;
+
 setup_alloc!();
 
 pub type BookId = String;
 
-#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize)]
+const MAX_RATING: u8 = 5;
+
+#[derive(
+    BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, Copy, PartialEq, Debug,
+)]
 #[serde(crate = "near_sdk::serde")]
 pub enum Status {
     List,
@@ -17,7 +157,17 @@ pub enum Status {
     Finished,
 }
 
-#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize)]
+#[derive(
+    BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, Copy, PartialEq, Debug,
+)]
+#[serde(crate = "near_sdk::serde")]
+pub enum Format {
+    Print,
+    Ebook,
+    Audiobook,
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone)]
 #[serde(crate = "near_sdk::serde")]
 pub struct Book {
     book_id: Option<BookId>,
@@ -25,137 +175,904 @@ pub struct Book {
     title: String,
     description: String,
     status: Status,
-    image: String,
+    images: Vec<String>,
+    pages: Option<u64>,
+    rating: Option<u8>,
+    author: Option<String>,
+    /// NEAR account of the author, who receives `tip_author` payments.
+    author_account_id: Option<AccountId>,
+    format: Option<Format>,
+    language: Option<String>,
+    isbn: Option<String>,
+    #[serde(default)]
+    tags: Vec<String>,
+    created_at: Option<U64>,
+    updated_at: Option<U64>,
+    finished_at: Option<U64>,
+    /// Set when `finished_at` was supplied by the owner, e.g. when importing reading history,
+    /// rather than stamped by the contract.
+    #[serde(default)]
+    finished_at_self_reported: bool,
+    #[serde(default)]
+    visibility: Visibility,
+}
+
+fn assert_valid_rating(rating: u8) {
+    assert!(
+        (1..=MAX_RATING).contains(&rating),
+        "Rating must be between 1 and {}",
+        MAX_RATING
+    );
+}
+
+fn assert_valid_finished_at(finished_at: u64) {
+    assert!(
+        finished_at <= env::block_timestamp(),
+        "Finished date cannot be in the future"
+    );
+}
+
+impl Book {
+    pub(crate) fn touch(&mut self) {
+        self.updated_at = Some(env::block_timestamp().into());
+    }
+
+    fn assert_valid(&self, config: &Config) {
+        assert!(
+            self.title.len() <= config.max_title_length as usize,
+            "Title exceeds {} characters",
+            config.max_title_length
+        );
+        assert!(
+            self.description.len() <= config.max_description_length as usize,
+            "Description exceeds {} characters",
+            config.max_description_length
+        );
+        if let Some(rating) = self.rating {
+            assert_valid_rating(rating);
+        }
+        if let Some(author_account_id) = &self.author_account_id {
+            assert!(
+                env::is_valid_account_id(author_account_id.as_bytes()),
+                "Author account id is invalid"
+            );
+        }
+        for image in &self.images {
+            assert!(
+                image.starts_with("https://") || image.starts_with("ipfs://"),
+                "Image must be an https:// or ipfs:// URI"
+            );
+            assert!(
+                image.len() <= config.max_image_uri_length as usize,
+                "Image URI exceeds {} characters",
+                config.max_image_uri_length
+            );
+        }
+    }
 }
 
 #[near_bindgen]
-#[derive(BorshDeserialize, BorshSerialize)]
+#[derive(BorshDeserialize, BorshSerialize, PanicOnDefault)]
 pub struct Contract {
+    owner_id: AccountId,
+    config: LazyOption<Config>,
+    paused: bool,
     books_by_owner_id: UnorderedMap<AccountId, UnorderedSet<BookId>>,
+    books_by_owner_status: UnorderedMap<(AccountId, Status), UnorderedSet<BookId>>,
+    titles_by_owner_id: UnorderedMap<AccountId, TreeMap<String, BookId>>,
     books: UnorderedMap<BookId, Book>,
     books_len: u64,
+    bookmarks_by_book_id: UnorderedMap<BookId, Vec<Bookmark>>,
+    curriculum_by_book_id: UnorderedMap<BookId, Curriculum>,
+    archived_books_by_owner_id: UnorderedMap<AccountId, UnorderedSet<BookId>>,
+    archived_books: UnorderedMap<BookId, Book>,
+    related_by_book_id: UnorderedMap<BookId, Vec<(BookId, RelationKind)>>,
+    certificates: UnorderedMap<CertificateId, Certificate>,
+    certificates_by_recipient_id: UnorderedMap<AccountId, UnorderedSet<CertificateId>>,
+    certificates_len: u64,
+    pending_deletions: UnorderedMap<BookId, PendingDeletion>,
+    pending_actions_by_owner_id: UnorderedMap<AccountId, Vec<PendingAction>>,
+    counters_by_owner_id: UnorderedMap<AccountId, AccountCounters>,
+    finished_by_owner_year: UnorderedMap<(AccountId, u32), YearCounters>,
+    finished_books_by_owner_year: UnorderedMap<(AccountId, u32), UnorderedSet<BookId>>,
+    snapshots_by_owner_id: UnorderedMap<AccountId, Vec<Snapshot>>,
+    recent_activity: Vector<Activity>,
+    recent_activity_head: u64,
+    updated_by_owner_id: UnorderedMap<AccountId, TreeMap<String, BookId>>,
+    storage_accounts: UnorderedMap<AccountId, StorageAccount>,
+    approvals_by_book_id: UnorderedMap<BookId, Approvals>,
+    last_reward_by_owner_id: UnorderedMap<AccountId, u64>,
+    rewarded_book_ids: UnorderedSet<BookId>,
+    badges_by_owner_id: UnorderedMap<AccountId, Vec<Badge>>,
+    royalties_by_book_id: UnorderedMap<BookId, Royalty>,
+    last_finalized_period: Option<u64>,
+    sessions_by_owner_id: UnorderedMap<AccountId, Vec<AccountId>>,
+    trusted_forwarders: Vec<AccountId>,
+    roles_by_account_id: UnorderedMap<AccountId, Vec<Role>>,
+    editors_by_owner_id: UnorderedMap<AccountId, Vec<AccountId>>,
+    blocked_accounts: UnorderedSet<AccountId>,
+    writes_by_account_id: UnorderedMap<AccountId, WriteWindow>,
+    council: Option<Council>,
+    proposals: UnorderedMap<u64, Proposal>,
+    proposals_len: u64,
+    featured_book_ids: UnorderedSet<BookId>,
+    guardians_by_owner_id: UnorderedMap<AccountId, Guardians>,
+    recoveries: UnorderedMap<AccountId, Recovery>,
+    shares_by_book_id: UnorderedMap<BookId, Vec<AccountId>>,
+    shared_books_by_reader_id: UnorderedMap<AccountId, UnorderedSet<BookId>>,
+    reviews: UnorderedMap<ReviewId, Review>,
+    reviews_by_book_id: UnorderedMap<BookId, UnorderedSet<ReviewId>>,
+    review_ids_by_book_reviewer: UnorderedMap<(BookId, AccountId), ReviewId>,
+    reviews_len: u64,
+    ratings_by_catalog_id: UnorderedMap<String, RatingCounters>,
+    activity_by_account_id: UnorderedMap<AccountId, Vec<Activity>>,
+    following_by_account_id: UnorderedMap<AccountId, UnorderedSet<AccountId>>,
+    followers_by_account_id: UnorderedMap<AccountId, UnorderedSet<AccountId>>,
+    profiles_by_account_id: UnorderedMap<AccountId, Profile>,
+    upvoters_by_review_id: UnorderedMap<ReviewId, UnorderedSet<AccountId>>,
+    comments: UnorderedMap<CommentId, Comment>,
+    comments_by_book_id: UnorderedMap<BookId, UnorderedSet<CommentId>>,
+    comments_len: u64,
+    recommendations: UnorderedMap<RecommendationId, Recommendation>,
+    recommendations_by_account_id: UnorderedMap<AccountId, UnorderedSet<RecommendationId>>,
+    recommendations_len: u64,
+    shared_shelves: UnorderedMap<SharedShelfId, SharedShelf>,
+    books_by_shared_shelf_id: UnorderedMap<SharedShelfId, UnorderedSet<BookId>>,
+    shared_shelves_len: u64,
+    clubs: UnorderedMap<ClubId, Club>,
+    clubs_len: u64,
+    buddy_reads: UnorderedMap<BuddyReadId, BuddyRead>,
+    buddy_read_ids_by_book_id: UnorderedMap<BookId, BuddyReadId>,
+    buddy_reads_len: u64,
+    leaderboards: UnorderedMap<(LeaderboardPeriod, LeaderboardMetric), Vec<LeaderboardEntry>>,
+    reading_goals: UnorderedMap<(AccountId, u32), u64>,
+    challenges: UnorderedMap<ChallengeId, Challenge>,
+    challenges_len: u64,
+    achievements_by_account_id: UnorderedMap<AccountId, Vec<EarnedAchievement>>,
+    explored_tags_by_account_id: UnorderedMap<AccountId, Vec<String>>,
+    gifts: UnorderedMap<GiftId, Gift>,
+    gifts_by_account_id: UnorderedMap<AccountId, UnorderedSet<GiftId>>,
+    gifts_len: u64,
+    swaps: UnorderedMap<SwapId, Swap>,
+    swaps_by_account_id: UnorderedMap<AccountId, UnorderedSet<SwapId>>,
+    swaps_len: u64,
+    wishlist_items: UnorderedMap<WishlistItemId, WishlistItem>,
+    wishlist_by_account_id: UnorderedMap<AccountId, UnorderedSet<WishlistItemId>>,
+    wishlist_items_len: u64,
+    reports: UnorderedMap<(ReportTarget, AccountId), Report>,
+    reporters_by_target: UnorderedMap<ReportTarget, UnorderedSet<AccountId>>,
+    hidden_targets: UnorderedSet<ReportTarget>,
+    quotes: UnorderedMap<QuoteId, Quote>,
+    quotes_by_account_id: UnorderedMap<AccountId, UnorderedSet<QuoteId>>,
+    public_quote_ids: UnorderedSet<QuoteId>,
+    quotes_len: u64,
+    loans: UnorderedMap<BookId, Loan>,
+    loans_by_account_id: UnorderedMap<AccountId, UnorderedSet<BookId>>,
+    borrow_requests: UnorderedMap<BorrowRequestId, BorrowRequest>,
+    borrow_requests_by_account_id: UnorderedMap<AccountId, UnorderedSet<BorrowRequestId>>,
+    borrow_requests_len: u64,
+    sales: UnorderedMap<BookId, Sale>,
+    sales_by_seller_id: UnorderedMap<AccountId, UnorderedSet<BookId>>,
+    auctions: UnorderedMap<BookId, Auction>,
+    review_tips_by_account_id: UnorderedMap<AccountId, Balance>,
+    goal_stakes: UnorderedMap<(AccountId, u32), GoalStake>,
+    challenge_ids_by_account_id: UnorderedMap<AccountId, Vec<ChallengeId>>,
+    challenge_progress: UnorderedMap<(ChallengeId, AccountId), Vec<BookId>>,
+    /// Open challenges by deadline, so `finalize_period` can close them earliest first.
+    challenge_ids_by_deadline: TreeMap<(u64, ChallengeId), ()>,
+    /// Unsettled goal stakes by year, so `finalize_period` can settle them oldest first.
+    goal_stakes_by_year: TreeMap<(u32, AccountId), ()>,
+    pending_transfers: UnorderedMap<(AccountId, BookId), PendingTransfer>,
+    /// Undoable actions by when they happened and the account that can undo them.
+    pending_actions_by_deadline: TreeMap<(u64, AccountId, PendingAction), ()>,
+    /// Finished books with a page count per account and year, ordered by pages.
+    finished_pages_by_owner_year: UnorderedMap<(AccountId, u32), TreeMap<(u64, BookId), ()>>,
+    /// Tip fees collected and not yet withdrawn by the owner.
+    tip_fees: Balance,
+    /// Books by lowercased tag, across all libraries and per owner, for tag filters.
+    books_by_tag: UnorderedMap<String, UnorderedSet<BookId>>,
+    books_by_owner_tag: UnorderedMap<(AccountId, String), UnorderedSet<BookId>>,
+    /// Books converted so far while `migrate_books` is upgrading books stored by an older
+    /// version; `None` when no migration is running.
+    migrated_books_len: Option<u64>,
 }
 
 #[derive(BorshStorageKey, BorshSerialize)]
 pub enum StorageKey {
     BooksByOwner,
     Books,
-    BooksPerOwner { account_hash: Vec<u8> },
+    BooksPerOwner {
+        account_hash: Vec<u8>,
+    },
+    BookmarksByBook,
+    CurriculumByBook,
+    ArchivedBooksByOwner,
+    ArchivedBooks,
+    ArchivedBooksPerOwner {
+        account_hash: Vec<u8>,
+    },
+    RelatedByBook,
+    Certificates,
+    CertificatesByRecipient,
+    CertificatesPerRecipient {
+        account_hash: Vec<u8>,
+    },
+    BooksByOwnerStatus,
+    BooksPerOwnerStatus {
+        account_hash: Vec<u8>,
+        status: Status,
+    },
+    TitlesByOwner,
+    TitlesPerOwner {
+        account_hash: Vec<u8>,
+    },
+    PendingDeletions,
+    PendingActionsByOwner,
+    CountersByOwner,
+    FinishedByOwnerYear,
+    SnapshotsByOwner,
+    RecentActivity,
+    FinishedBooksByOwnerYear,
+    FinishedBooksPerOwnerYear {
+        account_hash: Vec<u8>,
+        year: u32,
+    },
+    UpdatedByOwner,
+    UpdatedPerOwner {
+        account_hash: Vec<u8>,
+    },
+    StorageAccounts,
+    ApprovalsByBook,
+    LastRewardByOwner,
+    RewardedBooks,
+    BadgesByOwner,
+    RoyaltiesByBook,
+    SessionsByOwner,
+    RolesByAccount,
+    EditorsByOwner,
+    BlockedAccounts,
+    WritesByAccount,
+    Proposals,
+    FeaturedBooks,
+    GuardiansByOwner,
+    Recoveries,
+    SharesByBook,
+    SharedBooksByReader,
+    SharedBooksPerReader {
+        account_hash: Vec<u8>,
+    },
+    Config,
+    Reviews,
+    ReviewsByBook,
+    ReviewsPerBook {
+        book_hash: Vec<u8>,
+    },
+    ReviewIdsByBookReviewer,
+    RatingsByCatalog,
+    ActivityByAccount,
+    FollowingByAccount,
+    FollowingPerAccount {
+        account_hash: Vec<u8>,
+    },
+    FollowersByAccount,
+    FollowersPerAccount {
+        account_hash: Vec<u8>,
+    },
+    ProfilesByAccount,
+    UpvotersByReview,
+    UpvotersPerReview {
+        review_hash: Vec<u8>,
+    },
+    Comments,
+    CommentsByBook,
+    CommentsPerBook {
+        book_hash: Vec<u8>,
+    },
+    Recommendations,
+    RecommendationsByAccount,
+    RecommendationsPerAccount {
+        account_hash: Vec<u8>,
+    },
+    SharedShelves,
+    BooksBySharedShelf,
+    BooksPerSharedShelf {
+        shelf_hash: Vec<u8>,
+    },
+    Clubs,
+    MembersPerClub {
+        club_hash: Vec<u8>,
+    },
+    BuddyReads,
+    BuddyReadIdsByBook,
+    Leaderboards,
+    ReadingGoals,
+    Challenges,
+    ParticipantsPerChallenge {
+        challenge_hash: Vec<u8>,
+    },
+    AchievementsByAccount,
+    ExploredTagsByAccount,
+    Gifts,
+    GiftsByAccount,
+    GiftsPerAccount {
+        account_hash: Vec<u8>,
+    },
+    Swaps,
+    SwapsByAccount,
+    SwapsPerAccount {
+        account_hash: Vec<u8>,
+    },
+    WishlistItems,
+    WishlistByAccount,
+    WishlistPerAccount {
+        account_hash: Vec<u8>,
+    },
+    Reports,
+    ReportersByTarget,
+    ReportersPerTarget {
+        target_hash: Vec<u8>,
+    },
+    HiddenTargets,
+    Quotes,
+    QuotesByAccount,
+    QuotesPerAccount {
+        account_hash: Vec<u8>,
+    },
+    PublicQuoteIds,
+    Loans,
+    LoansByAccount,
+    LoansPerAccount {
+        account_hash: Vec<u8>,
+    },
+    BorrowRequests,
+    BorrowRequestsByAccount,
+    BorrowRequestsPerAccount {
+        account_hash: Vec<u8>,
+    },
+    Sales,
+    SalesBySeller,
+    SalesPerSeller {
+        account_hash: Vec<u8>,
+    },
+    Auctions,
+    ReviewTipsByAccount,
+    GoalStakes,
+    ChallengesByAccount,
+    ChallengeProgress,
+    ChallengesByDeadline,
+    GoalStakesByYear,
+    PendingTransfers,
+    PendingActionsByDeadline,
+    FinishedPagesByOwnerYear,
+    FinishedPagesPerOwnerYear {
+        account_hash: Vec<u8>,
+        year: u32,
+    },
+    BooksByTag,
+    BooksPerTag {
+        tag_hash: Vec<u8>,
+    },
+    BooksByOwnerTag,
+    BooksPerOwnerTag {
+        account_hash: Vec<u8>,
+        tag_hash: Vec<u8>,
+    },
+    StateVersion,
 }
 
-impl Default for Contract {
-    fn default() -> Self {
+#[near_bindgen]
+impl Contract {
+    #[init]
+    pub fn new(owner_id: ValidAccountId, config: Config) -> Self {
+        assert!(!env::state_exists(), "Already initialized");
+        Self::internal_new(owner_id.into(), config)
+    }
+}
+
+impl Contract {
+    pub(crate) fn internal_new(owner_id: AccountId, config: Config) -> Self {
+        config.assert_valid();
+        migration::internal_set_state_version(migration::STATE_VERSION);
         Self {
+            owner_id,
+            config: LazyOption::new(StorageKey::Config, Some(&config)),
+            paused: false,
             books_by_owner_id: UnorderedMap::new(StorageKey::BooksByOwner),
+            books_by_owner_status: UnorderedMap::new(StorageKey::BooksByOwnerStatus),
+            titles_by_owner_id: UnorderedMap::new(StorageKey::TitlesByOwner),
             books: UnorderedMap::new(StorageKey::Books),
             books_len: 0,
+            bookmarks_by_book_id: UnorderedMap::new(StorageKey::BookmarksByBook),
+            curriculum_by_book_id: UnorderedMap::new(StorageKey::CurriculumByBook),
+            archived_books_by_owner_id: UnorderedMap::new(StorageKey::ArchivedBooksByOwner),
+            archived_books: UnorderedMap::new(StorageKey::ArchivedBooks),
+            related_by_book_id: UnorderedMap::new(StorageKey::RelatedByBook),
+            certificates: UnorderedMap::new(StorageKey::Certificates),
+            certificates_by_recipient_id: UnorderedMap::new(StorageKey::CertificatesByRecipient),
+            certificates_len: 0,
+            pending_deletions: UnorderedMap::new(StorageKey::PendingDeletions),
+            pending_actions_by_owner_id: UnorderedMap::new(StorageKey::PendingActionsByOwner),
+            counters_by_owner_id: UnorderedMap::new(StorageKey::CountersByOwner),
+            finished_by_owner_year: UnorderedMap::new(StorageKey::FinishedByOwnerYear),
+            finished_books_by_owner_year: UnorderedMap::new(StorageKey::FinishedBooksByOwnerYear),
+            snapshots_by_owner_id: UnorderedMap::new(StorageKey::SnapshotsByOwner),
+            recent_activity: Vector::new(StorageKey::RecentActivity),
+            recent_activity_head: 0,
+            updated_by_owner_id: UnorderedMap::new(StorageKey::UpdatedByOwner),
+            storage_accounts: UnorderedMap::new(StorageKey::StorageAccounts),
+            approvals_by_book_id: UnorderedMap::new(StorageKey::ApprovalsByBook),
+            last_reward_by_owner_id: UnorderedMap::new(StorageKey::LastRewardByOwner),
+            rewarded_book_ids: UnorderedSet::new(StorageKey::RewardedBooks),
+            badges_by_owner_id: UnorderedMap::new(StorageKey::BadgesByOwner),
+            royalties_by_book_id: UnorderedMap::new(StorageKey::RoyaltiesByBook),
+            last_finalized_period: None,
+            sessions_by_owner_id: UnorderedMap::new(StorageKey::SessionsByOwner),
+            trusted_forwarders: vec![],
+            roles_by_account_id: UnorderedMap::new(StorageKey::RolesByAccount),
+            editors_by_owner_id: UnorderedMap::new(StorageKey::EditorsByOwner),
+            blocked_accounts: UnorderedSet::new(StorageKey::BlockedAccounts),
+            writes_by_account_id: UnorderedMap::new(StorageKey::WritesByAccount),
+            council: None,
+            proposals: UnorderedMap::new(StorageKey::Proposals),
+            proposals_len: 0,
+            featured_book_ids: UnorderedSet::new(StorageKey::FeaturedBooks),
+            guardians_by_owner_id: UnorderedMap::new(StorageKey::GuardiansByOwner),
+            recoveries: UnorderedMap::new(StorageKey::Recoveries),
+            shares_by_book_id: UnorderedMap::new(StorageKey::SharesByBook),
+            shared_books_by_reader_id: UnorderedMap::new(StorageKey::SharedBooksByReader),
+            reviews: UnorderedMap::new(StorageKey::Reviews),
+            reviews_by_book_id: UnorderedMap::new(StorageKey::ReviewsByBook),
+            review_ids_by_book_reviewer: UnorderedMap::new(StorageKey::ReviewIdsByBookReviewer),
+            reviews_len: 0,
+            ratings_by_catalog_id: UnorderedMap::new(StorageKey::RatingsByCatalog),
+            activity_by_account_id: UnorderedMap::new(StorageKey::ActivityByAccount),
+            following_by_account_id: UnorderedMap::new(StorageKey::FollowingByAccount),
+            followers_by_account_id: UnorderedMap::new(StorageKey::FollowersByAccount),
+            profiles_by_account_id: UnorderedMap::new(StorageKey::ProfilesByAccount),
+            upvoters_by_review_id: UnorderedMap::new(StorageKey::UpvotersByReview),
+            comments: UnorderedMap::new(StorageKey::Comments),
+            comments_by_book_id: UnorderedMap::new(StorageKey::CommentsByBook),
+            comments_len: 0,
+            recommendations: UnorderedMap::new(StorageKey::Recommendations),
+            recommendations_by_account_id: UnorderedMap::new(StorageKey::RecommendationsByAccount),
+            recommendations_len: 0,
+            shared_shelves: UnorderedMap::new(StorageKey::SharedShelves),
+            books_by_shared_shelf_id: UnorderedMap::new(StorageKey::BooksBySharedShelf),
+            shared_shelves_len: 0,
+            clubs: UnorderedMap::new(StorageKey::Clubs),
+            clubs_len: 0,
+            buddy_reads: UnorderedMap::new(StorageKey::BuddyReads),
+            buddy_read_ids_by_book_id: UnorderedMap::new(StorageKey::BuddyReadIdsByBook),
+            buddy_reads_len: 0,
+            leaderboards: UnorderedMap::new(StorageKey::Leaderboards),
+            reading_goals: UnorderedMap::new(StorageKey::ReadingGoals),
+            challenges: UnorderedMap::new(StorageKey::Challenges),
+            challenges_len: 0,
+            achievements_by_account_id: UnorderedMap::new(StorageKey::AchievementsByAccount),
+            explored_tags_by_account_id: UnorderedMap::new(StorageKey::ExploredTagsByAccount),
+            gifts: UnorderedMap::new(StorageKey::Gifts),
+            gifts_by_account_id: UnorderedMap::new(StorageKey::GiftsByAccount),
+            gifts_len: 0,
+            swaps: UnorderedMap::new(StorageKey::Swaps),
+            swaps_by_account_id: UnorderedMap::new(StorageKey::SwapsByAccount),
+            swaps_len: 0,
+            wishlist_items: UnorderedMap::new(StorageKey::WishlistItems),
+            wishlist_by_account_id: UnorderedMap::new(StorageKey::WishlistByAccount),
+            wishlist_items_len: 0,
+            reports: UnorderedMap::new(StorageKey::Reports),
+            reporters_by_target: UnorderedMap::new(StorageKey::ReportersByTarget),
+            hidden_targets: UnorderedSet::new(StorageKey::HiddenTargets),
+            quotes: UnorderedMap::new(StorageKey::Quotes),
+            quotes_by_account_id: UnorderedMap::new(StorageKey::QuotesByAccount),
+            public_quote_ids: UnorderedSet::new(StorageKey::PublicQuoteIds),
+            quotes_len: 0,
+            loans: UnorderedMap::new(StorageKey::Loans),
+            loans_by_account_id: UnorderedMap::new(StorageKey::LoansByAccount),
+            borrow_requests: UnorderedMap::new(StorageKey::BorrowRequests),
+            borrow_requests_by_account_id: UnorderedMap::new(StorageKey::BorrowRequestsByAccount),
+            borrow_requests_len: 0,
+            sales: UnorderedMap::new(StorageKey::Sales),
+            sales_by_seller_id: UnorderedMap::new(StorageKey::SalesBySeller),
+            auctions: UnorderedMap::new(StorageKey::Auctions),
+            review_tips_by_account_id: UnorderedMap::new(StorageKey::ReviewTipsByAccount),
+            goal_stakes: UnorderedMap::new(StorageKey::GoalStakes),
+            challenge_ids_by_account_id: UnorderedMap::new(StorageKey::ChallengesByAccount),
+            challenge_progress: UnorderedMap::new(StorageKey::ChallengeProgress),
+            challenge_ids_by_deadline: TreeMap::new(StorageKey::ChallengesByDeadline),
+            goal_stakes_by_year: TreeMap::new(StorageKey::GoalStakesByYear),
+            pending_transfers: UnorderedMap::new(StorageKey::PendingTransfers),
+            pending_actions_by_deadline: TreeMap::new(StorageKey::PendingActionsByDeadline),
+            finished_pages_by_owner_year: UnorderedMap::new(StorageKey::FinishedPagesByOwnerYear),
+            tip_fees: 0,
+            books_by_tag: UnorderedMap::new(StorageKey::BooksByTag),
+            books_by_owner_tag: UnorderedMap::new(StorageKey::BooksByOwnerTag),
+            migrated_books_len: None,
         }
     }
 }
 
 #[near_bindgen]
 impl Contract {
-    pub fn add_book(&mut self, mut book: Book) -> BookId {
-        let account_id = env::predecessor_account_id();
-
-        let current_book_id = format!("{}", self.books_len + 1);
-
-        book.book_id = Some(current_book_id.clone());
-        book.account_id = Some(account_id.clone());
-        self.books.insert(&current_book_id, &book);
-
-        let books_by_owner = self.books_by_owner_id.get(&account_id);
-        match books_by_owner {
-            Some(mut book_ids) => {
-                book_ids.insert(&current_book_id);
-                self.books_by_owner_id.insert(&account_id, &book_ids);
-            }
-            None => {
-                let mut book_ids: UnorderedSet<BookId> =
-                    UnorderedSet::new(StorageKey::BooksPerOwner {
-                        account_hash: env::sha256(&account_id.as_bytes()),
-                    });
-                book_ids.insert(&current_book_id);
-                self.books_by_owner_id.insert(&account_id, &book_ids);
-            }
-        }
-
-        self.books_len += 1;
-
-        return current_book_id;
+    pub fn add_book(&mut self, book: Book) -> BookId {
+        self.assert_can_mutate();
+        let account_id = self.internal_caller_id();
+        self.internal_add_book(&account_id, book)
     }
 
-    pub fn update_book(&mut self, book_id: BookId, status: Status) -> Option<Book> {
-        let account_id = env::predecessor_account_id();
+    /// Moves a book to another shelf. Editors of the owner's library and accounts approved
+    /// with `nft_approve` may do this on the owner's behalf.
+    pub fn update_book(
+        &mut self,
+        book_id: BookId,
+        status: Status,
+        approval_id: Option<u64>,
+    ) -> Option<Book> {
+        self.assert_can_mutate();
+        let account_id = self.internal_authorize_edit(&book_id, approval_id);
+        let initial_storage = env::storage_usage();
 
-        let book_ids = self.books_by_owner_id.get(&account_id).unwrap();
+        let book_ids = self
+            .books_by_owner_id
+            .get(&account_id)
+            .expect("Book does not exist");
         if book_ids.contains(&book_id) {
             let mut book = self.books.get(&book_id).unwrap();
+            self.internal_remove_book_from_status(&account_id, &book_id, book.status);
+            self.internal_add_book_to_status(&account_id, &book_id, status);
+            self.internal_remove_from_stats(&account_id, &book);
+            let finishing = status == Status::Finished && book.status != Status::Finished;
+            if finishing {
+                book.finished_at = Some(env::block_timestamp().into());
+                book.finished_at_self_reported = false;
+            }
             book.status = status;
+            self.internal_touch_book(&account_id, &mut book);
+            self.internal_add_to_stats(&account_id, &book);
             self.books.insert(&book_id, &book);
-            return Some(book);
+            if finishing {
+                self.internal_record_activity(ActivityKind::BookFinished, &account_id, &book);
+                self.internal_reward_finish(&account_id, &book_id);
+                self.internal_check_milestones(&account_id);
+                self.internal_check_achievements(&account_id, &book);
+                self.internal_check_buddy_read(&book_id);
+                self.internal_record_challenge_finish(&account_id, &book_id);
+                self.internal_record_goal_stake_finish(&account_id, &book_id);
+            }
+            self.internal_charge_book_update(&account_id, &book_id, initial_storage);
+
+            Some(book)
         } else {
             panic!("Book does not exist");
         }
     }
 
+    /// Records a historical finish date on one of the caller's finished books. The date is
+    /// flagged as self-reported.
+    pub fn backfill_finished_at(&mut self, book_id: BookId, finished_at: U64) -> Book {
+        self.assert_can_mutate();
+        let account_id = self.internal_library_owner(&book_id);
+        let initial_storage = env::storage_usage();
+        assert_valid_finished_at(finished_at.0);
+
+        let mut book = self.books.get(&book_id).unwrap();
+        assert_eq!(book.status, Status::Finished, "Book is not finished");
+        self.internal_remove_from_stats(&account_id, &book);
+        book.finished_at = Some(finished_at);
+        book.finished_at_self_reported = true;
+        self.internal_touch_book(&account_id, &mut book);
+        self.internal_add_to_stats(&account_id, &book);
+        self.books.insert(&book_id, &book);
+        self.internal_charge_book_update(&account_id, &book_id, initial_storage);
+
+        book
+    }
+
+    pub fn rate_book(&mut self, book_id: BookId, rating: u8, approval_id: Option<u64>) -> Book {
+        self.assert_can_mutate();
+        let account_id = self.internal_authorize_edit(&book_id, approval_id);
+        let initial_storage = env::storage_usage();
+        assert_valid_rating(rating);
+
+        let mut book = self.books.get(&book_id).unwrap();
+        self.internal_remove_from_stats(&account_id, &book);
+        book.rating = Some(rating);
+        self.internal_touch_book(&account_id, &mut book);
+        self.internal_add_to_stats(&account_id, &book);
+        self.books.insert(&book_id, &book);
+        self.internal_charge_book_update(&account_id, &book_id, initial_storage);
+
+        book
+    }
+
+    /// Deletes one of the caller's books. The deletion can be reverted with `undo_last_action`
+    /// until the undo window passes and `finalize_actions` makes it permanent. Requires one
+    /// yoctoNEAR so a function-call access key cannot delete books unconfirmed.
+    #[payable]
     pub fn delete_book(&mut self, book_id: BookId) -> Option<Book> {
-        let account_id = env::predecessor_account_id();
+        self.assert_can_mutate();
+        assert_one_yocto();
+        let account_id = self.internal_caller_id();
+        let initial_storage = env::storage_usage();
 
-        let book_ids = self.books_by_owner_id.get(&account_id).unwrap();
+        let book_ids = self
+            .books_by_owner_id
+            .get(&account_id)
+            .expect("Book does not exist");
         if book_ids.contains(&book_id) {
+            self.assert_not_on_loan(&book_id);
+            self.assert_not_at_auction(&book_id);
             let book = self.books.remove(&book_id);
+            if let Some(book) = &book {
+                self.internal_remove_book_from_owner(&account_id, &book_id, book);
+                self.internal_remove_sale(&book_id);
+                self.internal_queue_deletion(&account_id, &book_id, book);
+            }
+            let storage = self.internal_settle_storage(&account_id, initial_storage);
+            if book.is_some() {
+                emit_book_event(
+                    BookEventKind::BookDelete,
+                    &account_id,
+                    std::slice::from_ref(&book_id),
+                    storage,
+                );
+                emit_nft_event(NftEvent::Burn([NftMintData {
+                    owner_id: &account_id,
+                    token_ids: &[book_id],
+                    storage,
+                }]));
+            }
 
-            let mut book_ids = self.books_by_owner_id.get(&account_id).unwrap();
-            book_ids.remove(&book_id);
-            self.books_by_owner_id.insert(&account_id, &book_ids);
-
-            return book;
+            book
         } else {
             panic!("Book does not exist");
         }
     }
 
+    /// Pages through all books, or one account's books. Without `sort`, books come in the order
+    /// they were added and `order: Desc` lists the newest first. Removing a book moves the last
+    /// entry of the list into its slot.
     pub fn get_books(
-        self,
+        &self,
         account_id: Option<ValidAccountId>,
-        skip: u64,
+        from_index: Option<u64>,
         limit: Option<u64>,
-    ) -> Option<Vec<Book>> {
-        let limit = limit.map(|v| v as usize).unwrap_or(usize::MAX);
-        assert_ne!(limit, 0, "Cannot provide limit of 0.");
+        sort: Option<SortBy>,
+        order: Option<SortOrder>,
+        filter: Option<BookFilter>,
+    ) -> Page<Book> {
+        let order = order.unwrap_or(SortOrder::Asc);
+        if let Some(sort) = sort {
+            let mut books = self.internal_filter_books_to_sort(
+                account_id.map(|account_id| account_id.into()),
+                &filter.unwrap_or_default(),
+            );
+            sort_books(&mut books, sort, order);
+            return Page::from_vec(books, from_index, limit, Some);
+        }
+        if let Some(filter) = filter {
+            return self.internal_filter_books(
+                account_id.map(|account_id| account_id.into()),
+                &filter,
+                from_index,
+                limit,
+                order,
+            );
+        }
 
+        match account_id {
+            Some(account_id) => match self.books_by_owner_id.get(&account_id.to_string()) {
+                Some(book_ids) => Page::from_vector_ordered(
+                    book_ids.as_vector(),
+                    from_index,
+                    limit,
+                    order,
+                    |book_id| self.internal_get_listed_book(&book_id),
+                ),
+                None => Page::empty(),
+            },
+            None => Page::from_vector_ordered(
+                self.books.values_as_vector(),
+                from_index,
+                limit,
+                order,
+                |book| Some(book).filter(|book| self.internal_is_listed(book)),
+            ),
+        }
+    }
 
-        if account_id.is_none() {
-            let skip = cmp::min(self.books.len(), skip);
+    pub fn get_books_by_status(
+        &self,
+        account_id: ValidAccountId,
+        status: Status,
+        from_index: Option<u64>,
+        limit: Option<u64>,
+        order: Option<SortOrder>,
+    ) -> Page<Book> {
+        match self.books_by_owner_status.get(&(account_id.into(), status)) {
+            Some(book_ids) => Page::from_vector_ordered(
+                book_ids.as_vector(),
+                from_index,
+                limit,
+                order.unwrap_or(SortOrder::Asc),
+                |book_id| self.internal_get_listed_book(&book_id),
+            ),
+            None => Page::empty(),
+        }
+    }
 
-            return Some(
-                self.books
-                    .iter()
-                    .skip(skip as usize)
-                    .take(limit as usize)
-                    .map(|(_, book)| book)
-                    .collect(),
-            );
+    /// Picks a pseudo-random book from an account's books with the given status using the
+    /// block's random seed.
+    pub fn pick_random(&self, account_id: ValidAccountId, status: Status) -> Option<Book> {
+        let book_ids = self
+            .books_by_owner_status
+            .get(&(account_id.into(), status))?;
+        if book_ids.is_empty() {
+            return None;
         }
 
-        let book_ids: UnorderedSet<BookId> = self
-            .books_by_owner_id
-            .get(&account_id.unwrap().to_string())
-            .unwrap_or(UnorderedSet::new("".as_bytes()));
+        let seed = env::random_seed().iter().fold(0u64, |acc, byte| {
+            acc.wrapping_mul(256).wrapping_add(*byte as u64)
+        });
+        let book_id = book_ids.as_vector().get(seed % book_ids.len())?;
+        self.internal_get_listed_book(&book_id)
+    }
+
+    /// Resolves several books in one call; ids that don't exist map to `None`.
+    pub fn get_books_by_ids(&self, ids: Vec<BookId>) -> Vec<Option<Book>> {
+        ids.iter()
+            .map(|book_id| self.internal_get_visible_book(book_id))
+            .collect()
+    }
 
-        let skip = cmp::min(self.books.len(), skip);
+    pub fn get_total_books(&self) -> u64 {
+        self.books.len()
+    }
 
-        return book_ids
-            .iter()
-            .skip(skip as usize)
-            .take(limit)
-            .map(|book_id| self.books.get(&book_id))
-            .collect();
+    pub fn get_books_count(&self, account_id: ValidAccountId) -> u64 {
+        self.books_by_owner_id
+            .get(account_id.as_ref())
+            .map(|book_ids| book_ids.len())
+            .unwrap_or(0)
     }
 
-    pub fn get_book(self, book_id: BookId) -> Book {
-        self.books.get(&book_id).expect("Book does not exist")
+    pub fn get_book(&self, book_id: BookId) -> Book {
+        self.internal_get_visible_book(&book_id)
+            .expect("Book does not exist")
+    }
+}
+
+impl Contract {
+    pub(crate) fn internal_add_book(&mut self, account_id: &AccountId, book: Book) -> BookId {
+        let initial_storage = env::storage_usage();
+
+        book.assert_valid(&self.internal_config());
+        self.assert_book_quota(account_id, 1);
+
+        let current_book_id = format!("{}", self.books_len + 1);
+
+        let now = env::block_timestamp();
+        let (finished_at, finished_at_self_reported) = match (book.status, book.finished_at) {
+            (Status::Finished, Some(finished_at)) => {
+                assert_valid_finished_at(finished_at.0);
+                (Some(finished_at), true)
+            }
+            (Status::Finished, None) => (Some(now.into()), false),
+            _ => (None, false),
+        };
+        let book = Book {
+            book_id: Some(current_book_id.clone()),
+            account_id: Some(account_id.clone()),
+            created_at: Some(now.into()),
+            updated_at: Some(now.into()),
+            finished_at,
+            finished_at_self_reported,
+            ..book
+        };
+        self.books.insert(&current_book_id, &book);
+        self.internal_add_book_to_owner(account_id, &current_book_id, &book);
+        self.internal_record_activity(ActivityKind::BookAdded, account_id, &book);
+
+        self.books_len += 1;
+        if book.status == Status::Finished {
+            self.internal_check_milestones(account_id);
+            self.internal_check_achievements(account_id, &book);
+        }
+        let storage = self.internal_settle_storage(account_id, initial_storage);
+        emit_book_event(
+            BookEventKind::BookAdd,
+            account_id,
+            std::slice::from_ref(&current_book_id),
+            storage,
+        );
+        emit_nft_event(NftEvent::Mint([NftMintData {
+            owner_id: account_id,
+            token_ids: std::slice::from_ref(&current_book_id),
+            storage,
+        }]));
+
+        current_book_id
+    }
+
+    pub(crate) fn internal_add_book_to_owner(
+        &mut self,
+        account_id: &AccountId,
+        book_id: &BookId,
+        book: &Book,
+    ) {
+        let mut book_ids = self.books_by_owner_id.get(account_id).unwrap_or_else(|| {
+            UnorderedSet::new(StorageKey::BooksPerOwner {
+                account_hash: env::sha256(account_id.as_bytes()),
+            })
+        });
+        book_ids.insert(book_id);
+        self.books_by_owner_id.insert(account_id, &book_ids);
+        self.internal_add_book_to_status(account_id, book_id, book.status);
+        self.internal_index_title(account_id, book_id, &book.title);
+        self.internal_index_updated(account_id, book);
+        self.internal_index_tags(account_id, book_id, &book.tags);
+        self.internal_add_to_stats(account_id, book);
+    }
+
+    pub(crate) fn internal_remove_book_from_owner(
+        &mut self,
+        account_id: &AccountId,
+        book_id: &BookId,
+        book: &Book,
+    ) {
+        if let Some(mut book_ids) = self.books_by_owner_id.get(account_id) {
+            book_ids.remove(book_id);
+            if book_ids.is_empty() {
+                self.books_by_owner_id.remove(account_id);
+            } else {
+                self.books_by_owner_id.insert(account_id, &book_ids);
+            }
+        }
+        self.internal_remove_book_from_status(account_id, book_id, book.status);
+        self.internal_unindex_title(account_id, book_id, &book.title);
+        self.internal_unindex_updated(account_id, book);
+        self.internal_unindex_tags(account_id, book_id, &book.tags);
+        self.internal_remove_from_stats(account_id, book);
+    }
+
+    pub(crate) fn internal_add_book_to_status(
+        &mut self,
+        account_id: &AccountId,
+        book_id: &BookId,
+        status: Status,
+    ) {
+        let key = (account_id.clone(), status);
+        let mut book_ids = self.books_by_owner_status.get(&key).unwrap_or_else(|| {
+            UnorderedSet::new(StorageKey::BooksPerOwnerStatus {
+                account_hash: env::sha256(account_id.as_bytes()),
+                status,
+            })
+        });
+        book_ids.insert(book_id);
+        self.books_by_owner_status.insert(&key, &book_ids);
+    }
+
+    pub(crate) fn internal_remove_book_from_status(
+        &mut self,
+        account_id: &AccountId,
+        book_id: &BookId,
+        status: Status,
+    ) {
+        let key = (account_id.clone(), status);
+        if let Some(mut book_ids) = self.books_by_owner_status.get(&key) {
+            book_ids.remove(book_id);
+            self.books_by_owner_status.insert(&key, &book_ids);
+        }
+    }
+
+    pub(crate) fn assert_book_owner(&self, account_id: &AccountId, book_id: &BookId) {
+        let is_owner = self
+            .books_by_owner_id
+            .get(account_id)
+            .map(|book_ids| book_ids.contains(book_id))
+            .unwrap_or(false);
+        if !is_owner {
+            panic!("Book does not exist");
+        }
     }
 }
 
@@ -164,9 +1081,10 @@ mod tests {
     use super::*;
     use near_sdk::MockedBlockchain;
     use near_sdk::{testing_env, VMContext};
+    use std::convert::TryFrom;
 
     // mock the context for testing, notice "signer_account_id" that was accessed above from env::
-    fn get_context(input: Vec<u8>, is_view: bool) -> VMContext {
+    pub(crate) fn get_context(input: Vec<u8>, is_view: bool) -> VMContext {
         VMContext {
             current_account_id: "alice_near".to_string(),
             signer_account_id: "bob_near".to_string(),
@@ -187,24 +1105,65 @@ mod tests {
         }
     }
 
-    #[test]
-    fn test_add_book() {
-        let context = get_context(vec![], false);
-        testing_env!(context);
-        let mut contract = Contract::default();
-        let book_id = contract.add_book(Book {
+    /// A fresh contract owned by its own account, alice_near.
+    pub(crate) fn get_new_contract() -> Contract {
+        Contract::new(
+            ValidAccountId::try_from("alice_near").unwrap(),
+            Config::default(),
+        )
+    }
+
+    /// A fresh contract where the test accounts already have a storage deposit.
+    pub(crate) fn get_contract() -> Contract {
+        let mut contract = get_new_contract();
+        for account_id in &["carol_near", "dave_near", "erin_near", "frank_near"] {
+            contract.storage_accounts.insert(
+                &account_id.to_string(),
+                &StorageAccount {
+                    deposit: 10u128.pow(27),
+                    used_bytes: 0,
+                    accepts_transfers: true,
+                },
+            );
+        }
+        contract
+    }
+
+    pub(crate) fn get_sample_book() -> Book {
+        Book {
             book_id: None,
             account_id: None,
             description: "Tutorial for mechanics".to_string(),
-            image: "https://example.com".to_string(),
+            images: vec!["https://example.com".to_string()],
+            pages: Some(320),
+            rating: None,
+            author: None,
+            author_account_id: None,
+            format: None,
+            language: None,
+            isbn: None,
+            tags: vec![],
             status: Status::List,
             title: "Motorcycle Mechanics 101".to_string(),
-        });
+            created_at: None,
+            updated_at: None,
+            finished_at: None,
+            finished_at_self_reported: false,
+            visibility: Visibility::Public,
+        }
+    }
+
+    #[test]
+    fn test_add_book() {
+        let context = get_context(vec![], false);
+        testing_env!(context);
+        let mut contract = get_contract();
+        let book_id = contract.add_book(get_sample_book());
 
         let book = contract.get_book(book_id.clone());
         assert_eq!(book.book_id.unwrap(), book_id);
         assert_eq!(book.description, "Tutorial for mechanics".to_string());
-        assert_eq!(book.image, "https://example.com".to_string());
+        assert_eq!(book.images, vec!["https://example.com".to_string()]);
         assert_eq!(book.title, "Motorcycle Mechanics 101".to_string());
     }
 
@@ -212,36 +1171,253 @@ mod tests {
     fn test_update_book() {
         let context = get_context(vec![], false);
         testing_env!(context);
-        let mut contract = Contract::default();
+        let mut contract = get_contract();
+        let book_id = contract.add_book(get_sample_book());
+
+        contract.update_book(book_id, Status::Read, None);
+    }
+
+    #[test]
+    fn test_add_book_with_historical_finished_at() {
+        let mut context = get_context(vec![], false);
+        context.block_timestamp = 1_000;
+        testing_env!(context);
+        let mut contract = get_contract();
         let book_id = contract.add_book(Book {
-            book_id: None,
-            account_id: None,
-            description: "Tutorial for mechanics".to_string(),
-            image: "https://example.com".to_string(),
-            status: Status::List,
-            title: "Motorcycle Mechanics 101".to_string(),
+            status: Status::Finished,
+            finished_at: Some(U64(500)),
+            ..get_sample_book()
         });
 
-        contract.update_book(book_id, Status::Read);
+        let book = contract.get_book(book_id.clone());
+        assert_eq!(book.finished_at, Some(U64(500)));
+        assert!(book.finished_at_self_reported);
+
+        let book = contract.backfill_finished_at(book_id, U64(700));
+        assert_eq!(book.finished_at, Some(U64(700)));
     }
 
     #[test]
-    #[should_panic( expected = "Book does not exist" )]
-    fn test_delete_book() {
+    #[should_panic(expected = "Finished date cannot be in the future")]
+    fn test_backfill_finished_at_in_future() {
         let context = get_context(vec![], false);
         testing_env!(context);
-        let mut contract = Contract::default();
+        let mut contract = get_contract();
         let book_id = contract.add_book(Book {
-            book_id: None,
-            account_id: None,
-            description: "Tutorial for mechanics".to_string(),
-            image: "https://example.com".to_string(),
-            status: Status::List,
-            title: "Motorcycle Mechanics 101".to_string(),
+            status: Status::Finished,
+            ..get_sample_book()
         });
+        contract.backfill_finished_at(book_id, U64(1));
+    }
+
+    #[test]
+    fn test_get_books_cursor() {
+        let mut context = get_context(vec![], false);
+        testing_env!(context.clone());
+        let mut contract = get_contract();
+        contract.add_book(get_sample_book());
+
+        context.predecessor_account_id = "dave_near".to_string();
+        context.storage_usage = env::storage_usage();
+        testing_env!(context);
+        let first_id = contract.add_book(get_sample_book());
+        let second_id = contract.add_book(get_sample_book());
+        let third_id = contract.add_book(get_sample_book());
+
+        let account_id = ValidAccountId::try_from("dave_near").unwrap();
+        let page = contract.get_books(Some(account_id.clone()), None, Some(2), None, None, None);
+        assert_eq!(page.items.len(), 2);
+        assert_eq!(page.total, 3);
+        assert_eq!(page.items[0].book_id, Some(first_id));
+        assert_eq!(page.items[1].book_id, Some(second_id));
+        assert_eq!(page.next_cursor, Some(2));
+
+        let page = contract.get_books(
+            Some(account_id),
+            page.next_cursor,
+            Some(2),
+            None,
+            None,
+            None,
+        );
+        assert_eq!(page.items.len(), 1);
+        assert_eq!(page.items[0].book_id, Some(third_id.clone()));
+        assert_eq!(page.next_cursor, None);
+        assert!(!page.has_more);
+
+        let page = contract.get_books(
+            Some(ValidAccountId::try_from("dave_near").unwrap()),
+            None,
+            Some(1),
+            None,
+            Some(SortOrder::Desc),
+            None,
+        );
+        assert_eq!(page.items[0].book_id, Some(third_id));
+        assert_eq!(page.next_cursor, Some(1));
+    }
+
+    #[test]
+    fn test_get_books_by_ids() {
+        let context = get_context(vec![], false);
+        testing_env!(context);
+        let mut contract = get_contract();
+        let first_id = contract.add_book(get_sample_book());
+        let second_id = contract.add_book(get_sample_book());
+
+        let books = contract.get_books_by_ids(vec![second_id.clone(), "42".to_string(), first_id]);
+        assert_eq!(books.len(), 3);
+        assert_eq!(books[0].as_ref().unwrap().book_id, Some(second_id));
+        assert!(books[1].is_none());
+    }
+
+    #[test]
+    fn test_pick_random() {
+        let mut context = get_context(vec![], false);
+        context.random_seed = vec![0, 0, 5];
+        testing_env!(context);
+        let mut contract = get_contract();
+        contract.add_book(get_sample_book());
+        let second_id = contract.add_book(get_sample_book());
+        contract.add_book(Book {
+            status: Status::Finished,
+            ..get_sample_book()
+        });
+
+        let account_id = ValidAccountId::try_from("carol_near").unwrap();
+        let book = contract
+            .pick_random(account_id.clone(), Status::List)
+            .unwrap();
+        assert_eq!(book.book_id, Some(second_id));
+        assert!(contract.pick_random(account_id, Status::Read).is_none());
+    }
+
+    #[test]
+    fn test_get_books_count() {
+        let mut context = get_context(vec![], false);
+        context.attached_deposit = 1;
+        testing_env!(context.clone());
+        let mut contract = get_contract();
+        contract.add_book(get_sample_book());
+        let book_id = contract.add_book(get_sample_book());
+
+        context.predecessor_account_id = "dave_near".to_string();
+        context.storage_usage = env::storage_usage();
+        testing_env!(context.clone());
+        contract.add_book(get_sample_book());
+
+        context.predecessor_account_id = "carol_near".to_string();
+        context.storage_usage = env::storage_usage();
+        testing_env!(context);
+        contract.delete_book(book_id);
+
+        assert_eq!(contract.get_total_books(), 2);
+        assert_eq!(
+            contract.get_books_count(ValidAccountId::try_from("carol_near").unwrap()),
+            1
+        );
+        assert_eq!(
+            contract.get_books_count(ValidAccountId::try_from("erin_near").unwrap()),
+            0
+        );
+    }
+
+    #[test]
+    fn test_get_books_by_status() {
+        let mut context = get_context(vec![], false);
+        context.attached_deposit = 1;
+        testing_env!(context);
+        let mut contract = get_contract();
+        let reading_id = contract.add_book(get_sample_book());
+        contract.add_book(get_sample_book());
+        contract.update_book(reading_id.clone(), Status::Read, None);
+
+        let account_id = ValidAccountId::try_from("carol_near").unwrap();
+        let books = contract
+            .get_books_by_status(account_id.clone(), Status::Read, None, None, None)
+            .items;
+        assert_eq!(books.len(), 1);
+        assert_eq!(books[0].book_id, Some(reading_id.clone()));
+        assert_eq!(
+            contract
+                .get_books_by_status(account_id.clone(), Status::List, None, None, None)
+                .items
+                .len(),
+            1
+        );
+
+        contract.delete_book(reading_id);
+        assert_eq!(
+            contract
+                .get_books_by_status(account_id, Status::Read, None, None, None)
+                .items
+                .len(),
+            0
+        );
+    }
+
+    #[test]
+    fn test_get_books_sort_by_updated() {
+        let mut context = get_context(vec![], false);
+        testing_env!(context.clone());
+        let mut contract = get_contract();
+        let first_id = contract.add_book(get_sample_book());
+        let second_id = contract.add_book(get_sample_book());
+
+        context.block_timestamp = 100;
+        context.storage_usage = env::storage_usage();
+        testing_env!(context);
+        contract.update_book(first_id.clone(), Status::Read, None);
+
+        let books = contract
+            .get_books(
+                None,
+                None,
+                None,
+                Some(SortBy::DateUpdated),
+                Some(SortOrder::Desc),
+                None,
+            )
+            .items;
+        assert_eq!(books[0].book_id, Some(first_id));
+        assert_eq!(books[0].created_at, Some(U64(0)));
+        assert_eq!(books[0].updated_at, Some(U64(100)));
+        assert_eq!(books[1].book_id, Some(second_id));
+    }
+
+    #[test]
+    #[should_panic(expected = "Book does not exist")]
+    fn test_delete_book() {
+        let mut context = get_context(vec![], false);
+        context.attached_deposit = 1;
+        testing_env!(context);
+        let mut contract = get_contract();
+        let book_id = contract.add_book(get_sample_book());
 
         contract.delete_book(book_id.clone());
 
-        let book = contract.get_book(book_id.clone());
+        contract.get_book(book_id);
+    }
+
+    #[test]
+    #[should_panic(expected = "Requires attached deposit of exactly 1 yoctoNEAR")]
+    fn test_delete_book_requires_one_yocto() {
+        let context = get_context(vec![], false);
+        testing_env!(context);
+        let mut contract = get_contract();
+        let book_id = contract.add_book(get_sample_book());
+        contract.delete_book(book_id);
+    }
+
+    #[test]
+    #[should_panic(expected = "Image must be an https:// or ipfs:// URI")]
+    fn test_add_book_invalid_image() {
+        let context = get_context(vec![], false);
+        testing_env!(context);
+        let mut contract = get_contract();
+        contract.add_book(Book {
+            images: vec!["javascript:alert(1)".to_string()],
+            ..get_sample_book()
+        });
     }
 }
@@ -0,0 +1,191 @@
+use crate::*;
+
+/// A book lent to another reader. While on loan the book cannot be deleted, archived or
+/// transferred.
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct Loan {
+    lender_id: AccountId,
+    borrower_id: AccountId,
+    lent_at: u64,
+    due_at: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct LoanView {
+    book_id: BookId,
+    lender_id: AccountId,
+    borrower_id: AccountId,
+    lent_at: U64,
+    due_at: U64,
+    overdue: bool,
+}
+
+#[near_bindgen]
+impl Contract {
+    /// Lends one of the caller's books to `borrower` until `due_timestamp`.
+    pub fn lend_book(
+        &mut self,
+        book_id: BookId,
+        borrower: ValidAccountId,
+        due_timestamp: U64,
+    ) -> LoanView {
+        self.assert_can_mutate();
+        let lender_id = self.internal_caller_id();
+        self.internal_lend_book(lender_id, book_id, borrower.into(), due_timestamp.0)
+    }
+
+    /// Ends a loan. Either the lender or the borrower can record the return.
+    pub fn return_book(&mut self, book_id: BookId) {
+        self.assert_can_mutate();
+        let account_id = self.internal_caller_id();
+        let initial_storage = env::storage_usage();
+        let loan = self.loans.get(&book_id).expect("Book is not on loan");
+        assert!(
+            account_id == loan.lender_id || account_id == loan.borrower_id,
+            "Only the lender or the borrower can return a book"
+        );
+
+        self.loans.remove(&book_id);
+        self.internal_unindex_loan(&loan.lender_id, &book_id);
+        self.internal_unindex_loan(&loan.borrower_id, &book_id);
+        self.internal_charge_storage(&loan.lender_id, initial_storage);
+    }
+
+    /// Books the account has lent out or borrowed.
+    pub fn get_loans(
+        &self,
+        account_id: ValidAccountId,
+        from_index: Option<u64>,
+        limit: Option<u64>,
+    ) -> Page<LoanView> {
+        match self.loans_by_account_id.get(account_id.as_ref()) {
+            Some(book_ids) => {
+                Page::from_vector(book_ids.as_vector(), from_index, limit, |book_id| {
+                    self.loans
+                        .get(&book_id)
+                        .map(|loan| self.internal_loan_view(book_id, loan))
+                })
+            }
+            None => Page::empty(),
+        }
+    }
+}
+
+impl Contract {
+    /// Records a loan of one of `lender_id`'s books, charging the lender for its storage.
+    pub(crate) fn internal_lend_book(
+        &mut self,
+        lender_id: AccountId,
+        book_id: BookId,
+        borrower_id: AccountId,
+        due_at: u64,
+    ) -> LoanView {
+        let initial_storage = env::storage_usage();
+        assert_ne!(lender_id, borrower_id, "Cannot lend a book to yourself");
+        self.assert_book_owner(&lender_id, &book_id);
+        self.assert_not_on_loan(&book_id);
+        self.assert_not_at_auction(&book_id);
+        let now = env::block_timestamp();
+        assert!(due_at > now, "Due date must be in the future");
+
+        let loan = Loan {
+            lender_id: lender_id.clone(),
+            borrower_id: borrower_id.clone(),
+            lent_at: now,
+            due_at,
+        };
+        self.loans.insert(&book_id, &loan);
+        self.internal_index_loan(&lender_id, &book_id);
+        self.internal_index_loan(&borrower_id, &book_id);
+        self.internal_charge_storage(&lender_id, initial_storage);
+
+        self.internal_loan_view(book_id, loan)
+    }
+
+    pub(crate) fn assert_not_on_loan(&self, book_id: &BookId) {
+        assert!(self.loans.get(book_id).is_none(), "Book is on loan");
+    }
+
+    fn internal_loan_view(&self, book_id: BookId, loan: Loan) -> LoanView {
+        LoanView {
+            book_id,
+            overdue: env::block_timestamp() > loan.due_at,
+            lender_id: loan.lender_id,
+            borrower_id: loan.borrower_id,
+            lent_at: loan.lent_at.into(),
+            due_at: loan.due_at.into(),
+        }
+    }
+
+    fn internal_index_loan(&mut self, account_id: &AccountId, book_id: &BookId) {
+        let mut book_ids = self.loans_by_account_id.get(account_id).unwrap_or_else(|| {
+            UnorderedSet::new(StorageKey::LoansPerAccount {
+                account_hash: env::sha256(account_id.as_bytes()),
+            })
+        });
+        book_ids.insert(book_id);
+        self.loans_by_account_id.insert(account_id, &book_ids);
+    }
+
+    fn internal_unindex_loan(&mut self, account_id: &AccountId, book_id: &BookId) {
+        if let Some(mut book_ids) = self.loans_by_account_id.get(account_id) {
+            book_ids.remove(book_id);
+            if book_ids.is_empty() {
+                self.loans_by_account_id.remove(account_id);
+            } else {
+                self.loans_by_account_id.insert(account_id, &book_ids);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{get_context, get_contract, get_sample_book};
+    use near_sdk::testing_env;
+    use near_sdk::MockedBlockchain;
+    use std::convert::TryFrom;
+
+    #[test]
+    fn test_lend_and_return_book() {
+        let mut context = get_context(vec![], false);
+        testing_env!(context.clone());
+        let mut contract = get_contract();
+        let book_id = contract.add_book(get_sample_book());
+        let dave = ValidAccountId::try_from("dave_near").unwrap();
+        contract.lend_book(
+            book_id.clone(),
+            dave.clone(),
+            U64(context.block_timestamp + 1_000),
+        );
+
+        context.block_timestamp += 2_000;
+        context.predecessor_account_id = "dave_near".to_string();
+        context.storage_usage = env::storage_usage();
+        testing_env!(context);
+        let loans = contract.get_loans(dave.clone(), None, None).items;
+        assert_eq!(loans[0].lender_id, "carol_near");
+        assert!(loans[0].overdue);
+
+        contract.return_book(book_id);
+        assert_eq!(contract.get_loans(dave, None, None).total, 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "Book is on loan")]
+    fn test_delete_book_on_loan() {
+        let mut context = get_context(vec![], false);
+        context.attached_deposit = 1;
+        testing_env!(context.clone());
+        let mut contract = get_contract();
+        let book_id = contract.add_book(get_sample_book());
+        contract.lend_book(
+            book_id.clone(),
+            ValidAccountId::try_from("dave_near").unwrap(),
+            U64(context.block_timestamp + 1_000),
+        );
+        contract.delete_book(book_id);
+    }
+}
@@ -0,0 +1,214 @@
+use crate::*;
+use near_sdk::json_types::U128;
+use near_sdk::{assert_one_yocto, Promise};
+
+/// A book its owner has put up for sale. Cleared whenever the book changes hands, is archived
+/// or is deleted.
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct Sale {
+    book_id: BookId,
+    seller_id: AccountId,
+    price: U128,
+    listed_at: U64,
+}
+
+#[near_bindgen]
+impl Contract {
+    /// Lists one of the caller's books for `price` yoctoNEAR, or changes the price of an
+    /// existing listing. Requires one yoctoNEAR, like `nft_transfer`.
+    #[payable]
+    pub fn list_for_sale(&mut self, book_id: BookId, price: U128) -> Sale {
+        self.assert_can_mutate();
+        assert_one_yocto();
+        let seller_id = env::predecessor_account_id();
+        let initial_storage = env::storage_usage();
+        self.assert_book_owner(&seller_id, &book_id);
+        self.assert_not_on_loan(&book_id);
+        self.assert_not_at_auction(&book_id);
+        assert!(price.0 > 0, "Price must be greater than zero");
+
+        let sale = Sale {
+            book_id: book_id.clone(),
+            seller_id: seller_id.clone(),
+            price,
+            listed_at: env::block_timestamp().into(),
+        };
+        self.sales.insert(&book_id, &sale);
+        let mut book_ids = self.sales_by_seller_id.get(&seller_id).unwrap_or_else(|| {
+            UnorderedSet::new(StorageKey::SalesPerSeller {
+                account_hash: env::sha256(seller_id.as_bytes()),
+            })
+        });
+        book_ids.insert(&book_id);
+        self.sales_by_seller_id.insert(&seller_id, &book_ids);
+        self.internal_charge_storage(&seller_id, initial_storage);
+
+        sale
+    }
+
+    #[payable]
+    pub fn cancel_sale(&mut self, book_id: BookId) {
+        self.assert_can_mutate();
+        assert_one_yocto();
+        let account_id = env::predecessor_account_id();
+        let initial_storage = env::storage_usage();
+        let sale = self.sales.get(&book_id).expect("Book is not for sale");
+        assert_eq!(
+            sale.seller_id, account_id,
+            "Only the seller can cancel a sale"
+        );
+        self.internal_remove_sale(&book_id);
+        self.internal_charge_storage(&account_id, initial_storage);
+    }
+
+    /// Buys a listed book. The attached deposit must equal the price; it is paid out to the
+    /// seller and any royalty accounts once the book has been transferred to the caller.
+    #[payable]
+    pub fn buy(&mut self, book_id: BookId) -> Book {
+        self.assert_can_mutate();
+        let buyer_id = env::predecessor_account_id();
+        let sale = self.sales.get(&book_id).expect("Book is not for sale");
+        assert_eq!(
+            env::attached_deposit(),
+            sale.price.0,
+            "Attached deposit must equal the price"
+        );
+
+        let payout = self.internal_payout(
+            &sale.seller_id,
+            &book_id,
+            sale.price.0,
+            MAX_ROYALTY_ACCOUNTS as u32 + 1,
+        );
+        let book = self.internal_transfer_book(
+            &sale.seller_id,
+            &buyer_id,
+            &book_id,
+            None,
+            Some(format!("sale {}", sale.price.0)),
+        );
+        for (account_id, amount) in payout.payout {
+            if amount.0 > 0 {
+                Promise::new(account_id).transfer(amount.0);
+            }
+        }
+
+        book
+    }
+
+    pub fn get_sale(&self, book_id: BookId) -> Option<Sale> {
+        self.internal_get_listed_book(&book_id)
+            .and_then(|_| self.sales.get(&book_id))
+    }
+
+    /// Every open listing, in the order books were first listed.
+    pub fn get_sales(&self, from_index: Option<u64>, limit: Option<u64>) -> Page<Sale> {
+        Page::from_vector(self.sales.keys_as_vector(), from_index, limit, |book_id| {
+            self.get_sale(book_id)
+        })
+    }
+
+    pub fn get_sales_by_seller(
+        &self,
+        account_id: ValidAccountId,
+        from_index: Option<u64>,
+        limit: Option<u64>,
+    ) -> Page<Sale> {
+        match self.sales_by_seller_id.get(account_id.as_ref()) {
+            Some(book_ids) => {
+                Page::from_vector(book_ids.as_vector(), from_index, limit, |book_id| {
+                    self.get_sale(book_id)
+                })
+            }
+            None => Page::empty(),
+        }
+    }
+}
+
+impl Contract {
+    /// Drops a book's listing, if any. The caller's storage measurement releases its bytes.
+    pub(crate) fn internal_remove_sale(&mut self, book_id: &BookId) {
+        if let Some(sale) = self.sales.remove(book_id) {
+            let seller_id = sale.seller_id;
+            if let Some(mut book_ids) = self.sales_by_seller_id.get(&seller_id) {
+                book_ids.remove(book_id);
+                if book_ids.is_empty() {
+                    self.sales_by_seller_id.remove(&seller_id);
+                } else {
+                    self.sales_by_seller_id.insert(&seller_id, &book_ids);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{get_context, get_contract, get_sample_book};
+    use near_sdk::testing_env;
+    use near_sdk::MockedBlockchain;
+    use std::convert::TryFrom;
+
+    #[test]
+    fn test_buy_book() {
+        let mut context = get_context(vec![], false);
+        context.attached_deposit = 1;
+        context.account_balance = 10u128.pow(24);
+        testing_env!(context.clone());
+        let mut contract = get_contract();
+        let book_id = contract.add_book(get_sample_book());
+        contract.list_for_sale(book_id.clone(), U128(1_000));
+        assert_eq!(contract.get_sales(None, None).items.len(), 1);
+
+        context.predecessor_account_id = "dave_near".to_string();
+        context.attached_deposit = 1_000;
+        context.storage_usage = env::storage_usage();
+        testing_env!(context);
+        let book = contract.buy(book_id.clone());
+
+        assert_eq!(book.account_id, Some("dave_near".to_string()));
+        assert!(contract.get_sale(book_id).is_none());
+        assert_eq!(
+            contract
+                .get_sales_by_seller(ValidAccountId::try_from("carol_near").unwrap(), None, None)
+                .total,
+            0
+        );
+    }
+
+    #[test]
+    fn test_archive_removes_sale() {
+        let mut context = get_context(vec![], false);
+        context.attached_deposit = 1;
+        testing_env!(context);
+        let mut contract = get_contract();
+        let book_id = contract.add_book(get_sample_book());
+        contract.list_for_sale(book_id.clone(), U128(1_000));
+        contract.archive_book(book_id.clone());
+
+        assert!(contract.sales.get(&book_id).is_none());
+        assert!(contract
+            .sales_by_seller_id
+            .get(&"carol_near".to_string())
+            .is_none());
+    }
+
+    #[test]
+    #[should_panic(expected = "Attached deposit must equal the price")]
+    fn test_buy_book_underpaid() {
+        let mut context = get_context(vec![], false);
+        context.attached_deposit = 1;
+        testing_env!(context.clone());
+        let mut contract = get_contract();
+        let book_id = contract.add_book(get_sample_book());
+        contract.list_for_sale(book_id.clone(), U128(1_000));
+
+        context.predecessor_account_id = "dave_near".to_string();
+        context.attached_deposit = 999;
+        context.storage_usage = env::storage_usage();
+        testing_env!(context);
+        contract.buy(book_id);
+    }
+}
@@ -0,0 +1,177 @@
+use crate::*;
+use near_sdk::json_types::Base64VecU8;
+
+/// Most books one page of the library root covers, so a root always fits in a view call.
+const MAX_LEAVES_PER_PAGE: u64 = 100;
+
+/// Merkle root over one page of an account's books, with the ids of the books it covers in
+/// leaf order. `next_cursor` is the `from_index` of the next page, or `None` on the last one.
+#[derive(Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct LibraryRoot {
+    pub root: Option<Base64VecU8>,
+    pub book_ids: Vec<BookId>,
+    pub total: u64,
+    pub next_cursor: Option<u64>,
+}
+
+/// Sibling hashes from a book's leaf up to its page's root. At each level the sibling is on
+/// the right when the node's index is even, and is missing when an odd node was promoted.
+#[derive(Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct LibraryProof {
+    pub leaf_index: u64,
+    pub siblings: Vec<Option<Base64VecU8>>,
+}
+
+#[near_bindgen]
+impl Contract {
+    /// Merkle root over a page of an account's live books, so off-chain apps can check a copy
+    /// of the library against it page by page. Leaves are the sha256 of each Borsh-serialized
+    /// book in the order of `book_ids`; parents hash the concatenation of their children, and
+    /// an odd last node is promoted as is. At most 100 books per page; `root` is `None` for an
+    /// empty page.
+    pub fn get_library_root(
+        &self,
+        account_id: ValidAccountId,
+        from_index: Option<u64>,
+        limit: Option<u64>,
+    ) -> LibraryRoot {
+        let (book_ids, total, next_cursor) =
+            self.internal_library_page(account_id.as_ref(), from_index, limit);
+        let mut level = self.internal_library_leaves(&book_ids);
+        while level.len() > 1 {
+            level = parent_level(&level);
+        }
+        LibraryRoot {
+            root: level.pop().map(Base64VecU8),
+            book_ids,
+            total,
+            next_cursor,
+        }
+    }
+
+    /// Proof that one book is part of the root of the page that `from_index` and `limit`
+    /// select from its owner's library.
+    pub fn get_library_proof(
+        &self,
+        book_id: BookId,
+        from_index: Option<u64>,
+        limit: Option<u64>,
+    ) -> LibraryProof {
+        let account_id = self
+            .books
+            .get(&book_id)
+            .and_then(|book| book.account_id)
+            .expect("Book does not exist");
+        let (book_ids, _, _) = self.internal_library_page(&account_id, from_index, limit);
+        let leaf_index = book_ids
+            .iter()
+            .position(|id| *id == book_id)
+            .expect("Book is not in this page");
+
+        let mut level = self.internal_library_leaves(&book_ids);
+        let mut index = leaf_index;
+        let mut siblings = vec![];
+        while level.len() > 1 {
+            siblings.push(level.get(index ^ 1).cloned().map(Base64VecU8));
+            level = parent_level(&level);
+            index /= 2;
+        }
+        LibraryProof {
+            leaf_index: leaf_index as u64,
+            siblings,
+        }
+    }
+}
+
+impl Contract {
+    /// The ids of one page of the account's books, the library size and the next cursor.
+    fn internal_library_page(
+        &self,
+        account_id: &AccountId,
+        from_index: Option<u64>,
+        limit: Option<u64>,
+    ) -> (Vec<BookId>, u64, Option<u64>) {
+        let book_ids = match self.books_by_owner_id.get(account_id) {
+            Some(book_ids) => book_ids,
+            None => return (vec![], 0, None),
+        };
+        let limit = cmp::min(limit.unwrap_or(MAX_LEAVES_PER_PAGE), MAX_LEAVES_PER_PAGE);
+        assert_ne!(limit, 0, "Cannot provide limit of 0.");
+        let total = book_ids.len();
+        let start = cmp::min(from_index.unwrap_or(0), total);
+        let end = cmp::min(total, start + limit);
+        let page = (start..end)
+            .filter_map(|index| book_ids.as_vector().get(index))
+            .collect();
+        (page, total, if end < total { Some(end) } else { None })
+    }
+
+    fn internal_library_leaves(&self, book_ids: &[BookId]) -> Vec<Vec<u8>> {
+        book_ids
+            .iter()
+            .map(|book_id| {
+                let book = self.books.get(book_id).unwrap();
+                env::sha256(&book.try_to_vec().unwrap())
+            })
+            .collect()
+    }
+}
+
+fn parent_level(level: &[Vec<u8>]) -> Vec<Vec<u8>> {
+    level
+        .chunks(2)
+        .map(|pair| match pair {
+            [left, right] => env::sha256(&[left.as_slice(), right.as_slice()].concat()),
+            _ => pair[0].clone(),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{get_context, get_contract, get_sample_book};
+    use near_sdk::testing_env;
+    use near_sdk::MockedBlockchain;
+    use std::convert::TryFrom;
+
+    #[test]
+    fn test_library_proof_matches_root() {
+        let context = get_context(vec![], false);
+        testing_env!(context);
+        let mut contract = get_contract();
+        let account_id = ValidAccountId::try_from("carol_near").unwrap();
+        assert!(contract
+            .get_library_root(account_id.clone(), None, None)
+            .root
+            .is_none());
+
+        let book_ids: Vec<BookId> = (0..4)
+            .map(|_| contract.add_book(get_sample_book()))
+            .collect();
+        let page = contract.get_library_root(account_id, Some(1), Some(3));
+        assert_eq!(page.book_ids, book_ids[1..].to_vec());
+        assert_eq!(page.total, 4);
+        assert_eq!(page.next_cursor, None);
+        let root = page.root.unwrap();
+
+        let book = contract.books.get(&book_ids[3]).unwrap();
+        let proof = contract.get_library_proof(book_ids[3].clone(), Some(1), Some(3));
+        assert_eq!(proof.leaf_index, 2);
+        let mut hash = env::sha256(&book.try_to_vec().unwrap());
+        let mut index = proof.leaf_index;
+        for sibling in proof.siblings {
+            hash = match sibling {
+                Some(sibling) if index.is_multiple_of(2) => {
+                    env::sha256(&[hash, sibling.0].concat())
+                }
+                Some(sibling) => env::sha256(&[sibling.0, hash].concat()),
+                None => hash,
+            };
+            index /= 2;
+        }
+        assert_eq!(hash, root.0);
+    }
+}
@@ -0,0 +1,97 @@
+use crate::*;
+use near_sdk::json_types::Base64VecU8;
+
+pub const NFT_METADATA_SPEC: &str = "nft-1.0.0";
+
+/// NEP-177 contract metadata.
+#[derive(Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct NFTContractMetadata {
+    pub spec: String,
+    pub name: String,
+    pub symbol: String,
+    pub icon: Option<String>,
+    pub base_uri: Option<String>,
+    pub reference: Option<String>,
+    pub reference_hash: Option<Base64VecU8>,
+}
+
+/// NEP-177 token metadata, derived from the book on every read rather than stored.
+#[derive(Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct TokenMetadata {
+    pub title: Option<String>,
+    pub description: Option<String>,
+    pub media: Option<String>,
+    pub media_hash: Option<Base64VecU8>,
+    pub copies: Option<u64>,
+    pub issued_at: Option<String>,
+    pub expires_at: Option<String>,
+    pub starts_at: Option<String>,
+    pub updated_at: Option<String>,
+    pub extra: Option<String>,
+    pub reference: Option<String>,
+    pub reference_hash: Option<Base64VecU8>,
+}
+
+/// Block timestamps are in nanoseconds; NEP-177 dates are milliseconds since the epoch.
+fn to_millis(timestamp: Option<U64>) -> Option<String> {
+    timestamp.map(|timestamp| (timestamp.0 / 1_000_000).to_string())
+}
+
+impl Book {
+    pub(crate) fn token_metadata(&self) -> TokenMetadata {
+        TokenMetadata {
+            title: Some(self.title.clone()),
+            description: Some(self.description.clone()),
+            media: self.images.first().cloned(),
+            media_hash: None,
+            copies: Some(1),
+            issued_at: to_millis(self.created_at),
+            expires_at: None,
+            starts_at: None,
+            updated_at: to_millis(self.updated_at),
+            extra: None,
+            reference: None,
+            reference_hash: None,
+        }
+    }
+}
+
+#[near_bindgen]
+impl Contract {
+    pub fn nft_metadata(&self) -> NFTContractMetadata {
+        NFTContractMetadata {
+            spec: NFT_METADATA_SPEC.to_string(),
+            name: "Book Tracker".to_string(),
+            symbol: "BOOK".to_string(),
+            icon: None,
+            base_uri: None,
+            reference: None,
+            reference_hash: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{get_context, get_contract, get_sample_book};
+    use near_sdk::testing_env;
+    use near_sdk::MockedBlockchain;
+
+    #[test]
+    fn test_token_metadata() {
+        let mut context = get_context(vec![], false);
+        context.block_timestamp = 1_600_000_000_000_000_000;
+        testing_env!(context);
+        let mut contract = get_contract();
+        let book_id = contract.add_book(get_sample_book());
+
+        let metadata = contract.nft_token(book_id).unwrap().metadata.unwrap();
+        assert_eq!(metadata.title, Some("Motorcycle Mechanics 101".to_string()));
+        assert_eq!(metadata.media, Some("https://example.com".to_string()));
+        assert_eq!(metadata.issued_at, Some("1600000000000".to_string()));
+        assert_eq!(contract.nft_metadata().spec, NFT_METADATA_SPEC);
+    }
+}
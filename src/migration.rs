@@ -0,0 +1,240 @@
+use crate::*;
+
+/// Layout version of the stored state, kept under its own key so `migrate` can tell which
+/// layout to read before decoding it. Contracts deployed before versioning have none, which
+/// reads as version 0.
+pub(crate) const STATE_VERSION: u32 = 1;
+
+/// The state as deployed before the contract had an owner or config.
+#[derive(BorshDeserialize, BorshSerialize)]
+struct ContractV0 {
+    books_by_owner_id: UnorderedMap<AccountId, UnorderedSet<BookId>>,
+    books: UnorderedMap<BookId, BookV0>,
+    books_len: u64,
+}
+
+#[derive(BorshDeserialize, BorshSerialize)]
+struct BookV0 {
+    book_id: Option<BookId>,
+    account_id: Option<AccountId>,
+    title: String,
+    description: String,
+    status: Status,
+    image: String,
+}
+
+impl From<BookV0> for Book {
+    fn from(book: BookV0) -> Self {
+        Self {
+            book_id: book.book_id,
+            account_id: book.account_id,
+            title: book.title,
+            description: book.description,
+            status: book.status,
+            images: if book.image.is_empty() {
+                vec![]
+            } else {
+                vec![book.image]
+            },
+            pages: None,
+            rating: None,
+            author: None,
+            author_account_id: None,
+            format: None,
+            language: None,
+            isbn: None,
+            tags: vec![],
+            created_at: None,
+            updated_at: None,
+            finished_at: None,
+            finished_at_self_reported: false,
+            visibility: Visibility::default(),
+        }
+    }
+}
+
+#[near_bindgen]
+impl Contract {
+    /// Upgrades the stored state to the current layout after new code is deployed. State from
+    /// before versioning gets `owner_id` and `config`, keeps its books, and stays paused until
+    /// `migrate_books` has converted all of them.
+    #[init(ignore_state)]
+    #[private]
+    pub fn migrate(owner_id: ValidAccountId, config: Config) -> Self {
+        match internal_state_version() {
+            0 => Self::internal_migrate_from_v0(owner_id.into(), config),
+            STATE_VERSION => env::panic(b"State is already at the current version"),
+            version => panic!("Unknown state version {}", version),
+        }
+    }
+
+    /// Converts up to `limit` books left in the old layout by `migrate` and indexes them.
+    /// Returns how many are still left.
+    pub fn migrate_books(&mut self, limit: u64) -> u64 {
+        self.assert_owner();
+        let migrated = self.migrated_books_len.expect("No books to migrate");
+        let legacy_books = self.internal_legacy_books();
+        let book_ids = legacy_books.keys_as_vector();
+        let end = cmp::min(migrated + limit, book_ids.len());
+        for index in migrated..end {
+            let book_id = book_ids.get(index).unwrap();
+            let book = Book::from(legacy_books.get(&book_id).unwrap());
+            // `insert` would decode the replaced value as a new `Book`, so write it raw.
+            self.books
+                .insert_raw(&book_id.try_to_vec().unwrap(), &book.try_to_vec().unwrap());
+            self.internal_add_book_to_owner(book.account_id.as_ref().unwrap(), &book_id, &book);
+        }
+        let remaining = book_ids.len() - end;
+        self.migrated_books_len = if remaining == 0 { None } else { Some(end) };
+        remaining
+    }
+}
+
+impl Contract {
+    fn internal_migrate_from_v0(owner_id: AccountId, config: Config) -> Self {
+        let old: ContractV0 = env::state_read().expect("No state to migrate");
+        let mut contract = Self::internal_new(owner_id, config);
+        contract.books = UnorderedMap::try_from_slice(&old.books.try_to_vec().unwrap()).unwrap();
+        contract.books_by_owner_id = old.books_by_owner_id;
+        contract.books_len = old.books_len;
+        if !contract.books.is_empty() {
+            contract.paused = true;
+            contract.migrated_books_len = Some(0);
+        }
+        contract
+    }
+
+    /// `books` read back with the old value type. A map only stores its prefix and length
+    /// in the state, so both views address the same entries.
+    fn internal_legacy_books(&self) -> UnorderedMap<BookId, BookV0> {
+        UnorderedMap::try_from_slice(&self.books.try_to_vec().unwrap()).unwrap()
+    }
+
+    pub(crate) fn assert_not_migrating(&self) {
+        assert!(
+            self.migrated_books_len.is_none(),
+            "Books are still being migrated, finish with migrate_books"
+        );
+    }
+}
+
+fn internal_state_version() -> u32 {
+    LazyOption::<u32>::new(StorageKey::StateVersion, None)
+        .get()
+        .unwrap_or(0)
+}
+
+pub(crate) fn internal_set_state_version(version: u32) {
+    LazyOption::new(StorageKey::StateVersion, Some(&version));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::get_context;
+    use near_sdk::testing_env;
+    use near_sdk::MockedBlockchain;
+    use std::convert::TryFrom;
+
+    fn write_v0_state(books: &[(&str, &str)]) {
+        let mut old = ContractV0 {
+            books_by_owner_id: UnorderedMap::new(StorageKey::BooksByOwner),
+            books: UnorderedMap::new(StorageKey::Books),
+            books_len: 0,
+        };
+        for (account_id, title) in books {
+            let account_id = account_id.to_string();
+            old.books_len += 1;
+            let book_id = old.books_len.to_string();
+            old.books.insert(
+                &book_id,
+                &BookV0 {
+                    book_id: Some(book_id.clone()),
+                    account_id: Some(account_id.clone()),
+                    title: title.to_string(),
+                    description: "".to_string(),
+                    status: Status::Read,
+                    image: "cover.png".to_string(),
+                },
+            );
+            let mut book_ids = old.books_by_owner_id.get(&account_id).unwrap_or_else(|| {
+                UnorderedSet::new(StorageKey::BooksPerOwner {
+                    account_hash: env::sha256(account_id.as_bytes()),
+                })
+            });
+            book_ids.insert(&book_id);
+            old.books_by_owner_id.insert(&account_id, &book_ids);
+        }
+        env::state_write(&old);
+    }
+
+    fn migrate() -> Contract {
+        Contract::migrate(
+            ValidAccountId::try_from("alice_near").unwrap(),
+            Config::default(),
+        )
+    }
+
+    #[test]
+    fn test_migrate_from_v0() {
+        let mut context = get_context(vec![], false);
+        context.predecessor_account_id = "alice_near".to_string();
+        testing_env!(context);
+        write_v0_state(&[
+            ("carol_near", "Dune"),
+            ("carol_near", "Emma"),
+            ("dave_near", "Ulysses"),
+        ]);
+
+        let mut contract = migrate();
+        assert!(contract.is_paused());
+        assert_eq!(contract.migrate_books(2), 1);
+        assert_eq!(contract.migrate_books(10), 0);
+        contract.unpause();
+
+        let book = contract.get_book("1".to_string());
+        assert_eq!(book.title, "Dune");
+        assert_eq!(book.images, vec!["cover.png".to_string()]);
+        assert_eq!(
+            contract
+                .get_books(
+                    Some(ValidAccountId::try_from("carol_near").unwrap()),
+                    None,
+                    None,
+                    None,
+                    None,
+                    None
+                )
+                .items
+                .len(),
+            2
+        );
+        assert_eq!(contract.books_len, 3);
+    }
+
+    #[test]
+    #[should_panic(expected = "Books are still being migrated, finish with migrate_books")]
+    fn test_unpause_waits_for_migrated_books() {
+        let mut context = get_context(vec![], false);
+        context.predecessor_account_id = "alice_near".to_string();
+        testing_env!(context);
+        write_v0_state(&[("carol_near", "Dune"), ("carol_near", "Emma")]);
+
+        let mut contract = migrate();
+        contract.migrate_books(1);
+        contract.unpause();
+    }
+
+    #[test]
+    #[should_panic(expected = "State is already at the current version")]
+    fn test_migrate_current_state() {
+        let mut context = get_context(vec![], false);
+        context.predecessor_account_id = "alice_near".to_string();
+        testing_env!(context);
+        env::state_write(&Contract::new(
+            ValidAccountId::try_from("alice_near").unwrap(),
+            Config::default(),
+        ));
+        migrate();
+    }
+}
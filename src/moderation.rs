@@ -0,0 +1,138 @@
+use crate::*;
+
+const REDACTED: &str = "[redacted]";
+
+/// Public book fields a moderator can blank out.
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub enum RedactableField {
+    Title,
+    Description,
+    Images,
+    Author,
+    Tags,
+}
+
+#[near_bindgen]
+impl Contract {
+    /// Permanently removes a book, e.g. spam or illegal content, without the undo window of
+    /// `delete_book`.
+    pub fn admin_remove_book(&mut self, book_id: BookId, reason: String) -> Book {
+        let moderator_id = self.assert_role(Role::Moderator);
+        let initial_storage = env::storage_usage();
+        let book = self.books.remove(&book_id).expect("Book does not exist");
+        let account_id = book.account_id.clone().unwrap();
+
+        self.internal_remove_book_from_owner(&account_id, &book_id, &book);
+        self.internal_remove_book_data(&book_id);
+        let storage = self.internal_settle_storage(&account_id, initial_storage);
+        self.internal_clear_reports(&ReportTarget::Book(book_id.clone()));
+        self.internal_cancel_auction(&book_id);
+
+        emit_moderation_event(ModerationEvent::BookRemoved([ModerationData {
+            moderator_id: &moderator_id,
+            account_id: &account_id,
+            book_id: &book_id,
+            field: None,
+            reason: &reason,
+        }]));
+        emit_book_event(
+            BookEventKind::BookDelete,
+            &account_id,
+            std::slice::from_ref(&book_id),
+            storage,
+        );
+        emit_nft_event(NftEvent::Burn([NftMintData {
+            owner_id: &account_id,
+            token_ids: std::slice::from_ref(&book_id),
+            storage,
+        }]));
+
+        book
+    }
+
+    /// Replaces one public field of a book: text with "[redacted]", lists and optional fields
+    /// with nothing.
+    pub fn admin_redact_field(
+        &mut self,
+        book_id: BookId,
+        field: RedactableField,
+        reason: String,
+    ) -> Book {
+        let moderator_id = self.assert_role(Role::Moderator);
+        let initial_storage = env::storage_usage();
+        let mut book = self.books.get(&book_id).expect("Book does not exist");
+        let account_id = book.account_id.clone().unwrap();
+
+        self.internal_remove_book_from_owner(&account_id, &book_id, &book);
+        match field {
+            RedactableField::Title => book.title = REDACTED.to_string(),
+            RedactableField::Description => book.description = REDACTED.to_string(),
+            RedactableField::Images => book.images.clear(),
+            RedactableField::Author => book.author = None,
+            RedactableField::Tags => book.tags.clear(),
+        }
+        book.touch();
+        self.books.insert(&book_id, &book);
+        self.internal_add_book_to_owner(&account_id, &book_id, &book);
+        self.internal_charge_storage(&account_id, initial_storage);
+
+        emit_moderation_event(ModerationEvent::FieldRedacted([ModerationData {
+            moderator_id: &moderator_id,
+            account_id: &account_id,
+            book_id: &book_id,
+            field: Some(field),
+            reason: &reason,
+        }]));
+
+        book
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{get_context, get_contract, get_sample_book};
+    use near_sdk::testing_env;
+    use near_sdk::MockedBlockchain;
+    use std::convert::TryFrom;
+
+    #[test]
+    fn test_moderator_redacts_and_removes() {
+        let mut context = get_context(vec![], false);
+        testing_env!(context.clone());
+        let mut contract = get_contract();
+        let book_id = contract.add_book(get_sample_book());
+
+        context.predecessor_account_id = "alice_near".to_string();
+        context.storage_usage = env::storage_usage();
+        testing_env!(context.clone());
+        contract.add_role(
+            ValidAccountId::try_from("dave_near").unwrap(),
+            Role::Moderator,
+        );
+
+        context.predecessor_account_id = "dave_near".to_string();
+        context.storage_usage = env::storage_usage();
+        testing_env!(context);
+        let book =
+            contract.admin_redact_field(book_id.clone(), RedactableField::Title, "Spam".into());
+        assert_eq!(book.title, REDACTED);
+
+        contract.admin_remove_book(book_id.clone(), "Spam".to_string());
+        let account_id = ValidAccountId::try_from("carol_near").unwrap();
+        assert_eq!(contract.get_books_count(account_id), 0);
+        assert!(contract.books.get(&book_id).is_none());
+        assert!(contract.pending_deletions.get(&book_id).is_none());
+    }
+
+    #[test]
+    #[should_panic(expected = "Only the owner or a moderator can call this method")]
+    fn test_admin_remove_book_not_moderator() {
+        let context = get_context(vec![], false);
+        testing_env!(context);
+        let mut contract = get_contract();
+        let book_id = contract.add_book(get_sample_book());
+        contract.admin_remove_book(book_id, "Spam".to_string());
+    }
+}
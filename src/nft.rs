@@ -0,0 +1,347 @@
+use crate::*;
+use near_sdk::{assert_one_yocto, ext_contract, Balance, PromiseOrValue, PromiseResult};
+use std::collections::HashMap;
+
+const GAS_FOR_RESOLVE_TRANSFER: Gas = 10_000_000_000_000;
+const GAS_FOR_NFT_TRANSFER_CALL: Gas = 25_000_000_000_000 + GAS_FOR_RESOLVE_TRANSFER;
+const NO_DEPOSIT: Balance = 0;
+/// Upper bound on what a receiver's owner indexes take for one book, on top of the book
+/// record and its title key.
+const BOOK_INDEX_BYTES: u64 = 4_000;
+/// Upper bound on what the tag indexes take for each of the book's tags.
+const TAG_INDEX_BYTES: u64 = 500;
+
+/// A book as a NEP-171 token. The token id is the book id.
+#[derive(Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct Token {
+    pub token_id: BookId,
+    pub owner_id: AccountId,
+    pub metadata: Option<TokenMetadata>,
+    pub approved_account_ids: HashMap<AccountId, u64>,
+}
+
+#[ext_contract(ext_receiver)]
+pub trait NonFungibleTokenReceiver {
+    fn nft_on_transfer(
+        &mut self,
+        sender_id: AccountId,
+        previous_owner_id: AccountId,
+        token_id: BookId,
+        msg: String,
+    ) -> PromiseOrValue<bool>;
+}
+
+#[ext_contract(ext_self)]
+pub trait NonFungibleTokenResolver {
+    fn nft_resolve_transfer(
+        &mut self,
+        previous_owner_id: AccountId,
+        receiver_id: AccountId,
+        token_id: BookId,
+    ) -> bool;
+}
+
+#[near_bindgen]
+impl Contract {
+    /// Moves a book, with its bookmarks, curriculum and links, into `receiver_id`'s library.
+    /// The caller must own the book or hold an approval for it; approvals are cleared. The
+    /// receiver pays for the book's storage, so it must have opted in with
+    /// `set_accept_transfers`. An owner can take the book back with `undo_last_action` within
+    /// the undo window.
+    #[payable]
+    pub fn nft_transfer(
+        &mut self,
+        receiver_id: ValidAccountId,
+        token_id: BookId,
+        approval_id: Option<u64>,
+        memo: Option<String>,
+    ) {
+        self.assert_can_mutate();
+        assert_one_yocto();
+        let (owner_id, authorized_id) = self.internal_authorize(&token_id, approval_id);
+        self.assert_accepts_transfers(receiver_id.as_ref());
+        self.internal_transfer_book(
+            &owner_id,
+            receiver_id.as_ref(),
+            &token_id,
+            authorized_id.as_ref(),
+            memo,
+        );
+        if authorized_id.is_none() {
+            let initial_storage = env::storage_usage();
+            self.internal_queue_transfer(&owner_id, receiver_id.as_ref(), &token_id);
+            self.internal_charge_storage(&owner_id, initial_storage);
+        }
+    }
+
+    /// Transfers a book and calls `nft_on_transfer` on the receiver, which may hand it back by
+    /// returning `true`.
+    #[payable]
+    pub fn nft_transfer_call(
+        &mut self,
+        receiver_id: ValidAccountId,
+        token_id: BookId,
+        approval_id: Option<u64>,
+        memo: Option<String>,
+        msg: String,
+    ) -> PromiseOrValue<bool> {
+        self.assert_can_mutate();
+        assert_one_yocto();
+        let (sender_id, authorized_id) = self.internal_authorize(&token_id, approval_id);
+        self.assert_accepts_transfers(receiver_id.as_ref());
+        self.internal_transfer_book(
+            &sender_id,
+            receiver_id.as_ref(),
+            &token_id,
+            authorized_id.as_ref(),
+            memo,
+        );
+
+        ext_receiver::nft_on_transfer(
+            authorized_id.unwrap_or_else(|| sender_id.clone()),
+            sender_id.clone(),
+            token_id.clone(),
+            msg,
+            receiver_id.as_ref(),
+            NO_DEPOSIT,
+            env::prepaid_gas() - GAS_FOR_NFT_TRANSFER_CALL,
+        )
+        .then(ext_self::nft_resolve_transfer(
+            sender_id,
+            receiver_id.into(),
+            token_id,
+            &env::current_account_id(),
+            NO_DEPOSIT,
+            GAS_FOR_RESOLVE_TRANSFER,
+        ))
+        .into()
+    }
+
+    /// Returns the book to `previous_owner_id` if the receiver asked for it, or its
+    /// `nft_on_transfer` failed. Returns whether the transfer stuck. The book stays with the
+    /// receiver if the previous owner has since closed their storage account.
+    #[private]
+    pub fn nft_resolve_transfer(
+        &mut self,
+        previous_owner_id: AccountId,
+        receiver_id: AccountId,
+        token_id: BookId,
+    ) -> bool {
+        let return_book = match env::promise_result(0) {
+            PromiseResult::Successful(value) => {
+                near_sdk::serde_json::from_slice::<bool>(&value).unwrap_or(true)
+            }
+            _ => true,
+        };
+        if !return_book {
+            return true;
+        }
+
+        match self.books.get(&token_id) {
+            // The receiver may already have passed the book on.
+            Some(book)
+                if book.account_id.as_ref() == Some(&receiver_id)
+                    && self.storage_accounts.get(&previous_owner_id).is_some()
+                    && self.internal_has_book_quota(&previous_owner_id, 1) =>
+            {
+                self.internal_transfer_book(
+                    &receiver_id,
+                    &previous_owner_id,
+                    &token_id,
+                    None,
+                    None,
+                );
+                false
+            }
+            _ => true,
+        }
+    }
+
+    pub fn nft_token(&self, token_id: BookId) -> Option<Token> {
+        self.internal_get_visible_book(&token_id)
+            .map(|book| self.internal_token(book))
+    }
+}
+
+impl Contract {
+    pub(crate) fn internal_token(&self, book: Book) -> Token {
+        let token_id = book.book_id.clone().unwrap();
+        Token {
+            approved_account_ids: self.internal_approved_account_ids(&token_id),
+            metadata: Some(book.token_metadata()),
+            owner_id: book.account_id.unwrap(),
+            token_id,
+        }
+    }
+
+    /// Whether `account_id` has room in its library and is registered with enough available
+    /// storage balance to receive `book_id`. Callers that cannot let a transfer fail check this
+    /// first.
+    pub(crate) fn internal_can_receive_book(
+        &self,
+        account_id: &AccountId,
+        book_id: &BookId,
+    ) -> bool {
+        let book_bytes = self.books.get(book_id).map_or(0, |book| {
+            2 * book.try_to_vec().unwrap().len() as u64 + TAG_INDEX_BYTES * book.tags.len() as u64
+        });
+        let needed = (book_bytes + BOOK_INDEX_BYTES) as Balance * env::storage_byte_cost();
+        self.internal_has_book_quota(account_id, 1)
+            && self
+                .storage_accounts
+                .get(account_id)
+                .is_some_and(|storage_account| {
+                    let available: Balance = storage_account.balance().available.into();
+                    available >= needed
+                })
+    }
+
+    pub(crate) fn internal_transfer_book(
+        &mut self,
+        sender_id: &AccountId,
+        receiver_id: &AccountId,
+        book_id: &BookId,
+        authorized_id: Option<&AccountId>,
+        memo: Option<String>,
+    ) -> Book {
+        self.assert_book_owner(sender_id, book_id);
+        assert_ne!(sender_id, receiver_id, "Current and next owner must differ");
+        self.assert_book_quota(receiver_id, 1);
+        self.assert_not_on_loan(book_id);
+        self.assert_not_at_auction(book_id);
+
+        // The book's bytes are released from the sender and charged to the receiver.
+        let initial_storage = env::storage_usage();
+        let mut book = self.books.remove(book_id).unwrap();
+        self.internal_remove_book_from_owner(sender_id, book_id, &book);
+        self.internal_clear_approvals(book_id);
+        self.internal_clear_shares(book_id);
+        self.internal_remove_sale(book_id);
+        let old_owner_storage = self.internal_settle_storage(sender_id, initial_storage);
+
+        let initial_storage = env::storage_usage();
+        book.account_id = Some(receiver_id.clone());
+        book.touch();
+        self.books.insert(book_id, &book);
+        self.internal_add_book_to_owner(receiver_id, book_id, &book);
+        let new_owner_storage = self.internal_settle_storage(receiver_id, initial_storage);
+
+        emit_nft_event(NftEvent::Transfer([NftTransferData {
+            old_owner_id: sender_id,
+            new_owner_id: receiver_id,
+            token_ids: std::slice::from_ref(book_id),
+            authorized_id,
+            memo: memo.as_deref(),
+            old_owner_storage,
+            new_owner_storage,
+        }]));
+
+        book
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{get_context, get_contract, get_sample_book};
+    use near_sdk::testing_env;
+    use near_sdk::MockedBlockchain;
+    use std::convert::TryFrom;
+
+    #[test]
+    fn test_nft_transfer() {
+        let mut context = get_context(vec![], false);
+        testing_env!(context.clone());
+        let mut contract = get_contract();
+        let book_id = contract.add_book(get_sample_book());
+        contract.add_bookmark(book_id.clone(), "Chapter 1".to_string(), 12);
+
+        context.attached_deposit = 1;
+        context.storage_usage = env::storage_usage();
+        testing_env!(context);
+        let dave = ValidAccountId::try_from("dave_near").unwrap();
+        contract.nft_transfer(dave.clone(), book_id.clone(), None, None);
+
+        let token = contract.nft_token(book_id.clone()).unwrap();
+        assert_eq!(token.owner_id, "dave_near");
+        assert_eq!(contract.get_books_count(dave), 1);
+        assert_eq!(
+            contract.get_books_count(ValidAccountId::try_from("carol_near").unwrap()),
+            0
+        );
+        assert_eq!(contract.get_bookmarks(book_id).len(), 1);
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Receiver does not accept transfers, it must call set_accept_transfers first"
+    )]
+    fn test_nft_transfer_requires_opt_in() {
+        let mut context = get_context(vec![], false);
+        testing_env!(context.clone());
+        let mut contract = get_contract();
+        let book_id = contract.add_book(get_sample_book());
+
+        context.predecessor_account_id = "dave_near".to_string();
+        context.storage_usage = env::storage_usage();
+        testing_env!(context.clone());
+        contract.set_accept_transfers(false);
+
+        context.predecessor_account_id = "carol_near".to_string();
+        context.attached_deposit = 1;
+        context.storage_usage = env::storage_usage();
+        testing_env!(context);
+        let dave = ValidAccountId::try_from("dave_near").unwrap();
+        contract.nft_transfer(dave, book_id, None, None);
+    }
+
+    #[test]
+    fn test_nft_transfer_moves_storage() {
+        let mut context = get_context(vec![], false);
+        testing_env!(context.clone());
+        let mut contract = get_contract();
+        let book_id = contract.add_book(get_sample_book());
+        let used_bytes = |contract: &Contract, account_id: &str| {
+            contract
+                .storage_accounts
+                .get(&account_id.to_string())
+                .unwrap()
+                .used_bytes
+        };
+        let carol_bytes = used_bytes(&contract, "carol_near");
+
+        context.attached_deposit = 1;
+        context.storage_usage = env::storage_usage();
+        testing_env!(context);
+        contract.nft_transfer(
+            ValidAccountId::try_from("dave_near").unwrap(),
+            book_id,
+            None,
+            None,
+        );
+
+        assert!(used_bytes(&contract, "carol_near") < carol_bytes);
+        assert!(used_bytes(&contract, "dave_near") > 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "Book does not exist")]
+    fn test_nft_transfer_requires_owner() {
+        let mut context = get_context(vec![], false);
+        testing_env!(context.clone());
+        let mut contract = get_contract();
+        let book_id = contract.add_book(get_sample_book());
+
+        context.predecessor_account_id = "dave_near".to_string();
+        context.attached_deposit = 1;
+        context.storage_usage = env::storage_usage();
+        testing_env!(context);
+        contract.nft_transfer(
+            ValidAccountId::try_from("erin_near").unwrap(),
+            book_id,
+            None,
+            None,
+        );
+    }
+}
@@ -0,0 +1,60 @@
+use crate::*;
+
+#[derive(Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct Owner {
+    account_id: AccountId,
+    books_count: u64,
+}
+
+#[near_bindgen]
+impl Contract {
+    /// Lists accounts that currently have at least one book, with their book counts.
+    pub fn get_owners(&self, from_index: Option<u64>, limit: Option<u64>) -> Page<Owner> {
+        Page::from_vector(
+            self.books_by_owner_id.keys_as_vector(),
+            from_index,
+            limit,
+            |account_id| {
+                let books_count = self.books_by_owner_id.get(&account_id)?.len();
+                Some(Owner {
+                    account_id,
+                    books_count,
+                })
+            },
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{get_context, get_contract, get_sample_book};
+    use near_sdk::testing_env;
+    use near_sdk::MockedBlockchain;
+
+    #[test]
+    fn test_get_owners() {
+        let mut context = get_context(vec![], false);
+        context.attached_deposit = 1;
+        testing_env!(context.clone());
+        let mut contract = get_contract();
+        contract.add_book(get_sample_book());
+        contract.add_book(get_sample_book());
+
+        context.predecessor_account_id = "dave_near".to_string();
+        context.storage_usage = env::storage_usage();
+        testing_env!(context);
+        let book_id = contract.add_book(get_sample_book());
+
+        let owners = contract.get_owners(None, None).items;
+        assert_eq!(owners.len(), 2);
+        assert_eq!(owners[0].account_id, "carol_near".to_string());
+        assert_eq!(owners[0].books_count, 2);
+        assert_eq!(owners[1].account_id, "dave_near".to_string());
+
+        contract.delete_book(book_id);
+        let owners = contract.get_owners(Some(1), None).items;
+        assert_eq!(owners.len(), 0);
+    }
+}
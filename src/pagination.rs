@@ -0,0 +1,217 @@
+use crate::*;
+
+/// Default for `Config::view_gas_budget`, kept well under the RPC limit for view calls.
+pub(crate) const DEFAULT_VIEW_GAS_BUDGET: Gas = 100_000_000_000_000;
+
+/// The configured view gas budget. Read straight from storage, since pages are built without
+/// access to the contract.
+fn view_gas_budget() -> Gas {
+    LazyOption::<Config>::new(StorageKey::Config, None)
+        .get()
+        .map_or(DEFAULT_VIEW_GAS_BUDGET, |config| config.view_gas_budget)
+}
+
+/// Envelope returned by every paginated view. `next_cursor` is the `from_index` of the next
+/// page, or `None` on the last page, with `has_more` mirroring it for clients that only need a
+/// flag; `total` counts all items across pages. A page may hold
+/// fewer than `limit` items when the view ran out of gas budget, in which case `next_cursor`
+/// continues where it stopped. Every page advances the cursor by at least one entry, so
+/// following it always terminates.
+#[derive(Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct Page<T> {
+    pub items: Vec<T>,
+    pub total: u64,
+    pub has_more: bool,
+    pub next_cursor: Option<u64>,
+}
+
+impl<T> Page<T> {
+    pub(crate) fn empty() -> Self {
+        Self {
+            items: vec![],
+            total: 0,
+            has_more: false,
+            next_cursor: None,
+        }
+    }
+
+    /// Pages through a persistent vector, resolving each entry with `resolve`. Entries that
+    /// fail to resolve are dropped from the page but still advance the cursor.
+    pub(crate) fn from_vector<K, F>(
+        vector: &Vector<K>,
+        from_index: Option<u64>,
+        limit: Option<u64>,
+        resolve: F,
+    ) -> Self
+    where
+        K: BorshSerialize + BorshDeserialize,
+        F: FnMut(K) -> Option<T>,
+    {
+        Self::from_vector_ordered(vector, from_index, limit, SortOrder::Asc, resolve)
+    }
+
+    /// Same as `from_vector`, but `SortOrder::Desc` walks the vector from its last entry, so
+    /// the most recently pushed entries come first.
+    pub(crate) fn from_vector_ordered<K, F>(
+        vector: &Vector<K>,
+        from_index: Option<u64>,
+        limit: Option<u64>,
+        order: SortOrder,
+        mut resolve: F,
+    ) -> Self
+    where
+        K: BorshSerialize + BorshDeserialize,
+        F: FnMut(K) -> Option<T>,
+    {
+        let total = vector.len();
+        Self::collect(total, from_index, limit, view_gas_budget(), |index| {
+            let index = match order {
+                SortOrder::Asc => index,
+                SortOrder::Desc => total - 1 - index,
+            };
+            vector.get(index).and_then(&mut resolve)
+        })
+    }
+
+    /// Same as `from_vector` for lists that were already collected in memory, e.g. after
+    /// filtering or sorting.
+    pub(crate) fn from_vec<K, F>(
+        items: Vec<K>,
+        from_index: Option<u64>,
+        limit: Option<u64>,
+        mut resolve: F,
+    ) -> Self
+    where
+        F: FnMut(K) -> Option<T>,
+    {
+        let total = items.len() as u64;
+        let mut items = items.into_iter().map(Some).collect::<Vec<_>>();
+        Self::collect(total, from_index, limit, view_gas_budget(), |index| {
+            items[index as usize].take().and_then(&mut resolve)
+        })
+    }
+
+    /// Pages through `total` items looked up by index, e.g. several collections read as one
+    /// list.
+    pub(crate) fn from_indexed<F>(
+        total: u64,
+        from_index: Option<u64>,
+        limit: Option<u64>,
+        item_at: F,
+    ) -> Self
+    where
+        F: FnMut(u64) -> Option<T>,
+    {
+        Self::collect(total, from_index, limit, view_gas_budget(), item_at)
+    }
+
+    /// Pages through entries that can only be walked in order, e.g. a range of a `TreeMap`.
+    /// `total` is the size of the collection the entries come from; skipped entries are read
+    /// but not resolved.
+    pub(crate) fn from_iter<K, I, F>(
+        entries: I,
+        total: u64,
+        from_index: Option<u64>,
+        limit: Option<u64>,
+        mut resolve: F,
+    ) -> Self
+    where
+        I: Iterator<Item = K>,
+        F: FnMut(K) -> Option<T>,
+    {
+        let (start, end) = page_bounds(from_index, limit, total);
+        let gas_budget = view_gas_budget();
+        let mut entries = entries.skip(start as usize).peekable();
+        let mut items = vec![];
+        let mut index = start;
+        while index < end && (index == start || env::used_gas() < gas_budget) {
+            match entries.next() {
+                Some(entry) => {
+                    if let Some(item) = resolve(entry) {
+                        items.push(item);
+                    }
+                    index += 1;
+                }
+                None => break,
+            }
+        }
+        let has_more = entries.peek().is_some();
+        Self {
+            items,
+            total,
+            has_more,
+            next_cursor: if has_more { Some(index) } else { None },
+        }
+    }
+
+    fn collect<F>(
+        total: u64,
+        from_index: Option<u64>,
+        limit: Option<u64>,
+        gas_budget: Gas,
+        mut item_at: F,
+    ) -> Self
+    where
+        F: FnMut(u64) -> Option<T>,
+    {
+        let (start, end) = page_bounds(from_index, limit, total);
+        let mut items = vec![];
+        let mut index = start;
+        while index < end && (index == start || env::used_gas() < gas_budget) {
+            if let Some(item) = item_at(index) {
+                items.push(item);
+            }
+            index += 1;
+        }
+        let has_more = index < total;
+        Self {
+            items,
+            total,
+            has_more,
+            next_cursor: if has_more { Some(index) } else { None },
+        }
+    }
+}
+
+fn page_bounds(from_index: Option<u64>, limit: Option<u64>, total: u64) -> (u64, u64) {
+    let limit = limit.unwrap_or(u64::MAX);
+    assert_ne!(limit, 0, "Cannot provide limit of 0.");
+    let start = cmp::min(from_index.unwrap_or(0), total);
+    (start, cmp::min(total, start.saturating_add(limit)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::get_context;
+    use near_sdk::testing_env;
+    use near_sdk::MockedBlockchain;
+
+    #[test]
+    fn test_page_stops_at_gas_budget() {
+        let context = get_context(vec![], false);
+        testing_env!(context);
+
+        // Enough for the first check to pass but far less than a single storage write.
+        let gas_budget = env::used_gas() + 10_000_000_000;
+        let page: Page<u64> = Page::collect(10, None, None, gas_budget, |index| {
+            env::storage_write(&index.to_le_bytes(), &[0]);
+            Some(index)
+        });
+        assert_eq!(page.items, vec![0]);
+        assert_eq!(page.total, 10);
+        assert!(page.has_more);
+        assert_eq!(page.next_cursor, Some(1));
+    }
+
+    #[test]
+    fn test_page_advances_past_spent_budget() {
+        let context = get_context(vec![], false);
+        testing_env!(context);
+
+        let page: Page<u64> = Page::collect(10, Some(3), None, 0, Some);
+        assert_eq!(page.items, vec![3]);
+        assert_eq!(page.next_cursor, Some(4));
+    }
+}
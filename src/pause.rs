@@ -0,0 +1,62 @@
+use crate::*;
+
+#[near_bindgen]
+impl Contract {
+    /// Freezes every mutating method, e.g. during an upgrade or an incident. Views, owner
+    /// settings and callbacks of calls already in flight keep working.
+    pub fn pause(&mut self) {
+        self.assert_owner();
+        self.paused = true;
+    }
+
+    pub fn unpause(&mut self) {
+        self.assert_owner();
+        self.assert_not_migrating();
+        self.paused = false;
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+}
+
+impl Contract {
+    /// Guard at the top of every mutating method: the contract must not be paused, the caller
+    /// must not be blocked, and the call counts against the caller's rate limit.
+    pub(crate) fn assert_can_mutate(&mut self) {
+        assert!(!self.paused, "Contract is paused");
+        self.assert_not_blocked();
+        self.internal_count_write();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{get_context, get_contract, get_sample_book};
+    use near_sdk::testing_env;
+    use near_sdk::MockedBlockchain;
+
+    #[test]
+    #[should_panic(expected = "Contract is paused")]
+    fn test_paused_rejects_writes() {
+        let mut context = get_context(vec![], false);
+        context.predecessor_account_id = "alice_near".to_string();
+        testing_env!(context.clone());
+        let mut contract = get_contract();
+        contract.pause();
+        assert!(contract.is_paused());
+
+        context.predecessor_account_id = "carol_near".to_string();
+        testing_env!(context);
+        contract.add_book(get_sample_book());
+    }
+
+    #[test]
+    #[should_panic(expected = "Only the owner can call this method")]
+    fn test_pause_owner_only() {
+        let context = get_context(vec![], false);
+        testing_env!(context);
+        let mut contract = get_contract();
+        contract.pause();
+    }
+}
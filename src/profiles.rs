@@ -0,0 +1,142 @@
+use crate::*;
+
+const MAX_FAVORITE_GENRES: usize = 10;
+const MAX_PINNED_BOOKS: usize = 5;
+
+/// What an account says about itself. Pinned books are shown at the top of its profile.
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Default)]
+#[serde(crate = "near_sdk::serde")]
+pub struct Profile {
+    pub display_name: Option<String>,
+    pub bio: Option<String>,
+    #[serde(default)]
+    pub favorite_genres: Vec<String>,
+    #[serde(default)]
+    pub pinned_book_ids: Vec<BookId>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct ProfileView {
+    account_id: AccountId,
+    display_name: Option<String>,
+    bio: Option<String>,
+    favorite_genres: Vec<String>,
+    pinned_books: Vec<Book>,
+    stats: AccountStats,
+    followers: u64,
+    following: u64,
+}
+
+#[near_bindgen]
+impl Contract {
+    /// Replaces the caller's profile. Pinned books must be in the caller's library.
+    pub fn update_profile(&mut self, profile: Profile) {
+        self.assert_can_mutate();
+        let account_id = self.internal_caller_id();
+        let initial_storage = env::storage_usage();
+
+        let config = self.internal_config();
+        if let Some(display_name) = &profile.display_name {
+            assert!(
+                display_name.len() <= config.max_title_length as usize,
+                "Display name exceeds {} characters",
+                config.max_title_length
+            );
+        }
+        if let Some(bio) = &profile.bio {
+            assert!(
+                bio.len() <= config.max_description_length as usize,
+                "Bio exceeds {} characters",
+                config.max_description_length
+            );
+        }
+        assert!(
+            profile.favorite_genres.len() <= MAX_FAVORITE_GENRES,
+            "At most {} favorite genres",
+            MAX_FAVORITE_GENRES
+        );
+        assert!(
+            profile.pinned_book_ids.len() <= MAX_PINNED_BOOKS,
+            "At most {} pinned books",
+            MAX_PINNED_BOOKS
+        );
+        for book_id in &profile.pinned_book_ids {
+            self.assert_book_owner(&account_id, book_id);
+        }
+
+        self.profiles_by_account_id.insert(&account_id, &profile);
+        self.internal_charge_storage(&account_id, initial_storage);
+    }
+
+    /// An account's public profile with its reading stats. Pinned books that were removed or
+    /// are not public are left out.
+    pub fn get_profile(&self, account_id: ValidAccountId) -> ProfileView {
+        let profile = self
+            .profiles_by_account_id
+            .get(account_id.as_ref())
+            .unwrap_or_default();
+        let count = |sets: &UnorderedMap<AccountId, UnorderedSet<AccountId>>| {
+            sets.get(account_id.as_ref()).map_or(0, |set| set.len())
+        };
+        ProfileView {
+            account_id: account_id.to_string(),
+            display_name: profile.display_name,
+            bio: profile.bio,
+            favorite_genres: profile.favorite_genres,
+            pinned_books: profile
+                .pinned_book_ids
+                .iter()
+                .filter_map(|book_id| self.internal_get_listed_book(book_id))
+                .filter(|book| book.account_id.as_deref() == Some(account_id.as_ref()))
+                .collect(),
+            stats: self.get_stats(account_id.clone()),
+            followers: count(&self.followers_by_account_id),
+            following: count(&self.following_by_account_id),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{get_context, get_contract, get_sample_book};
+    use near_sdk::testing_env;
+    use near_sdk::MockedBlockchain;
+    use std::convert::TryFrom;
+
+    #[test]
+    fn test_update_profile() {
+        let context = get_context(vec![], false);
+        testing_env!(context);
+        let mut contract = get_contract();
+        let book_id = contract.add_book(get_sample_book());
+        contract.update_profile(Profile {
+            display_name: Some("Carol".to_string()),
+            bio: None,
+            favorite_genres: vec!["DIY".to_string()],
+            pinned_book_ids: vec![book_id.clone()],
+        });
+
+        let profile = contract.get_profile(ValidAccountId::try_from("carol_near").unwrap());
+        assert_eq!(profile.display_name, Some("Carol".to_string()));
+        assert_eq!(profile.pinned_books[0].book_id, Some(book_id));
+    }
+
+    #[test]
+    #[should_panic(expected = "Book does not exist")]
+    fn test_pin_other_accounts_book() {
+        let mut context = get_context(vec![], false);
+        testing_env!(context.clone());
+        let mut contract = get_contract();
+        let book_id = contract.add_book(get_sample_book());
+
+        context.predecessor_account_id = "dave_near".to_string();
+        context.storage_usage = env::storage_usage();
+        testing_env!(context);
+        contract.update_profile(Profile {
+            pinned_book_ids: vec![book_id],
+            ..Default::default()
+        });
+    }
+}
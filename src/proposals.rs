@@ -0,0 +1,244 @@
+use crate::*;
+use near_sdk::json_types::Base64VecU8;
+use near_sdk::Promise;
+
+const GAS_FOR_PROPOSAL_CALL: Gas = 50_000_000_000_000;
+/// Owner-only methods a proposal can call. Callbacks and user methods are left out so an
+/// approved proposal cannot act as the contract anywhere else.
+const PROPOSAL_METHODS: &[&str] = &[
+    "add_role",
+    "block_account",
+    "pause",
+    "remove_role",
+    "set_badge_contract",
+    "set_council",
+    "set_cron_config",
+    "set_finish_reward",
+    "set_max_books_per_account",
+    "set_owner",
+    "set_rate_limit",
+    "set_tip_fee",
+    "set_trusted_forwarders",
+    "unblock_account",
+    "unpause",
+    "update_config",
+    "withdraw_tip_fees",
+];
+
+/// Accounts that share the owner's powers. Once set, owner-only methods only run through a
+/// proposal approved by `threshold` members.
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct Council {
+    pub members: Vec<AccountId>,
+    pub threshold: u32,
+}
+
+/// A pending call to one of the owner-only methods, with JSON `args`.
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct Proposal {
+    pub proposer_id: AccountId,
+    pub method_name: String,
+    pub args: Base64VecU8,
+    pub approvals: Vec<AccountId>,
+    pub proposed_at: U64,
+}
+
+#[near_bindgen]
+impl Contract {
+    /// Hands the owner's powers to a council, e.g. the signers of a multisig, or back to the
+    /// owner with `None`.
+    pub fn set_council(&mut self, council: Option<Council>) {
+        self.assert_owner();
+        if let Some(council) = &council {
+            for member_id in &council.members {
+                assert!(
+                    env::is_valid_account_id(member_id.as_bytes()),
+                    "Invalid council member"
+                );
+            }
+            assert!(
+                council.threshold > 0 && council.threshold as usize <= council.members.len(),
+                "Threshold must be between 1 and the number of members"
+            );
+        }
+        self.council = council;
+    }
+
+    pub fn get_council(&self) -> Option<Council> {
+        self.council.clone()
+    }
+
+    /// Proposes calling one of the owner-only methods with `args` and counts as the
+    /// proposer's approval. Returns the proposal id.
+    pub fn propose(&mut self, method_name: String, args: Base64VecU8) -> u64 {
+        let proposer_id = env::predecessor_account_id();
+        self.assert_council_member(&proposer_id);
+        assert!(
+            PROPOSAL_METHODS.contains(&method_name.as_str()),
+            "Method cannot be proposed"
+        );
+
+        let proposal_id = self.proposals_len;
+        self.proposals.insert(
+            &proposal_id,
+            &Proposal {
+                proposer_id: proposer_id.clone(),
+                method_name,
+                args,
+                approvals: vec![proposer_id],
+                proposed_at: env::block_timestamp().into(),
+            },
+        );
+        self.proposals_len += 1;
+
+        proposal_id
+    }
+
+    pub fn approve_proposal(&mut self, proposal_id: u64) {
+        let account_id = env::predecessor_account_id();
+        self.assert_council_member(&account_id);
+        let mut proposal = self
+            .proposals
+            .get(&proposal_id)
+            .expect("Proposal does not exist");
+        if !proposal.approvals.contains(&account_id) {
+            proposal.approvals.push(account_id);
+            self.proposals.insert(&proposal_id, &proposal);
+        }
+    }
+
+    /// Withdraws a proposal. Only its proposer can cancel it.
+    pub fn cancel_proposal(&mut self, proposal_id: u64) {
+        let proposal = self
+            .proposals
+            .get(&proposal_id)
+            .expect("Proposal does not exist");
+        assert_eq!(
+            env::predecessor_account_id(),
+            proposal.proposer_id,
+            "Only the proposer can cancel a proposal"
+        );
+        self.proposals.remove(&proposal_id);
+    }
+
+    /// Runs an approved proposal by calling the contract itself, so the method sees the
+    /// contract as its caller. Anyone can execute it once enough members approve, and the
+    /// proposal is used up even if the call fails.
+    pub fn execute_proposal(&mut self, proposal_id: u64) -> Promise {
+        let proposal = self
+            .proposals
+            .get(&proposal_id)
+            .expect("Proposal does not exist");
+        let threshold = self.council.as_ref().map_or(1, |council| council.threshold);
+        let approvals = proposal
+            .approvals
+            .iter()
+            .filter(|account_id| self.internal_is_council_member(account_id))
+            .count();
+        assert!(
+            approvals >= threshold as usize,
+            "Proposal needs {} approvals",
+            threshold
+        );
+
+        self.proposals.remove(&proposal_id);
+        Promise::new(env::current_account_id()).function_call(
+            proposal.method_name.into_bytes(),
+            proposal.args.into(),
+            0,
+            GAS_FOR_PROPOSAL_CALL,
+        )
+    }
+
+    pub fn get_proposals(
+        &self,
+        from_index: Option<u64>,
+        limit: Option<u64>,
+    ) -> Page<(u64, Proposal)> {
+        Page::from_vector(
+            self.proposals.keys_as_vector(),
+            from_index,
+            limit,
+            |proposal_id| {
+                self.proposals
+                    .get(&proposal_id)
+                    .map(|proposal| (proposal_id, proposal))
+            },
+        )
+    }
+}
+
+impl Contract {
+    /// Without a council the owner alone makes and approves proposals.
+    pub(crate) fn internal_is_council_member(&self, account_id: &AccountId) -> bool {
+        match &self.council {
+            Some(council) => council.members.contains(account_id),
+            None => account_id == &self.owner_id,
+        }
+    }
+
+    fn assert_council_member(&self, account_id: &AccountId) {
+        assert!(
+            self.internal_is_council_member(account_id),
+            "Only council members can call this method"
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{get_context, get_new_contract};
+    use near_sdk::testing_env;
+    use near_sdk::MockedBlockchain;
+
+    fn get_council() -> Council {
+        Council {
+            members: vec!["carol_near".to_string(), "dave_near".to_string()],
+            threshold: 2,
+        }
+    }
+
+    #[test]
+    fn test_proposal_flow() {
+        let mut context = get_context(vec![], false);
+        testing_env!(context.clone());
+        let mut contract = get_new_contract();
+        contract.council = Some(get_council());
+
+        let proposal_id = contract.propose("pause".to_string(), b"{}".to_vec().into());
+        context.predecessor_account_id = "dave_near".to_string();
+        context.storage_usage = env::storage_usage();
+        testing_env!(context);
+        contract.approve_proposal(proposal_id);
+        contract.execute_proposal(proposal_id);
+
+        assert_eq!(contract.get_proposals(None, None).total, 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "Proposal needs 2 approvals")]
+    fn test_execute_proposal_without_quorum() {
+        let context = get_context(vec![], false);
+        testing_env!(context);
+        let mut contract = get_new_contract();
+        contract.council = Some(get_council());
+
+        let proposal_id = contract.propose("pause".to_string(), b"{}".to_vec().into());
+        contract.execute_proposal(proposal_id);
+    }
+
+    #[test]
+    #[should_panic(expected = "Owner actions must go through an approved proposal")]
+    fn test_council_blocks_owner() {
+        let mut context = get_context(vec![], false);
+        context.predecessor_account_id = "bob_near".to_string();
+        testing_env!(context);
+        let mut contract = get_new_contract();
+        contract.owner_id = "bob_near".to_string();
+        contract.council = Some(get_council());
+        contract.pause();
+    }
+}
@@ -0,0 +1,109 @@
+use crate::*;
+
+#[near_bindgen]
+impl Contract {
+    /// Caps how many books each account can keep in its library; `None` lifts the cap. Accounts
+    /// already above a new cap keep their books but cannot add more.
+    pub fn set_max_books_per_account(&mut self, max_books_per_account: Option<u64>) {
+        self.update_config(ConfigPatch {
+            max_books_per_account: Some(max_books_per_account),
+            ..Default::default()
+        });
+    }
+}
+
+impl Contract {
+    /// Checks that `account_id`'s library has room for `books_count` more books.
+    pub(crate) fn assert_book_quota(&self, account_id: &AccountId, books_count: u64) {
+        if let Some(max_books) = self.internal_config().max_books_per_account {
+            assert!(
+                self.internal_has_book_quota(account_id, books_count),
+                "Library is full at {} books, delete some books or ask for more capacity",
+                max_books
+            );
+        }
+    }
+
+    /// Whether `account_id`'s library has room for `books_count` more books.
+    pub(crate) fn internal_has_book_quota(&self, account_id: &AccountId, books_count: u64) -> bool {
+        self.internal_config()
+            .max_books_per_account
+            .is_none_or(|max_books| {
+                let current_count = self
+                    .books_by_owner_id
+                    .get(account_id)
+                    .map_or(0, |book_ids| book_ids.len());
+                current_count + books_count <= max_books
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{get_context, get_contract, get_sample_book};
+    use near_sdk::testing_env;
+    use near_sdk::MockedBlockchain;
+
+    #[test]
+    #[should_panic(
+        expected = "Library is full at 1 books, delete some books or ask for more capacity"
+    )]
+    fn test_book_quota() {
+        let mut context = get_context(vec![], false);
+        context.attached_deposit = 1;
+        testing_env!(context);
+        let mut contract = get_contract();
+        let mut config = contract.get_config();
+        config.max_books_per_account = Some(1);
+        contract.internal_set_config(&config);
+        let book_id = contract.add_book(get_sample_book());
+        contract.delete_book(book_id);
+        contract.add_book(get_sample_book());
+        contract.add_book(get_sample_book());
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Library is full at 1 books, delete some books or ask for more capacity"
+    )]
+    fn test_book_quota_on_transfer() {
+        let mut context = get_context(vec![], false);
+        context.attached_deposit = 1;
+        testing_env!(context.clone());
+        let mut contract = get_contract();
+        let book_id = contract.add_book(get_sample_book());
+
+        context.predecessor_account_id = "dave_near".to_string();
+        context.storage_usage = near_sdk::env::storage_usage();
+        testing_env!(context);
+        contract.add_book(get_sample_book());
+        let mut config = contract.get_config();
+        config.max_books_per_account = Some(1);
+        contract.internal_set_config(&config);
+        contract.internal_transfer_book(
+            &"carol_near".to_string(),
+            &"dave_near".to_string(),
+            &book_id,
+            None,
+            None,
+        );
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Library is full at 1 books, delete some books or ask for more capacity"
+    )]
+    fn test_book_quota_on_restore() {
+        let mut context = get_context(vec![], false);
+        context.attached_deposit = 1;
+        testing_env!(context);
+        let mut contract = get_contract();
+        let book_id = contract.add_book(get_sample_book());
+        contract.archive_book(book_id.clone());
+        contract.add_book(get_sample_book());
+        let mut config = contract.get_config();
+        config.max_books_per_account = Some(1);
+        contract.internal_set_config(&config);
+        contract.restore_book(book_id);
+    }
+}
@@ -0,0 +1,201 @@
+use crate::*;
+
+pub type QuoteId = String;
+
+/// A passage saved from one of the owner's books. Public quotes show up in the quote feeds.
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct Quote {
+    quote_id: QuoteId,
+    book_id: BookId,
+    account_id: AccountId,
+    text: String,
+    page: Option<u64>,
+    public: bool,
+    created_at: U64,
+}
+
+#[near_bindgen]
+impl Contract {
+    pub fn add_quote(
+        &mut self,
+        book_id: BookId,
+        text: String,
+        page: Option<u64>,
+        public: bool,
+    ) -> QuoteId {
+        self.assert_can_mutate();
+        let account_id = self.internal_library_owner(&book_id);
+        let initial_storage = env::storage_usage();
+        assert!(!text.is_empty(), "Quote cannot be empty");
+        let max_length = self.internal_config().max_description_length;
+        assert!(
+            text.len() <= max_length as usize,
+            "Quote exceeds {} characters",
+            max_length
+        );
+
+        let quote_id = format!("{}", self.quotes_len + 1);
+        self.quotes.insert(
+            &quote_id,
+            &Quote {
+                quote_id: quote_id.clone(),
+                book_id,
+                account_id: account_id.clone(),
+                text,
+                page,
+                public,
+                created_at: env::block_timestamp().into(),
+            },
+        );
+        let mut quote_ids = self
+            .quotes_by_account_id
+            .get(&account_id)
+            .unwrap_or_else(|| {
+                UnorderedSet::new(StorageKey::QuotesPerAccount {
+                    account_hash: env::sha256(account_id.as_bytes()),
+                })
+            });
+        quote_ids.insert(&quote_id);
+        self.quotes_by_account_id.insert(&account_id, &quote_ids);
+        if public {
+            self.public_quote_ids.insert(&quote_id);
+        }
+
+        self.quotes_len += 1;
+        self.internal_charge_storage(&account_id, initial_storage);
+
+        quote_id
+    }
+
+    /// Shares a quote in the public feeds, or takes it back out.
+    pub fn set_quote_public(&mut self, quote_id: QuoteId, public: bool) -> Quote {
+        self.assert_can_mutate();
+        let account_id = self.internal_caller_id();
+        let initial_storage = env::storage_usage();
+        let mut quote = self.internal_owned_quote(&quote_id, &account_id);
+
+        quote.public = public;
+        self.quotes.insert(&quote_id, &quote);
+        if public {
+            self.public_quote_ids.insert(&quote_id);
+        } else {
+            self.public_quote_ids.remove(&quote_id);
+        }
+        self.internal_charge_storage(&account_id, initial_storage);
+
+        quote
+    }
+
+    pub fn delete_quote(&mut self, quote_id: QuoteId) {
+        self.assert_can_mutate();
+        let account_id = self.internal_caller_id();
+        let initial_storage = env::storage_usage();
+        self.internal_owned_quote(&quote_id, &account_id);
+
+        self.quotes.remove(&quote_id);
+        self.public_quote_ids.remove(&quote_id);
+        if let Some(mut quote_ids) = self.quotes_by_account_id.get(&account_id) {
+            quote_ids.remove(&quote_id);
+            if quote_ids.is_empty() {
+                self.quotes_by_account_id.remove(&account_id);
+            } else {
+                self.quotes_by_account_id.insert(&account_id, &quote_ids);
+            }
+        }
+        self.internal_charge_storage(&account_id, initial_storage);
+    }
+
+    /// Public quotes from every reader, most recently shared first.
+    pub fn get_public_quotes(&self, from_index: Option<u64>, limit: Option<u64>) -> Page<Quote> {
+        Page::from_vector_ordered(
+            self.public_quote_ids.as_vector(),
+            from_index,
+            limit,
+            SortOrder::Desc,
+            |quote_id| self.internal_get_public_quote(&quote_id),
+        )
+    }
+
+    pub fn get_quotes_by_account(
+        &self,
+        account_id: ValidAccountId,
+        from_index: Option<u64>,
+        limit: Option<u64>,
+    ) -> Page<Quote> {
+        match self.quotes_by_account_id.get(account_id.as_ref()) {
+            Some(quote_ids) => {
+                Page::from_vector(quote_ids.as_vector(), from_index, limit, |quote_id| {
+                    self.internal_get_public_quote(&quote_id)
+                })
+            }
+            None => Page::empty(),
+        }
+    }
+}
+
+impl Contract {
+    /// A quote as the feeds may return it: public, from a book others can see.
+    fn internal_get_public_quote(&self, quote_id: &QuoteId) -> Option<Quote> {
+        self.quotes.get(quote_id).filter(|quote| {
+            quote.public && self.internal_get_visible_book(&quote.book_id).is_some()
+        })
+    }
+
+    fn internal_owned_quote(&self, quote_id: &QuoteId, account_id: &AccountId) -> Quote {
+        let quote = self.quotes.get(quote_id).expect("Quote does not exist");
+        assert_eq!(
+            &quote.account_id, account_id,
+            "Only the owner can change a quote"
+        );
+        quote
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{get_context, get_contract, get_sample_book};
+    use near_sdk::testing_env;
+    use near_sdk::MockedBlockchain;
+    use std::convert::TryFrom;
+
+    #[test]
+    fn test_public_quotes() {
+        let context = get_context(vec![], false);
+        testing_env!(context);
+        let mut contract = get_contract();
+        let book_id = contract.add_book(get_sample_book());
+        let first_id = contract.add_quote(book_id.clone(), "Check the oil".into(), Some(3), true);
+        contract.add_quote(book_id.clone(), "Private note".into(), None, false);
+        let second_id = contract.add_quote(book_id, "Tighten the chain".into(), None, false);
+        contract.set_quote_public(second_id.clone(), true);
+
+        let feed = contract.get_public_quotes(None, None).items;
+        assert_eq!(feed.len(), 2);
+        assert_eq!(feed[0].quote_id, second_id);
+        assert_eq!(feed[1].quote_id, first_id);
+        assert_eq!(
+            contract
+                .get_quotes_by_account(ValidAccountId::try_from("carol_near").unwrap(), None, None)
+                .items
+                .len(),
+            2
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Only the owner can change a quote")]
+    fn test_set_someone_elses_quote_public() {
+        let mut context = get_context(vec![], false);
+        testing_env!(context.clone());
+        let mut contract = get_contract();
+        let book_id = contract.add_book(get_sample_book());
+        let quote_id = contract.add_quote(book_id, "Check the oil".into(), None, false);
+
+        context.predecessor_account_id = "dave_near".to_string();
+        context.storage_usage = env::storage_usage();
+        testing_env!(context);
+        contract.set_quote_public(quote_id, true);
+    }
+}
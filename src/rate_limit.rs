@@ -0,0 +1,124 @@
+use crate::*;
+
+/// At most `max_writes` mutating calls per account in each window of `window_blocks` blocks.
+/// Every operation of a `batch` counts.
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct RateLimit {
+    pub max_writes: u32,
+    pub window_blocks: u64,
+}
+
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct WriteWindow {
+    window: u64,
+    writes: u32,
+}
+
+#[near_bindgen]
+impl Contract {
+    pub fn set_rate_limit(&mut self, rate_limit: Option<RateLimit>) {
+        self.update_config(ConfigPatch {
+            rate_limit: Some(rate_limit),
+            ..Default::default()
+        });
+    }
+}
+
+impl Contract {
+    /// Counts a write against the caller, who pays for their counter. Unregistered callers,
+    /// e.g. a cron agent, are counted at the contract's expense.
+    pub(crate) fn internal_count_write(&mut self) {
+        let rate_limit = match self.internal_config().rate_limit {
+            Some(rate_limit) => rate_limit,
+            None => return,
+        };
+        let account_id = self.internal_caller_id();
+        let initial_storage = env::storage_usage();
+        let window = env::block_index() / rate_limit.window_blocks;
+        let writes = match self.writes_by_account_id.get(&account_id) {
+            Some(current) if current.window == window => current.writes + 1,
+            _ => 1,
+        };
+        assert!(
+            writes <= rate_limit.max_writes,
+            "Rate limit exceeded, try again in a later block"
+        );
+        self.writes_by_account_id
+            .insert(&account_id, &WriteWindow { window, writes });
+        if self.storage_accounts.get(&account_id).is_some() {
+            self.internal_charge_storage(&account_id, initial_storage);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{get_context, get_contract, get_sample_book};
+    use near_sdk::testing_env;
+    use near_sdk::MockedBlockchain;
+
+    #[test]
+    fn test_rate_limit_resets_next_window() {
+        let mut context = get_context(vec![], false);
+        testing_env!(context.clone());
+        let mut contract = get_contract();
+        let mut config = contract.get_config();
+        config.rate_limit = Some(RateLimit {
+            max_writes: 2,
+            window_blocks: 10,
+        });
+        contract.internal_set_config(&config);
+        contract.add_book(get_sample_book());
+        contract.add_book(get_sample_book());
+
+        context.block_index = 10;
+        context.storage_usage = env::storage_usage();
+        testing_env!(context);
+        contract.add_book(get_sample_book());
+    }
+
+    #[test]
+    #[should_panic(expected = "Rate limit exceeded, try again in a later block")]
+    fn test_rate_limit_exceeded() {
+        let context = get_context(vec![], false);
+        testing_env!(context);
+        let mut contract = get_contract();
+        let mut config = contract.get_config();
+        config.rate_limit = Some(RateLimit {
+            max_writes: 2,
+            window_blocks: 10,
+        });
+        contract.internal_set_config(&config);
+        for _ in 0..3 {
+            contract.add_book(get_sample_book());
+        }
+    }
+
+    #[test]
+    fn test_rate_limit_counter_is_charged() {
+        let mut context = get_context(vec![], false);
+        testing_env!(context.clone());
+        let mut contract = get_contract();
+        let mut config = contract.get_config();
+        config.rate_limit = Some(RateLimit {
+            max_writes: 2,
+            window_blocks: 10,
+        });
+        contract.internal_set_config(&config);
+        let used_bytes = |contract: &Contract| {
+            contract
+                .storage_accounts
+                .get(&"carol_near".to_string())
+                .unwrap()
+                .used_bytes
+        };
+        let before = used_bytes(&contract);
+
+        context.storage_usage = env::storage_usage();
+        testing_env!(context);
+        contract.assert_can_mutate();
+        assert!(used_bytes(&contract) > before);
+    }
+}
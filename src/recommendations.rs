@@ -0,0 +1,216 @@
+use crate::*;
+
+pub type RecommendationId = String;
+
+const MAX_INBOX_RECOMMENDATIONS: u64 = 100;
+
+/// A book suggested to another account. The sender pays for its storage until the recipient
+/// accepts or dismisses it.
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct Recommendation {
+    recommendation_id: RecommendationId,
+    sender_id: AccountId,
+    book_id: BookId,
+    message: Option<String>,
+    created_at: U64,
+}
+
+#[near_bindgen]
+impl Contract {
+    /// Sends a book the caller can see to another account's inbox.
+    pub fn recommend_book(
+        &mut self,
+        to_account: ValidAccountId,
+        book_id: BookId,
+        message: Option<String>,
+    ) -> RecommendationId {
+        self.assert_can_mutate();
+        let sender_id = self.internal_caller_id();
+        let initial_storage = env::storage_usage();
+        let recipient_id: AccountId = to_account.into();
+        assert_ne!(
+            sender_id, recipient_id,
+            "Cannot recommend a book to yourself"
+        );
+        assert!(
+            self.internal_get_visible_book(&book_id).is_some(),
+            "Book does not exist"
+        );
+        if let Some(message) = &message {
+            let max_length = self.internal_config().max_description_length;
+            assert!(
+                message.len() <= max_length as usize,
+                "Message exceeds {} characters",
+                max_length
+            );
+        }
+
+        let mut inbox = self
+            .recommendations_by_account_id
+            .get(&recipient_id)
+            .unwrap_or_else(|| {
+                UnorderedSet::new(StorageKey::RecommendationsPerAccount {
+                    account_hash: env::sha256(recipient_id.as_bytes()),
+                })
+            });
+        assert!(
+            inbox.len() < MAX_INBOX_RECOMMENDATIONS,
+            "Recommendation inbox is full"
+        );
+
+        let recommendation_id = format!("{}", self.recommendations_len + 1);
+        self.recommendations.insert(
+            &recommendation_id,
+            &Recommendation {
+                recommendation_id: recommendation_id.clone(),
+                sender_id: sender_id.clone(),
+                book_id,
+                message,
+                created_at: env::block_timestamp().into(),
+            },
+        );
+        inbox.insert(&recommendation_id);
+        self.recommendations_by_account_id
+            .insert(&recipient_id, &inbox);
+
+        self.recommendations_len += 1;
+        self.internal_charge_storage(&sender_id, initial_storage);
+
+        recommendation_id
+    }
+
+    /// Adds a copy of the recommended book to the caller's List shelf and clears the
+    /// recommendation. Returns the new book's id.
+    pub fn accept_recommendation(&mut self, recommendation_id: RecommendationId) -> BookId {
+        self.assert_can_mutate();
+        let account_id = self.internal_caller_id();
+        let recommendation = self.internal_take_recommendation(&account_id, &recommendation_id);
+        let book = self
+            .internal_get_visible_book(&recommendation.book_id)
+            .expect("Recommended book is no longer available");
+
+        self.internal_add_book(
+            &account_id,
+            Book {
+                book_id: None,
+                account_id: None,
+                status: Status::List,
+                rating: None,
+                tags: vec![],
+                created_at: None,
+                updated_at: None,
+                finished_at: None,
+                finished_at_self_reported: false,
+                visibility: Visibility::default(),
+                ..book
+            },
+        )
+    }
+
+    pub fn dismiss_recommendation(&mut self, recommendation_id: RecommendationId) {
+        self.assert_can_mutate();
+        let account_id = self.internal_caller_id();
+        self.internal_take_recommendation(&account_id, &recommendation_id);
+    }
+
+    pub fn get_recommendations(
+        &self,
+        account_id: ValidAccountId,
+        from_index: Option<u64>,
+        limit: Option<u64>,
+    ) -> Page<Recommendation> {
+        match self.recommendations_by_account_id.get(account_id.as_ref()) {
+            Some(inbox) => {
+                Page::from_vector(inbox.as_vector(), from_index, limit, |recommendation_id| {
+                    self.recommendations.get(&recommendation_id)
+                })
+            }
+            None => Page::empty(),
+        }
+    }
+}
+
+impl Contract {
+    /// Removes a recommendation from the recipient's inbox and releases its storage back to
+    /// the sender.
+    fn internal_take_recommendation(
+        &mut self,
+        recipient_id: &AccountId,
+        recommendation_id: &RecommendationId,
+    ) -> Recommendation {
+        let initial_storage = env::storage_usage();
+        let mut inbox = self
+            .recommendations_by_account_id
+            .get(recipient_id)
+            .expect("Recommendation does not exist");
+        assert!(
+            inbox.remove(recommendation_id),
+            "Recommendation does not exist"
+        );
+        if inbox.is_empty() {
+            self.recommendations_by_account_id.remove(recipient_id);
+        } else {
+            self.recommendations_by_account_id
+                .insert(recipient_id, &inbox);
+        }
+
+        let recommendation = self.recommendations.remove(recommendation_id).unwrap();
+        self.internal_charge_storage(&recommendation.sender_id, initial_storage);
+        recommendation
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{get_context, get_contract, get_sample_book};
+    use near_sdk::testing_env;
+    use near_sdk::MockedBlockchain;
+    use std::convert::TryFrom;
+
+    #[test]
+    fn test_accept_recommendation() {
+        let mut context = get_context(vec![], false);
+        testing_env!(context.clone());
+        let mut contract = get_contract();
+        let book_id = contract.add_book(Book {
+            status: Status::Finished,
+            rating: Some(5),
+            ..get_sample_book()
+        });
+        let dave = ValidAccountId::try_from("dave_near").unwrap();
+        let recommendation_id =
+            contract.recommend_book(dave.clone(), book_id, Some("You'll love it".into()));
+        assert_eq!(
+            contract.get_recommendations(dave.clone(), None, None).total,
+            1
+        );
+
+        context.predecessor_account_id = "dave_near".to_string();
+        context.storage_usage = env::storage_usage();
+        testing_env!(context);
+        let new_id = contract.accept_recommendation(recommendation_id);
+
+        let book = contract.get_book(new_id);
+        assert_eq!(book.account_id, Some("dave_near".to_string()));
+        assert_eq!(book.status, Status::List);
+        assert_eq!(book.rating, None);
+        assert_eq!(contract.get_recommendations(dave, None, None).total, 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "Recommendation does not exist")]
+    fn test_dismiss_someone_elses_recommendation() {
+        let context = get_context(vec![], false);
+        testing_env!(context);
+        let mut contract = get_contract();
+        let book_id = contract.add_book(get_sample_book());
+        let recommendation_id = contract.recommend_book(
+            ValidAccountId::try_from("dave_near").unwrap(),
+            book_id,
+            None,
+        );
+        contract.dismiss_recommendation(recommendation_id);
+    }
+}
@@ -0,0 +1,81 @@
+use crate::*;
+
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, PartialEq, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub enum RelationKind {
+    Sequel,
+    Prequel,
+    Companion,
+}
+
+#[near_bindgen]
+impl Contract {
+    /// Links one of the caller's books to another book. Linking the same pair again replaces the
+    /// relation kind.
+    pub fn link_books(
+        &mut self,
+        book_id: BookId,
+        other_id: BookId,
+        kind: RelationKind,
+    ) -> Vec<(BookId, RelationKind)> {
+        self.assert_can_mutate();
+        let account_id = self.internal_library_owner(&book_id);
+        let initial_storage = env::storage_usage();
+        assert_ne!(book_id, other_id, "Cannot link a book to itself");
+        assert!(
+            self.books.get(&other_id).is_some(),
+            "Related book does not exist"
+        );
+
+        let mut related = self.related_by_book_id.get(&book_id).unwrap_or_default();
+        related.retain(|(related_id, _)| related_id != &other_id);
+        related.push((other_id, kind));
+        self.related_by_book_id.insert(&book_id, &related);
+
+        let mut book = self.books.get(&book_id).unwrap();
+        self.internal_touch_book(&account_id, &mut book);
+        self.books.insert(&book_id, &book);
+        self.internal_charge_book_update(&account_id, &book_id, initial_storage);
+
+        related
+    }
+
+    pub fn get_related_books(&self, book_id: BookId) -> Vec<(BookId, RelationKind)> {
+        self.related_by_book_id.get(&book_id).unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{get_context, get_contract, get_sample_book};
+    use near_sdk::testing_env;
+    use near_sdk::MockedBlockchain;
+
+    #[test]
+    fn test_link_books() {
+        let context = get_context(vec![], false);
+        testing_env!(context);
+        let mut contract = get_contract();
+        let book_id = contract.add_book(get_sample_book());
+        let sequel_id = contract.add_book(get_sample_book());
+
+        contract.link_books(book_id.clone(), sequel_id.clone(), RelationKind::Companion);
+        contract.link_books(book_id.clone(), sequel_id.clone(), RelationKind::Sequel);
+
+        assert_eq!(
+            contract.get_related_books(book_id),
+            vec![(sequel_id, RelationKind::Sequel)]
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Related book does not exist")]
+    fn test_link_books_missing_other() {
+        let context = get_context(vec![], false);
+        testing_env!(context);
+        let mut contract = get_contract();
+        let book_id = contract.add_book(get_sample_book());
+        contract.link_books(book_id, "42".to_string(), RelationKind::Sequel);
+    }
+}
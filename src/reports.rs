@@ -0,0 +1,214 @@
+use crate::*;
+
+/// Public content anyone can report.
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, PartialEq, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub enum ReportTarget {
+    Book(BookId),
+    Review(ReviewId),
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct Report {
+    reporter_id: AccountId,
+    reason: String,
+    created_at: U64,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct ReportedTarget {
+    target: ReportTarget,
+    report_count: u64,
+    hidden: bool,
+}
+
+#[near_bindgen]
+impl Contract {
+    /// Reports a book or review, once per account. Content with `report_hide_threshold`
+    /// reports is hidden from public views until a moderator dismisses the reports.
+    pub fn report(&mut self, target: ReportTarget, reason: String) -> u64 {
+        self.assert_can_mutate();
+        let reporter_id = self.internal_caller_id();
+        let initial_storage = env::storage_usage();
+        let config = self.internal_config();
+        assert!(
+            reason.len() <= config.max_title_length as usize,
+            "Reason exceeds {} characters",
+            config.max_title_length
+        );
+        let exists = match &target {
+            ReportTarget::Book(book_id) => self.internal_get_visible_book(book_id).is_some(),
+            ReportTarget::Review(review_id) => {
+                self.internal_get_visible_review(review_id).is_some()
+            }
+        };
+        assert!(exists, "Reported content does not exist");
+
+        let mut reporter_ids = self.reporters_by_target.get(&target).unwrap_or_else(|| {
+            UnorderedSet::new(StorageKey::ReportersPerTarget {
+                target_hash: env::sha256(&target.try_to_vec().unwrap()),
+            })
+        });
+        assert!(
+            reporter_ids.insert(&reporter_id),
+            "You already reported this"
+        );
+        self.reporters_by_target.insert(&target, &reporter_ids);
+        self.reports.insert(
+            &(target.clone(), reporter_id.clone()),
+            &Report {
+                reporter_id: reporter_id.clone(),
+                reason,
+                created_at: env::block_timestamp().into(),
+            },
+        );
+
+        let report_count = reporter_ids.len();
+        if config
+            .report_hide_threshold
+            .is_some_and(|threshold| report_count >= threshold)
+        {
+            self.hidden_targets.insert(&target);
+        }
+        self.internal_charge_storage(&reporter_id, initial_storage);
+
+        report_count
+    }
+
+    /// Clears the reports on a target, e.g. after a moderator found nothing wrong, and shows it
+    /// again. Each reporter gets their storage back.
+    pub fn dismiss_reports(&mut self, target: ReportTarget) {
+        self.assert_role(Role::Moderator);
+        assert!(
+            self.reporters_by_target.get(&target).is_some(),
+            "No reports for this content"
+        );
+        self.internal_clear_reports(&target);
+    }
+
+    /// The moderation queue: all reported content with its report count.
+    pub fn get_reported_targets(
+        &self,
+        from_index: Option<u64>,
+        limit: Option<u64>,
+    ) -> Page<ReportedTarget> {
+        Page::from_vector(
+            self.reporters_by_target.keys_as_vector(),
+            from_index,
+            limit,
+            |target| {
+                self.reporters_by_target
+                    .get(&target)
+                    .map(|reporter_ids| ReportedTarget {
+                        hidden: self.hidden_targets.contains(&target),
+                        report_count: reporter_ids.len(),
+                        target,
+                    })
+            },
+        )
+    }
+
+    pub fn get_reports(
+        &self,
+        target: ReportTarget,
+        from_index: Option<u64>,
+        limit: Option<u64>,
+    ) -> Page<Report> {
+        match self.reporters_by_target.get(&target) {
+            Some(reporter_ids) => {
+                Page::from_vector(reporter_ids.as_vector(), from_index, limit, |reporter_id| {
+                    self.reports.get(&(target.clone(), reporter_id))
+                })
+            }
+            None => Page::empty(),
+        }
+    }
+}
+
+impl Contract {
+    /// Drops every report on a target, releasing each reporter's storage, and unhides it.
+    /// Also called when a moderator removes a reported book or its reviewer deletes a review.
+    pub(crate) fn internal_clear_reports(&mut self, target: &ReportTarget) {
+        if let Some(mut reporter_ids) = self.reporters_by_target.remove(target) {
+            for reporter_id in reporter_ids.to_vec() {
+                let initial_storage = env::storage_usage();
+                self.reports.remove(&(target.clone(), reporter_id.clone()));
+                reporter_ids.remove(&reporter_id);
+                self.internal_charge_storage(&reporter_id, initial_storage);
+            }
+        }
+        self.hidden_targets.remove(target);
+    }
+
+    /// Whether reports have hidden a target from public views.
+    pub(crate) fn internal_is_hidden(&self, target: &ReportTarget) -> bool {
+        self.hidden_targets.contains(target)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{get_context, get_contract, get_sample_book};
+    use near_sdk::testing_env;
+    use near_sdk::MockedBlockchain;
+    use std::convert::TryFrom;
+
+    #[test]
+    fn test_reports_hide_book() {
+        let mut context = get_context(vec![], false);
+        testing_env!(context.clone());
+        let mut contract = get_contract();
+        let mut config = contract.get_config();
+        config.report_hide_threshold = Some(2);
+        contract.internal_set_config(&config);
+        let book_id = contract.add_book(get_sample_book());
+        let target = ReportTarget::Book(book_id.clone());
+
+        for account_id in ["dave_near", "erin_near"] {
+            context.predecessor_account_id = account_id.to_string();
+            context.storage_usage = env::storage_usage();
+            testing_env!(context.clone());
+            contract.report(target.clone(), "Spam".into());
+        }
+        let carol = ValidAccountId::try_from("carol_near").unwrap();
+        assert_eq!(
+            contract
+                .get_books(Some(carol.clone()), None, None, None, None, None)
+                .items
+                .len(),
+            0
+        );
+        assert!(contract.get_reported_targets(None, None).items[0].hidden);
+
+        context.predecessor_account_id = "alice_near".to_string();
+        context.storage_usage = env::storage_usage();
+        testing_env!(context);
+        contract.dismiss_reports(target);
+        assert_eq!(
+            contract
+                .get_books(Some(carol), None, None, None, None, None)
+                .items
+                .len(),
+            1
+        );
+        assert_eq!(contract.get_reported_targets(None, None).total, 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "You already reported this")]
+    fn test_report_twice() {
+        let mut context = get_context(vec![], false);
+        testing_env!(context.clone());
+        let mut contract = get_contract();
+        let book_id = contract.add_book(get_sample_book());
+
+        context.predecessor_account_id = "dave_near".to_string();
+        context.storage_usage = env::storage_usage();
+        testing_env!(context);
+        contract.report(ReportTarget::Book(book_id.clone()), "Spam".into());
+        contract.report(ReportTarget::Book(book_id), "Spam".into());
+    }
+}
@@ -0,0 +1,297 @@
+use crate::*;
+
+pub type ReviewId = String;
+
+#[derive(Serialize, Deserialize, Clone, Copy)]
+#[serde(crate = "near_sdk::serde")]
+pub enum ReviewSort {
+    Oldest,
+    Helpful,
+}
+
+/// A public review of a book. Reviews belong to the reviewer, not to the book's owner, and
+/// are kept apart from the owner's own record of the book.
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct Review {
+    review_id: ReviewId,
+    pub(crate) book_id: BookId,
+    pub(crate) reviewer: AccountId,
+    text: String,
+    rating: u8,
+    created_at: U64,
+    updated_at: U64,
+    upvotes: u64,
+}
+
+#[near_bindgen]
+impl Contract {
+    /// Reviews a book the caller can see. Each account reviews a book at most once; use
+    /// `edit_review` to change it.
+    pub fn add_review(&mut self, book_id: BookId, text: String, rating: u8) -> ReviewId {
+        self.assert_can_mutate();
+        let reviewer = self.internal_caller_id();
+        let initial_storage = env::storage_usage();
+        assert!(
+            self.internal_get_visible_book(&book_id).is_some(),
+            "Book does not exist"
+        );
+        assert!(
+            self.review_ids_by_book_reviewer
+                .get(&(book_id.clone(), reviewer.clone()))
+                .is_none(),
+            "You already reviewed this book"
+        );
+        self.assert_valid_review(&text, rating);
+
+        let review_id = format!("{}", self.reviews_len + 1);
+        let now = env::block_timestamp().into();
+        self.reviews.insert(
+            &review_id,
+            &Review {
+                review_id: review_id.clone(),
+                book_id: book_id.clone(),
+                reviewer: reviewer.clone(),
+                text,
+                rating,
+                created_at: now,
+                updated_at: now,
+                upvotes: 0,
+            },
+        );
+
+        let mut review_ids = self.reviews_by_book_id.get(&book_id).unwrap_or_else(|| {
+            UnorderedSet::new(StorageKey::ReviewsPerBook {
+                book_hash: env::sha256(book_id.as_bytes()),
+            })
+        });
+        review_ids.insert(&review_id);
+        self.reviews_by_book_id.insert(&book_id, &review_ids);
+        self.review_ids_by_book_reviewer
+            .insert(&(book_id, reviewer.clone()), &review_id);
+
+        self.reviews_len += 1;
+        self.internal_charge_storage(&reviewer, initial_storage);
+
+        review_id
+    }
+
+    pub fn edit_review(&mut self, review_id: ReviewId, text: String, rating: u8) -> Review {
+        self.assert_can_mutate();
+        let reviewer = self.internal_caller_id();
+        let initial_storage = env::storage_usage();
+        let mut review = self.internal_reviewer_review(&review_id, &reviewer);
+        self.assert_valid_review(&text, rating);
+
+        review.text = text;
+        review.rating = rating;
+        review.updated_at = env::block_timestamp().into();
+        self.reviews.insert(&review_id, &review);
+        self.internal_charge_storage(&reviewer, initial_storage);
+
+        review
+    }
+
+    pub fn delete_review(&mut self, review_id: ReviewId) {
+        self.assert_can_mutate();
+        let reviewer = self.internal_caller_id();
+        let initial_storage = env::storage_usage();
+        let review = self.internal_reviewer_review(&review_id, &reviewer);
+
+        self.reviews.remove(&review_id);
+        if let Some(mut review_ids) = self.reviews_by_book_id.get(&review.book_id) {
+            review_ids.remove(&review_id);
+            if review_ids.is_empty() {
+                self.reviews_by_book_id.remove(&review.book_id);
+            } else {
+                self.reviews_by_book_id.insert(&review.book_id, &review_ids);
+            }
+        }
+        self.review_ids_by_book_reviewer
+            .remove(&(review.book_id, reviewer.clone()));
+        if let Some(mut upvoters) = self.upvoters_by_review_id.remove(&review_id) {
+            upvoters.clear();
+        }
+        self.internal_charge_storage(&reviewer, initial_storage);
+        self.internal_clear_reports(&ReportTarget::Review(review_id));
+    }
+
+    /// Marks someone else's review as helpful. Each account upvotes a review at most once;
+    /// returns the review's upvote count.
+    pub fn upvote_review(&mut self, review_id: ReviewId) -> u64 {
+        self.assert_can_mutate();
+        let account_id = self.internal_caller_id();
+        let initial_storage = env::storage_usage();
+        let mut review = self.reviews.get(&review_id).expect("Review does not exist");
+        assert_ne!(
+            review.reviewer, account_id,
+            "You cannot upvote your own review"
+        );
+
+        let mut upvoters = self
+            .upvoters_by_review_id
+            .get(&review_id)
+            .unwrap_or_else(|| {
+                UnorderedSet::new(StorageKey::UpvotersPerReview {
+                    review_hash: env::sha256(review_id.as_bytes()),
+                })
+            });
+        assert!(
+            upvoters.insert(&account_id),
+            "You already upvoted this review"
+        );
+        self.upvoters_by_review_id.insert(&review_id, &upvoters);
+
+        review.upvotes += 1;
+        self.reviews.insert(&review_id, &review);
+        self.internal_charge_storage(&account_id, initial_storage);
+
+        review.upvotes
+    }
+
+    pub fn get_review(&self, review_id: ReviewId) -> Option<Review> {
+        self.internal_get_visible_review(&review_id)
+    }
+
+    /// Pages through a book's reviews, oldest first by default. `Helpful` puts the most upvoted
+    /// reviews first and keeps ties oldest first.
+    pub fn get_reviews(
+        &self,
+        book_id: BookId,
+        from_index: Option<u64>,
+        limit: Option<u64>,
+        sort: Option<ReviewSort>,
+    ) -> Page<Review> {
+        let review_ids = match self.reviews_by_book_id.get(&book_id) {
+            Some(review_ids) => review_ids,
+            None => return Page::empty(),
+        };
+        match sort.unwrap_or(ReviewSort::Oldest) {
+            ReviewSort::Oldest => {
+                Page::from_vector(review_ids.as_vector(), from_index, limit, |review_id| {
+                    self.internal_get_visible_review(&review_id)
+                })
+            }
+            ReviewSort::Helpful => {
+                let mut reviews: Vec<Review> = review_ids
+                    .iter()
+                    .filter_map(|review_id| self.internal_get_visible_review(&review_id))
+                    .collect();
+                reviews.sort_by_key(|review| cmp::Reverse(review.upvotes));
+                Page::from_vec(reviews, from_index, limit, Some)
+            }
+        }
+    }
+}
+
+impl Contract {
+    /// A review as views may return it: anything not hidden by reports.
+    pub(crate) fn internal_get_visible_review(&self, review_id: &ReviewId) -> Option<Review> {
+        self.reviews
+            .get(review_id)
+            .filter(|_| !self.internal_is_hidden(&ReportTarget::Review(review_id.clone())))
+    }
+
+    fn assert_valid_review(&self, text: &str, rating: u8) {
+        assert_valid_rating(rating);
+        let max_length = self.internal_config().max_description_length;
+        assert!(
+            text.len() <= max_length as usize,
+            "Review exceeds {} characters",
+            max_length
+        );
+    }
+
+    fn internal_reviewer_review(&self, review_id: &ReviewId, reviewer: &AccountId) -> Review {
+        let review = self.reviews.get(review_id).expect("Review does not exist");
+        assert_eq!(
+            &review.reviewer, reviewer,
+            "Only the reviewer can change a review"
+        );
+        review
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{get_context, get_contract, get_sample_book};
+    use near_sdk::testing_env;
+    use near_sdk::MockedBlockchain;
+
+    #[test]
+    fn test_reviews() {
+        let mut context = get_context(vec![], false);
+        testing_env!(context.clone());
+        let mut contract = get_contract();
+        let book_id = contract.add_book(get_sample_book());
+
+        context.predecessor_account_id = "dave_near".to_string();
+        context.storage_usage = env::storage_usage();
+        testing_env!(context);
+        let review_id = contract.add_review(book_id.clone(), "Clear and practical".into(), 4);
+        let review = contract.edit_review(review_id.clone(), "Clear, practical".into(), 5);
+        assert_eq!(review.rating, 5);
+
+        let reviews = contract
+            .get_reviews(book_id.clone(), None, None, None)
+            .items;
+        assert_eq!(reviews.len(), 1);
+        assert_eq!(reviews[0].reviewer, "dave_near");
+
+        contract.delete_review(review_id);
+        assert_eq!(contract.get_reviews(book_id, None, None, None).total, 0);
+    }
+
+    #[test]
+    fn test_get_reviews_by_helpfulness() {
+        let mut context = get_context(vec![], false);
+        testing_env!(context.clone());
+        let mut contract = get_contract();
+        let book_id = contract.add_book(get_sample_book());
+        contract.add_review(book_id.clone(), "Good".into(), 4);
+
+        context.predecessor_account_id = "dave_near".to_string();
+        context.storage_usage = env::storage_usage();
+        testing_env!(context.clone());
+        let review_id = contract.add_review(book_id.clone(), "Great".into(), 5);
+
+        context.predecessor_account_id = "erin_near".to_string();
+        context.storage_usage = env::storage_usage();
+        testing_env!(context);
+        assert_eq!(contract.upvote_review(review_id.clone()), 1);
+
+        let reviews = contract
+            .get_reviews(book_id, None, None, Some(ReviewSort::Helpful))
+            .items;
+        assert_eq!(reviews[0].review_id, review_id);
+        assert_eq!(reviews[0].upvotes, 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "You already upvoted this review")]
+    fn test_upvote_review_twice() {
+        let mut context = get_context(vec![], false);
+        testing_env!(context.clone());
+        let mut contract = get_contract();
+        let book_id = contract.add_book(get_sample_book());
+        let review_id = contract.add_review(book_id, "Good".into(), 4);
+
+        context.predecessor_account_id = "dave_near".to_string();
+        context.storage_usage = env::storage_usage();
+        testing_env!(context);
+        contract.upvote_review(review_id.clone());
+        contract.upvote_review(review_id);
+    }
+
+    #[test]
+    #[should_panic(expected = "You already reviewed this book")]
+    fn test_add_review_twice() {
+        let context = get_context(vec![], false);
+        testing_env!(context);
+        let mut contract = get_contract();
+        let book_id = contract.add_book(get_sample_book());
+        contract.add_review(book_id.clone(), "Good".into(), 4);
+        contract.add_review(book_id, "Still good".into(), 4);
+    }
+}
@@ -0,0 +1,133 @@
+use crate::*;
+use near_sdk::json_types::U128;
+use near_sdk::{ext_contract, is_promise_success, Balance};
+
+const GAS_FOR_FT_TRANSFER: Gas = 10_000_000_000_000;
+const GAS_FOR_RESOLVE_REWARD: Gas = 5_000_000_000_000;
+const ONE_YOCTO: Balance = 1;
+
+/// Paid out in a NEP-141 token when a reader moves a book to `Finished`.
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct FinishReward {
+    pub token_id: AccountId,
+    pub amount: U128,
+    /// Minimum time between two rewards to the same account, in nanoseconds.
+    pub cooldown: U64,
+}
+
+#[ext_contract(ext_ft)]
+pub trait FungibleToken {
+    fn ft_transfer(&mut self, receiver_id: AccountId, amount: U128, memo: Option<String>);
+}
+
+#[ext_contract(ext_reward_resolver)]
+pub trait RewardResolver {
+    fn resolve_finish_reward(&mut self, account_id: AccountId, book_id: BookId);
+}
+
+#[near_bindgen]
+impl Contract {
+    /// Sets or, with `None`, turns off the reward for finishing a book. The contract account
+    /// must hold enough of the token to pay it out.
+    pub fn set_finish_reward(&mut self, reward: Option<FinishReward>) {
+        self.update_config(ConfigPatch {
+            finish_reward: Some(reward),
+            ..Default::default()
+        });
+    }
+
+    pub fn get_finish_reward(&self) -> Option<FinishReward> {
+        self.internal_config().finish_reward
+    }
+
+    /// Forgets a failed payout so the book can earn the reward again.
+    #[private]
+    pub fn resolve_finish_reward(&mut self, account_id: AccountId, book_id: BookId) {
+        if !is_promise_success() {
+            self.rewarded_book_ids.remove(&book_id);
+            self.last_reward_by_owner_id.remove(&account_id);
+        }
+    }
+}
+
+impl Contract {
+    /// Pays the finish reward for a book that just moved to `Finished`. Each book is rewarded
+    /// at most once, and an account earns nothing until its cooldown has passed.
+    pub(crate) fn internal_reward_finish(&mut self, account_id: &AccountId, book_id: &BookId) {
+        let reward = match self.internal_config().finish_reward {
+            Some(reward) => reward,
+            None => return,
+        };
+        let now = env::block_timestamp();
+        let cooling_down = self
+            .last_reward_by_owner_id
+            .get(account_id)
+            .is_some_and(|last_reward| now < last_reward + reward.cooldown.0);
+        if cooling_down || self.rewarded_book_ids.contains(book_id) {
+            return;
+        }
+
+        self.rewarded_book_ids.insert(book_id);
+        self.last_reward_by_owner_id.insert(account_id, &now);
+        ext_ft::ft_transfer(
+            account_id.clone(),
+            reward.amount,
+            Some(format!("Finished book {}", book_id)),
+            &reward.token_id,
+            ONE_YOCTO,
+            GAS_FOR_FT_TRANSFER,
+        )
+        .then(ext_reward_resolver::resolve_finish_reward(
+            account_id.clone(),
+            book_id.clone(),
+            &env::current_account_id(),
+            0,
+            GAS_FOR_RESOLVE_REWARD,
+        ));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{get_context, get_contract, get_sample_book};
+    use near_sdk::testing_env;
+    use near_sdk::MockedBlockchain;
+
+    const DAY: u64 = 24 * 60 * 60 * 1_000_000_000;
+
+    #[test]
+    fn test_finish_reward_cooldown() {
+        let mut context = get_context(vec![], false);
+        context.account_balance = 10;
+        testing_env!(context.clone());
+        let mut contract = get_contract();
+        let mut config = contract.get_config();
+        config.finish_reward = Some(FinishReward {
+            token_id: "token_near".to_string(),
+            amount: U128(100),
+            cooldown: U64(DAY),
+        });
+        contract.internal_set_config(&config);
+        let first_id = contract.add_book(get_sample_book());
+        let second_id = contract.add_book(get_sample_book());
+
+        contract.update_book(first_id.clone(), Status::Finished, None);
+        contract.update_book(second_id.clone(), Status::Finished, None);
+        assert!(contract.rewarded_book_ids.contains(&first_id));
+        assert!(!contract.rewarded_book_ids.contains(&second_id));
+
+        context.block_timestamp = DAY;
+        context.storage_usage = env::storage_usage();
+        testing_env!(context);
+        contract.update_book(first_id.clone(), Status::Read, None);
+        contract.update_book(first_id, Status::Finished, None);
+        assert_eq!(
+            contract
+                .last_reward_by_owner_id
+                .get(&"carol_near".to_string()),
+            Some(0)
+        );
+    }
+}
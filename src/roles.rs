@@ -0,0 +1,119 @@
+use crate::*;
+
+/// Powers the owner can grant without handing over ownership. Moderators can remove and
+/// redact books; curators pick the featured books.
+#[derive(
+    BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, Copy, PartialEq, Debug,
+)]
+#[serde(crate = "near_sdk::serde")]
+pub enum Role {
+    Moderator,
+    Curator,
+}
+
+impl Role {
+    fn name(&self) -> &'static str {
+        match self {
+            Role::Moderator => "moderator",
+            Role::Curator => "curator",
+        }
+    }
+}
+
+#[near_bindgen]
+impl Contract {
+    pub fn add_role(&mut self, account_id: ValidAccountId, role: Role) {
+        self.assert_owner();
+        let mut roles = self
+            .roles_by_account_id
+            .get(account_id.as_ref())
+            .unwrap_or_default();
+        if !roles.contains(&role) {
+            roles.push(role);
+            self.roles_by_account_id.insert(account_id.as_ref(), &roles);
+        }
+    }
+
+    pub fn remove_role(&mut self, account_id: ValidAccountId, role: Role) {
+        self.assert_owner();
+        if let Some(mut roles) = self.roles_by_account_id.get(account_id.as_ref()) {
+            roles.retain(|granted| granted != &role);
+            if roles.is_empty() {
+                self.roles_by_account_id.remove(account_id.as_ref());
+            } else {
+                self.roles_by_account_id.insert(account_id.as_ref(), &roles);
+            }
+        }
+    }
+
+    pub fn has_role(&self, account_id: ValidAccountId, role: Role) -> bool {
+        self.internal_has_role(account_id.as_ref(), role)
+    }
+
+    pub fn get_roles(&self, account_id: ValidAccountId) -> Vec<Role> {
+        self.roles_by_account_id
+            .get(account_id.as_ref())
+            .unwrap_or_default()
+    }
+}
+
+impl Contract {
+    pub(crate) fn internal_has_role(&self, account_id: &AccountId, role: Role) -> bool {
+        self.roles_by_account_id
+            .get(account_id)
+            .is_some_and(|roles| roles.contains(&role))
+    }
+
+    /// Checks that the caller is the owner or holds `role`, and returns it. With a council set,
+    /// the owner needs the role like anyone else.
+    pub(crate) fn assert_role(&self, role: Role) -> AccountId {
+        let caller_id = env::predecessor_account_id();
+        let is_owner = self.council.is_none() && caller_id == self.owner_id;
+        assert!(
+            is_owner || self.internal_has_role(&caller_id, role),
+            "Only the owner or a {} can call this method",
+            role.name()
+        );
+        caller_id
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{get_context, get_new_contract};
+    use near_sdk::testing_env;
+    use near_sdk::MockedBlockchain;
+    use std::convert::TryFrom;
+
+    #[test]
+    fn test_add_and_remove_role() {
+        let mut context = get_context(vec![], false);
+        context.predecessor_account_id = "alice_near".to_string();
+        testing_env!(context);
+        let mut contract = get_new_contract();
+        let dave = ValidAccountId::try_from("dave_near").unwrap();
+
+        contract.add_role(dave.clone(), Role::Curator);
+        contract.add_role(dave.clone(), Role::Moderator);
+        assert!(contract.has_role(dave.clone(), Role::Curator));
+
+        contract.remove_role(dave.clone(), Role::Curator);
+        assert!(!contract.has_role(dave.clone(), Role::Curator));
+        assert_eq!(contract.get_roles(dave), vec![Role::Moderator]);
+    }
+
+    #[test]
+    #[should_panic(expected = "Only the owner or a curator can call this method")]
+    fn test_owner_needs_role_with_council() {
+        let mut context = get_context(vec![], false);
+        context.predecessor_account_id = "alice_near".to_string();
+        testing_env!(context);
+        let mut contract = get_new_contract();
+        contract.set_council(Some(Council {
+            members: vec!["dave_near".to_string()],
+            threshold: 1,
+        }));
+        contract.feature_book("1".to_string());
+    }
+}
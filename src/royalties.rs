@@ -0,0 +1,222 @@
+use crate::*;
+use near_sdk::assert_one_yocto;
+use near_sdk::json_types::U128;
+use std::collections::HashMap;
+
+/// Royalties are in basis points of the sale price.
+const ROYALTY_DENOMINATOR: u32 = 10_000;
+pub(crate) const MAX_ROYALTY_ACCOUNTS: usize = 10;
+
+/// Royalty split on one book. Only the account that set it, the cataloguer, can change it, so a
+/// later owner cannot drop it before reselling.
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct Royalty {
+    pub cataloguer_id: AccountId,
+    pub split: HashMap<AccountId, u32>,
+}
+
+/// NEP-199 payout: what each account receives from a sale.
+#[derive(Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct Payout {
+    pub payout: HashMap<AccountId, U128>,
+}
+
+#[near_bindgen]
+impl Contract {
+    /// Sets the royalty split, in basis points, paid to other accounts whenever the book is
+    /// sold through `nft_transfer_payout`. The caller must own the book, and once set only
+    /// the same account can change it.
+    pub fn set_royalty(&mut self, book_id: BookId, split: HashMap<AccountId, u32>) {
+        self.assert_can_mutate();
+        let account_id = self.internal_caller_id();
+        let initial_storage = env::storage_usage();
+        self.assert_book_owner(&account_id, &book_id);
+        if let Some(royalty) = self.royalties_by_book_id.get(&book_id) {
+            assert_eq!(
+                royalty.cataloguer_id, account_id,
+                "Only the cataloguer can change the royalty"
+            );
+        }
+        assert!(
+            split.len() <= MAX_ROYALTY_ACCOUNTS,
+            "Royalty can have at most {} accounts",
+            MAX_ROYALTY_ACCOUNTS
+        );
+        for receiver_id in split.keys() {
+            assert!(
+                env::is_valid_account_id(receiver_id.as_bytes()),
+                "Royalty account id is invalid"
+            );
+        }
+        let max_total_royalty_bps = self.internal_config().max_total_royalty_bps;
+        assert!(
+            split.values().sum::<u32>() <= max_total_royalty_bps,
+            "Royalty cannot exceed {} basis points",
+            max_total_royalty_bps
+        );
+
+        self.royalties_by_book_id.insert(
+            &book_id,
+            &Royalty {
+                cataloguer_id: account_id.clone(),
+                split,
+            },
+        );
+        self.internal_charge_storage(&account_id, initial_storage);
+    }
+
+    pub fn get_royalty(&self, book_id: BookId) -> Option<Royalty> {
+        self.royalties_by_book_id.get(&book_id)
+    }
+
+    /// How a sale of `balance` would be split between the royalty accounts and the owner.
+    pub fn nft_payout(&self, token_id: BookId, balance: U128, max_len_payout: u32) -> Payout {
+        let owner_id = self
+            .books
+            .get(&token_id)
+            .and_then(|book| book.account_id)
+            .expect("Book does not exist");
+        self.internal_payout(&owner_id, &token_id, balance.0, max_len_payout)
+    }
+
+    /// Transfers a book like `nft_transfer` and returns the payout the marketplace should
+    /// make for the sale.
+    #[payable]
+    pub fn nft_transfer_payout(
+        &mut self,
+        receiver_id: ValidAccountId,
+        token_id: BookId,
+        approval_id: Option<u64>,
+        memo: Option<String>,
+        balance: U128,
+        max_len_payout: u32,
+    ) -> Payout {
+        self.assert_can_mutate();
+        assert_one_yocto();
+        let (owner_id, authorized_id) = self.internal_authorize(&token_id, approval_id);
+        let payout = self.internal_payout(&owner_id, &token_id, balance.0, max_len_payout);
+        self.assert_accepts_transfers(receiver_id.as_ref());
+        self.internal_transfer_book(
+            &owner_id,
+            receiver_id.as_ref(),
+            &token_id,
+            authorized_id.as_ref(),
+            memo,
+        );
+        payout
+    }
+}
+
+impl Contract {
+    pub(crate) fn internal_payout(
+        &self,
+        owner_id: &AccountId,
+        book_id: &BookId,
+        balance: u128,
+        max_len_payout: u32,
+    ) -> Payout {
+        let split = self
+            .royalties_by_book_id
+            .get(book_id)
+            .map(|royalty| royalty.split)
+            .unwrap_or_default();
+        let mut payout = HashMap::new();
+        let mut paid = 0;
+        for (account_id, basis_points) in split {
+            let amount = balance * basis_points as u128 / ROYALTY_DENOMINATOR as u128;
+            *payout.entry(account_id).or_insert(0) += amount;
+            paid += amount;
+        }
+        *payout.entry(owner_id.clone()).or_insert(0) += balance - paid;
+        assert!(
+            payout.len() <= max_len_payout as usize,
+            "Payout exceeds max_len_payout"
+        );
+
+        Payout {
+            payout: payout
+                .into_iter()
+                .map(|(account_id, amount)| (account_id, U128(amount)))
+                .collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{get_context, get_contract, get_sample_book};
+    use near_sdk::testing_env;
+    use near_sdk::MockedBlockchain;
+    use std::convert::TryFrom;
+
+    #[test]
+    fn test_transfer_payout() {
+        let mut context = get_context(vec![], false);
+        testing_env!(context.clone());
+        let mut contract = get_contract();
+        let book_id = contract.add_book(get_sample_book());
+        let mut split = HashMap::new();
+        split.insert("author_near".to_string(), 1_000);
+        contract.set_royalty(book_id.clone(), split);
+
+        context.attached_deposit = 1;
+        context.storage_usage = env::storage_usage();
+        testing_env!(context);
+        let payout = contract.nft_transfer_payout(
+            ValidAccountId::try_from("dave_near").unwrap(),
+            book_id.clone(),
+            None,
+            None,
+            U128(1_000),
+            2,
+        );
+        assert_eq!(payout.payout["author_near"], U128(100));
+        assert_eq!(payout.payout["carol_near"], U128(900));
+
+        let payout = contract.nft_payout(book_id, U128(1_000), 2);
+        assert_eq!(payout.payout["dave_near"], U128(900));
+    }
+
+    #[test]
+    #[should_panic(expected = "Royalty account id is invalid")]
+    fn test_royalty_invalid_account() {
+        let context = get_context(vec![], false);
+        testing_env!(context);
+        let mut contract = get_contract();
+        let book_id = contract.add_book(get_sample_book());
+        let mut split = HashMap::new();
+        split.insert("Not An Account".to_string(), 1_000);
+        contract.set_royalty(book_id, split);
+    }
+
+    #[test]
+    #[should_panic(expected = "Only the cataloguer can change the royalty")]
+    fn test_new_owner_cannot_change_royalty() {
+        let mut context = get_context(vec![], false);
+        testing_env!(context.clone());
+        let mut contract = get_contract();
+        let book_id = contract.add_book(get_sample_book());
+        let mut split = HashMap::new();
+        split.insert("author_near".to_string(), 1_000);
+        contract.set_royalty(book_id.clone(), split);
+
+        context.attached_deposit = 1;
+        context.storage_usage = env::storage_usage();
+        testing_env!(context.clone());
+        contract.nft_transfer(
+            ValidAccountId::try_from("dave_near").unwrap(),
+            book_id.clone(),
+            None,
+            None,
+        );
+
+        context.predecessor_account_id = "dave_near".to_string();
+        context.attached_deposit = 0;
+        context.storage_usage = env::storage_usage();
+        testing_env!(context);
+        contract.set_royalty(book_id, HashMap::new());
+    }
+}
@@ -0,0 +1,119 @@
+use crate::*;
+use std::ops::Bound;
+
+/// Index keys are the lowercase title followed by the book id, so books sharing a title each
+/// get their own entry while keys still sort by title.
+fn title_key(title: &str, book_id: &BookId) -> String {
+    format!("{}\0{}", title.to_lowercase(), book_id)
+}
+
+#[near_bindgen]
+impl Contract {
+    /// Lists an account's books whose title starts with `query`, ignoring case, ordered by title.
+    pub fn search_books(
+        &self,
+        account_id: ValidAccountId,
+        query: String,
+        from_index: Option<u64>,
+        limit: Option<u64>,
+    ) -> Page<Book> {
+        let titles = match self.titles_by_owner_id.get(account_id.as_ref()) {
+            Some(titles) => titles,
+            None => return Page::empty(),
+        };
+
+        let prefix = query.to_lowercase();
+        let matching: Vec<BookId> = titles
+            .range((Bound::Included(prefix.clone()), Bound::Unbounded))
+            .take_while(|(key, _)| key.starts_with(&prefix))
+            .map(|(_, book_id)| book_id)
+            .collect();
+
+        Page::from_vec(matching, from_index, limit, |book_id| {
+            self.internal_get_listed_book(&book_id)
+        })
+    }
+}
+
+impl Contract {
+    /// Ids of an account's books whose title equals `title`, ignoring case.
+    pub(crate) fn internal_books_titled(&self, account_id: &AccountId, title: &str) -> Vec<BookId> {
+        let prefix = format!("{}\0", title.to_lowercase());
+        self.titles_by_owner_id
+            .get(account_id)
+            .map(|titles| {
+                titles
+                    .range((Bound::Included(prefix.clone()), Bound::Unbounded))
+                    .take_while(|(key, _)| key.starts_with(&prefix))
+                    .map(|(_, book_id)| book_id)
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    pub(crate) fn internal_index_title(
+        &mut self,
+        account_id: &AccountId,
+        book_id: &BookId,
+        title: &str,
+    ) {
+        let mut titles = self.titles_by_owner_id.get(account_id).unwrap_or_else(|| {
+            TreeMap::new(StorageKey::TitlesPerOwner {
+                account_hash: env::sha256(account_id.as_bytes()),
+            })
+        });
+        titles.insert(&title_key(title, book_id), book_id);
+        self.titles_by_owner_id.insert(account_id, &titles);
+    }
+
+    pub(crate) fn internal_unindex_title(
+        &mut self,
+        account_id: &AccountId,
+        book_id: &BookId,
+        title: &str,
+    ) {
+        if let Some(mut titles) = self.titles_by_owner_id.get(account_id) {
+            titles.remove(&title_key(title, book_id));
+            self.titles_by_owner_id.insert(account_id, &titles);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{get_context, get_contract, get_sample_book};
+    use near_sdk::testing_env;
+    use near_sdk::MockedBlockchain;
+    use std::convert::TryFrom;
+
+    #[test]
+    fn test_search_books() {
+        let mut context = get_context(vec![], false);
+        context.attached_deposit = 1;
+        testing_env!(context);
+        let mut contract = get_contract();
+        let mechanics_id = contract.add_book(get_sample_book());
+        contract.add_book(Book {
+            title: "Motorcycle Diaries".to_string(),
+            ..get_sample_book()
+        });
+        contract.add_book(Book {
+            title: "Zen and the Art of Motorcycle Maintenance".to_string(),
+            ..get_sample_book()
+        });
+
+        let account_id = ValidAccountId::try_from("carol_near").unwrap();
+        let books = contract
+            .search_books(account_id.clone(), "motorcycle".to_string(), None, None)
+            .items;
+        assert_eq!(books.len(), 2);
+        assert_eq!(books[0].title, "Motorcycle Diaries".to_string());
+
+        contract.delete_book(mechanics_id);
+        let books = contract
+            .search_books(account_id, "MOTORCYCLE M".to_string(), None, None)
+            .items;
+        assert_eq!(books.len(), 0);
+    }
+}
@@ -0,0 +1,136 @@
+use crate::*;
+
+#[near_bindgen]
+impl Contract {
+    /// Lets `app_id`, e.g. a relayer contract, act on the caller's library: calls it forwards
+    /// from a transaction the caller signed are attributed to the caller. Must be called
+    /// directly, not through another app.
+    pub fn allow_session(&mut self, app_id: ValidAccountId) {
+        self.assert_can_mutate();
+        let account_id = env::predecessor_account_id();
+        let initial_storage = env::storage_usage();
+        let mut app_ids = self
+            .sessions_by_owner_id
+            .get(&account_id)
+            .unwrap_or_default();
+        if !app_ids.contains(app_id.as_ref()) {
+            app_ids.push(app_id.into());
+            self.sessions_by_owner_id.insert(&account_id, &app_ids);
+        }
+        self.internal_charge_storage(&account_id, initial_storage);
+    }
+
+    pub fn revoke_session(&mut self, app_id: ValidAccountId) {
+        self.assert_can_mutate();
+        let account_id = env::predecessor_account_id();
+        let initial_storage = env::storage_usage();
+        let mut app_ids = self
+            .sessions_by_owner_id
+            .get(&account_id)
+            .unwrap_or_default();
+        app_ids.retain(|id| id != app_id.as_ref());
+        if app_ids.is_empty() {
+            self.sessions_by_owner_id.remove(&account_id);
+        } else {
+            self.sessions_by_owner_id.insert(&account_id, &app_ids);
+        }
+        self.internal_charge_storage(&account_id, initial_storage);
+    }
+
+    pub fn get_sessions(&self, account_id: ValidAccountId) -> Vec<AccountId> {
+        self.sessions_by_owner_id
+            .get(account_id.as_ref())
+            .unwrap_or_default()
+    }
+
+    /// Contracts whose forwarded calls are always attributed to their signer, as if every user
+    /// had allowed them. Only list forwarders that act solely on their signer's request.
+    pub fn set_trusted_forwarders(&mut self, forwarders: Vec<ValidAccountId>) {
+        self.assert_owner();
+        self.trusted_forwarders = forwarders.into_iter().map(AccountId::from).collect();
+    }
+
+    pub fn get_trusted_forwarders(&self) -> Vec<AccountId> {
+        self.trusted_forwarders.clone()
+    }
+}
+
+impl Contract {
+    /// The account a library mutation acts for: the predecessor, or the signer when the call
+    /// was forwarded by an app the signer allowed or by a trusted forwarder. Calls that move
+    /// deposits keep using the predecessor, which is the account that attached them.
+    ///
+    /// NEP-366 delegate actions need nothing extra: the delegating user is the predecessor
+    /// and the relayer only the signer, so such calls resolve to the user unless the user
+    /// is itself an allowed app of the relayer.
+    pub(crate) fn internal_caller_id(&self) -> AccountId {
+        let predecessor_id = env::predecessor_account_id();
+        let signer_id = env::signer_account_id();
+        if predecessor_id == signer_id {
+            return predecessor_id;
+        }
+        let allowed = self.trusted_forwarders.contains(&predecessor_id)
+            || self
+                .sessions_by_owner_id
+                .get(&signer_id)
+                .is_some_and(|app_ids| app_ids.contains(&predecessor_id));
+        if allowed {
+            signer_id
+        } else {
+            predecessor_id
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{get_context, get_contract, get_sample_book};
+    use near_sdk::testing_env;
+    use near_sdk::MockedBlockchain;
+    use std::convert::TryFrom;
+
+    #[test]
+    fn test_session_attributes_to_signer() {
+        let mut context = get_context(vec![], false);
+        context.signer_account_id = "carol_near".to_string();
+        testing_env!(context.clone());
+        let mut contract = get_contract();
+        contract.allow_session(ValidAccountId::try_from("relayer_near").unwrap());
+
+        context.predecessor_account_id = "relayer_near".to_string();
+        context.storage_usage = env::storage_usage();
+        testing_env!(context.clone());
+        let book_id = contract.add_book(get_sample_book());
+        assert_eq!(
+            contract.get_book(book_id).account_id,
+            Some("carol_near".to_string())
+        );
+
+        context.predecessor_account_id = "carol_near".to_string();
+        context.storage_usage = env::storage_usage();
+        testing_env!(context.clone());
+        contract.revoke_session(ValidAccountId::try_from("relayer_near").unwrap());
+
+        context.predecessor_account_id = "relayer_near".to_string();
+        context.storage_usage = env::storage_usage();
+        testing_env!(context);
+        assert_eq!(contract.internal_caller_id(), "relayer_near");
+    }
+
+    #[test]
+    fn test_delegate_action_attributes_to_sender() {
+        let mut context = get_context(vec![], false);
+        context.predecessor_account_id = "dave_near".to_string();
+        context.signer_account_id = "relayer_near".to_string();
+        testing_env!(context);
+        let mut contract = get_contract();
+        contract.trusted_forwarders = vec!["forwarder_near".to_string()];
+
+        let book_id = contract.add_book(get_sample_book());
+        assert_eq!(
+            contract.get_book(book_id).account_id,
+            Some("dave_near".to_string())
+        );
+    }
+}
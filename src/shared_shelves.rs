@@ -0,0 +1,225 @@
+use crate::*;
+
+pub type SharedShelfId = String;
+
+const MAX_SHELF_MEMBERS: usize = 50;
+
+/// A shelf several accounts curate together, e.g. a family, a classroom or a club. Members
+/// add books they can see; the creator manages membership.
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct SharedShelf {
+    shelf_id: SharedShelfId,
+    name: String,
+    creator_id: AccountId,
+    member_ids: Vec<AccountId>,
+    created_at: U64,
+}
+
+#[near_bindgen]
+impl Contract {
+    pub fn create_shared_shelf(&mut self, name: String) -> SharedShelfId {
+        self.assert_can_mutate();
+        let creator_id = self.internal_caller_id();
+        let initial_storage = env::storage_usage();
+        assert!(!name.is_empty(), "Shelf name cannot be empty");
+        let max_length = self.internal_config().max_title_length;
+        assert!(
+            name.len() <= max_length as usize,
+            "Shelf name exceeds {} characters",
+            max_length
+        );
+
+        let shelf_id = format!("{}", self.shared_shelves_len + 1);
+        self.shared_shelves.insert(
+            &shelf_id,
+            &SharedShelf {
+                shelf_id: shelf_id.clone(),
+                name,
+                creator_id: creator_id.clone(),
+                member_ids: vec![creator_id.clone()],
+                created_at: env::block_timestamp().into(),
+            },
+        );
+        self.shared_shelves_len += 1;
+        self.internal_charge_storage(&creator_id, initial_storage);
+
+        shelf_id
+    }
+
+    pub fn invite_member(&mut self, shelf_id: SharedShelfId, account_id: ValidAccountId) {
+        self.assert_can_mutate();
+        let creator_id = self.internal_caller_id();
+        let initial_storage = env::storage_usage();
+        let mut shelf = self.internal_created_shelf(&shelf_id, &creator_id);
+        if !shelf.member_ids.contains(account_id.as_ref()) {
+            assert!(
+                shelf.member_ids.len() < MAX_SHELF_MEMBERS,
+                "A shared shelf has at most {} members",
+                MAX_SHELF_MEMBERS
+            );
+            shelf.member_ids.push(account_id.into());
+            self.shared_shelves.insert(&shelf_id, &shelf);
+        }
+        self.internal_charge_storage(&creator_id, initial_storage);
+    }
+
+    /// Removes a member. The creator can remove anyone but themselves; members can leave.
+    /// Books they added stay on the shelf.
+    pub fn remove_member(&mut self, shelf_id: SharedShelfId, account_id: ValidAccountId) {
+        self.assert_can_mutate();
+        let caller_id = self.internal_caller_id();
+        let initial_storage = env::storage_usage();
+        let mut shelf = self
+            .shared_shelves
+            .get(&shelf_id)
+            .expect("Shared shelf does not exist");
+        assert!(
+            caller_id == shelf.creator_id || &caller_id == account_id.as_ref(),
+            "Only the shelf creator can remove other members"
+        );
+        assert_ne!(
+            &shelf.creator_id,
+            account_id.as_ref(),
+            "The shelf creator cannot be removed"
+        );
+        shelf.member_ids.retain(|id| id != account_id.as_ref());
+        self.shared_shelves.insert(&shelf_id, &shelf);
+        self.internal_charge_storage(&shelf.creator_id, initial_storage);
+    }
+
+    /// Adds a book the caller can see to a shelf they are a member of. The caller pays for
+    /// the entry.
+    pub fn add_book_to_shared_shelf(&mut self, shelf_id: SharedShelfId, book_id: BookId) {
+        self.assert_can_mutate();
+        let account_id = self.internal_caller_id();
+        let initial_storage = env::storage_usage();
+        self.assert_shelf_member(&shelf_id, &account_id);
+        assert!(
+            self.internal_get_visible_book(&book_id).is_some(),
+            "Book does not exist"
+        );
+
+        let mut book_ids = self
+            .books_by_shared_shelf_id
+            .get(&shelf_id)
+            .unwrap_or_else(|| {
+                UnorderedSet::new(StorageKey::BooksPerSharedShelf {
+                    shelf_hash: env::sha256(shelf_id.as_bytes()),
+                })
+            });
+        book_ids.insert(&book_id);
+        self.books_by_shared_shelf_id.insert(&shelf_id, &book_ids);
+        self.internal_charge_storage(&account_id, initial_storage);
+    }
+
+    pub fn remove_book_from_shared_shelf(&mut self, shelf_id: SharedShelfId, book_id: BookId) {
+        self.assert_can_mutate();
+        let account_id = self.internal_caller_id();
+        let initial_storage = env::storage_usage();
+        self.assert_shelf_member(&shelf_id, &account_id);
+
+        if let Some(mut book_ids) = self.books_by_shared_shelf_id.get(&shelf_id) {
+            book_ids.remove(&book_id);
+            self.books_by_shared_shelf_id.insert(&shelf_id, &book_ids);
+        }
+        self.internal_charge_storage(&account_id, initial_storage);
+    }
+
+    pub fn get_shared_shelf(&self, shelf_id: SharedShelfId) -> Option<SharedShelf> {
+        self.shared_shelves.get(&shelf_id)
+    }
+
+    /// Pages through a shared shelf's books. Books that were deleted or made private since
+    /// they were added are skipped.
+    pub fn get_shared_shelf_books(
+        &self,
+        shelf_id: SharedShelfId,
+        from_index: Option<u64>,
+        limit: Option<u64>,
+    ) -> Page<Book> {
+        match self.books_by_shared_shelf_id.get(&shelf_id) {
+            Some(book_ids) => {
+                Page::from_vector(book_ids.as_vector(), from_index, limit, |book_id| {
+                    self.internal_get_visible_book(&book_id)
+                })
+            }
+            None => Page::empty(),
+        }
+    }
+}
+
+impl Contract {
+    fn internal_created_shelf(
+        &self,
+        shelf_id: &SharedShelfId,
+        account_id: &AccountId,
+    ) -> SharedShelf {
+        let shelf = self
+            .shared_shelves
+            .get(shelf_id)
+            .expect("Shared shelf does not exist");
+        assert_eq!(
+            &shelf.creator_id, account_id,
+            "Only the shelf creator can invite members"
+        );
+        shelf
+    }
+
+    fn assert_shelf_member(&self, shelf_id: &SharedShelfId, account_id: &AccountId) {
+        let shelf = self
+            .shared_shelves
+            .get(shelf_id)
+            .expect("Shared shelf does not exist");
+        assert!(
+            shelf.member_ids.contains(account_id),
+            "Not a member of this shelf"
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{get_context, get_contract, get_sample_book};
+    use near_sdk::testing_env;
+    use near_sdk::MockedBlockchain;
+    use std::convert::TryFrom;
+
+    #[test]
+    fn test_shared_shelf() {
+        let mut context = get_context(vec![], false);
+        testing_env!(context.clone());
+        let mut contract = get_contract();
+        let book_id = contract.add_book(get_sample_book());
+        let shelf_id = contract.create_shared_shelf("Family reads".into());
+        contract.invite_member(
+            shelf_id.clone(),
+            ValidAccountId::try_from("dave_near").unwrap(),
+        );
+
+        context.predecessor_account_id = "dave_near".to_string();
+        context.storage_usage = env::storage_usage();
+        testing_env!(context);
+        contract.add_book_to_shared_shelf(shelf_id.clone(), book_id.clone());
+
+        let books = contract.get_shared_shelf_books(shelf_id, None, None).items;
+        assert_eq!(books.len(), 1);
+        assert_eq!(books[0].book_id, Some(book_id));
+    }
+
+    #[test]
+    #[should_panic(expected = "Not a member of this shelf")]
+    fn test_add_book_to_shared_shelf_not_member() {
+        let mut context = get_context(vec![], false);
+        testing_env!(context.clone());
+        let mut contract = get_contract();
+        let book_id = contract.add_book(get_sample_book());
+        let shelf_id = contract.create_shared_shelf("Family reads".into());
+
+        context.predecessor_account_id = "dave_near".to_string();
+        context.storage_usage = env::storage_usage();
+        testing_env!(context);
+        contract.add_book_to_shared_shelf(shelf_id, book_id);
+    }
+}
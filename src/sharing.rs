@@ -0,0 +1,164 @@
+use crate::*;
+
+#[near_bindgen]
+impl Contract {
+    /// Lets `account_id` read one of the caller's private books, with its bookmarks and
+    /// curriculum, through `get_shared_book` and `get_books_shared_with_me`.
+    pub fn share_with(&mut self, book_id: BookId, account_id: ValidAccountId) {
+        self.assert_can_mutate();
+        let owner_id = self.internal_library_owner(&book_id);
+        let initial_storage = env::storage_usage();
+        let reader_id: AccountId = account_id.into();
+        assert_ne!(owner_id, reader_id, "Cannot share a book with its owner");
+
+        let mut reader_ids = self.shares_by_book_id.get(&book_id).unwrap_or_default();
+        if !reader_ids.contains(&reader_id) {
+            reader_ids.push(reader_id.clone());
+            self.shares_by_book_id.insert(&book_id, &reader_ids);
+
+            let mut book_ids = self
+                .shared_books_by_reader_id
+                .get(&reader_id)
+                .unwrap_or_else(|| {
+                    UnorderedSet::new(StorageKey::SharedBooksPerReader {
+                        account_hash: env::sha256(reader_id.as_bytes()),
+                    })
+                });
+            book_ids.insert(&book_id);
+            self.shared_books_by_reader_id.insert(&reader_id, &book_ids);
+        }
+        self.internal_charge_storage(&owner_id, initial_storage);
+    }
+
+    pub fn unshare_with(&mut self, book_id: BookId, account_id: ValidAccountId) {
+        self.assert_can_mutate();
+        let owner_id = self.internal_library_owner(&book_id);
+        let initial_storage = env::storage_usage();
+
+        let mut reader_ids = self.shares_by_book_id.get(&book_id).unwrap_or_default();
+        reader_ids.retain(|reader_id| reader_id != account_id.as_ref());
+        if reader_ids.is_empty() {
+            self.shares_by_book_id.remove(&book_id);
+        } else {
+            self.shares_by_book_id.insert(&book_id, &reader_ids);
+        }
+        self.internal_unindex_share(&book_id, account_id.as_ref());
+        self.internal_charge_storage(&owner_id, initial_storage);
+    }
+
+    pub fn get_shared_with(&self, book_id: BookId) -> Vec<AccountId> {
+        self.shares_by_book_id.get(&book_id).unwrap_or_default()
+    }
+
+    /// Returns a book the caller owns or that was shared with them, private or not, together
+    /// with its bookmarks and curriculum. Has to be called as a transaction, like
+    /// `get_my_books`.
+    pub fn get_shared_book(&mut self, book_id: BookId) -> ExportedBook {
+        let reader_id = self.internal_caller_id();
+        let book = self.books.get(&book_id).expect("Book does not exist");
+        assert!(
+            book.account_id.as_ref() == Some(&reader_id)
+                || self.internal_is_shared_with(&book_id, &reader_id),
+            "Book is not shared with you"
+        );
+        self.internal_export_book(book, false)
+    }
+
+    /// Lists the books shared with the caller. Has to be called as a transaction.
+    pub fn get_books_shared_with_me(
+        &mut self,
+        from_index: Option<u64>,
+        limit: Option<u64>,
+    ) -> Page<Book> {
+        let reader_id = self.internal_caller_id();
+        match self.shared_books_by_reader_id.get(&reader_id) {
+            Some(book_ids) => {
+                Page::from_vector(book_ids.as_vector(), from_index, limit, |book_id| {
+                    self.books.get(&book_id)
+                })
+            }
+            None => Page::empty(),
+        }
+    }
+}
+
+impl Contract {
+    pub(crate) fn internal_is_shared_with(&self, book_id: &BookId, reader_id: &AccountId) -> bool {
+        self.shares_by_book_id
+            .get(book_id)
+            .is_some_and(|reader_ids| reader_ids.contains(reader_id))
+    }
+
+    /// Revokes every share of a book, e.g. when it changes hands or is deleted for good.
+    pub(crate) fn internal_clear_shares(&mut self, book_id: &BookId) {
+        if let Some(reader_ids) = self.shares_by_book_id.remove(book_id) {
+            for reader_id in &reader_ids {
+                self.internal_unindex_share(book_id, reader_id);
+            }
+        }
+    }
+
+    fn internal_unindex_share(&mut self, book_id: &BookId, reader_id: &AccountId) {
+        if let Some(mut book_ids) = self.shared_books_by_reader_id.get(reader_id) {
+            book_ids.remove(book_id);
+            if book_ids.is_empty() {
+                self.shared_books_by_reader_id.remove(reader_id);
+            } else {
+                self.shared_books_by_reader_id.insert(reader_id, &book_ids);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{get_context, get_contract, get_sample_book};
+    use near_sdk::testing_env;
+    use near_sdk::MockedBlockchain;
+    use std::convert::TryFrom;
+
+    #[test]
+    fn test_share_with() {
+        let mut context = get_context(vec![], false);
+        testing_env!(context.clone());
+        let mut contract = get_contract();
+        let book_id = contract.add_book(Book {
+            visibility: Visibility::Private,
+            ..get_sample_book()
+        });
+        contract.add_bookmark(book_id.clone(), "Chapter 1".to_string(), 12);
+        let dave = ValidAccountId::try_from("dave_near").unwrap();
+        contract.share_with(book_id.clone(), dave.clone());
+
+        context.predecessor_account_id = "dave_near".to_string();
+        context.storage_usage = env::storage_usage();
+        testing_env!(context.clone());
+        let shared = contract.get_shared_book(book_id.clone());
+        assert_eq!(shared.bookmarks.len(), 1);
+        assert_eq!(contract.get_books_shared_with_me(None, None).total, 1);
+
+        context.predecessor_account_id = "carol_near".to_string();
+        context.storage_usage = env::storage_usage();
+        testing_env!(context);
+        contract.unshare_with(book_id.clone(), dave);
+        assert!(contract.get_shared_with(book_id).is_empty());
+    }
+
+    #[test]
+    #[should_panic(expected = "Book is not shared with you")]
+    fn test_get_shared_book_not_shared() {
+        let mut context = get_context(vec![], false);
+        testing_env!(context.clone());
+        let mut contract = get_contract();
+        let book_id = contract.add_book(Book {
+            visibility: Visibility::Private,
+            ..get_sample_book()
+        });
+
+        context.predecessor_account_id = "dave_near".to_string();
+        context.storage_usage = env::storage_usage();
+        testing_env!(context);
+        contract.get_shared_book(book_id);
+    }
+}
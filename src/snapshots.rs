@@ -0,0 +1,116 @@
+use crate::*;
+
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct SnapshotEntry {
+    book_id: BookId,
+    title: String,
+}
+
+/// Frozen copy of one status shelf, kept even if the books later change or are deleted.
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct Snapshot {
+    shelf: Status,
+    label: Option<String>,
+    block_height: U64,
+    created_at: U64,
+    books: Vec<SnapshotEntry>,
+}
+
+#[near_bindgen]
+impl Contract {
+    /// Records the caller's listed books currently on `shelf` and returns the snapshot's index.
+    /// Snapshots are public, so private, unlisted and hidden books are left out.
+    pub fn snapshot_shelf(&mut self, shelf: Status, label: Option<String>) -> u64 {
+        self.assert_can_mutate();
+        let account_id = self.internal_caller_id();
+        let initial_storage = env::storage_usage();
+
+        let books = self
+            .books_by_owner_status
+            .get(&(account_id.clone(), shelf))
+            .map(|book_ids| {
+                book_ids
+                    .iter()
+                    .filter_map(|book_id| {
+                        self.internal_get_listed_book(&book_id)
+                            .map(|book| SnapshotEntry {
+                                book_id,
+                                title: book.title,
+                            })
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        let mut snapshots = self
+            .snapshots_by_owner_id
+            .get(&account_id)
+            .unwrap_or_default();
+        snapshots.push(Snapshot {
+            shelf,
+            label,
+            block_height: env::block_index().into(),
+            created_at: env::block_timestamp().into(),
+            books,
+        });
+        self.snapshots_by_owner_id.insert(&account_id, &snapshots);
+        self.internal_charge_storage(&account_id, initial_storage);
+
+        snapshots.len() as u64 - 1
+    }
+
+    pub fn get_snapshots(
+        &self,
+        account_id: ValidAccountId,
+        from_index: Option<u64>,
+        limit: Option<u64>,
+    ) -> Page<Snapshot> {
+        let snapshots = self
+            .snapshots_by_owner_id
+            .get(account_id.as_ref())
+            .unwrap_or_default();
+        Page::from_vec(snapshots, from_index, limit, Some)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{get_context, get_contract, get_sample_book};
+    use near_sdk::testing_env;
+    use near_sdk::MockedBlockchain;
+    use std::convert::TryFrom;
+
+    #[test]
+    fn test_snapshot_shelf() {
+        let mut context = get_context(vec![], false);
+        context.attached_deposit = 1;
+        context.block_index = 42;
+        testing_env!(context);
+        let mut contract = get_contract();
+        let book_id = contract.add_book(Book {
+            status: Status::Finished,
+            ..get_sample_book()
+        });
+        contract.add_book(get_sample_book());
+        contract.add_book(Book {
+            status: Status::Finished,
+            visibility: Visibility::Private,
+            ..get_sample_book()
+        });
+
+        let index = contract.snapshot_shelf(Status::Finished, Some("2024 favorites".to_string()));
+        contract.delete_book(book_id.clone());
+
+        let snapshots = contract
+            .get_snapshots(ValidAccountId::try_from("carol_near").unwrap(), None, None)
+            .items;
+        assert_eq!(index, 0);
+        assert_eq!(snapshots.len(), 1);
+        assert_eq!(snapshots[0].block_height, U64(42));
+        assert_eq!(snapshots[0].books.len(), 1);
+        assert_eq!(snapshots[0].books[0].book_id, book_id);
+    }
+}
@@ -0,0 +1,156 @@
+use crate::*;
+
+#[derive(Serialize, Deserialize, Clone, Copy)]
+#[serde(crate = "near_sdk::serde")]
+pub enum SortBy {
+    Title,
+    DateAdded,
+    DateUpdated,
+    DateFinished,
+    Rating,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy)]
+#[serde(crate = "near_sdk::serde")]
+pub enum SortOrder {
+    Asc,
+    Desc,
+}
+
+/// Sorts books in place. Books that were never finished sort before finished ones when
+/// ascending by `DateFinished`. Unrated books sort after rated ones by `Rating` in either
+/// order, and books with the same rating keep their order.
+pub(crate) fn sort_books(books: &mut [Book], sort: SortBy, order: SortOrder) {
+    match sort {
+        SortBy::Title => books.sort_by_cached_key(|book| book.title.to_lowercase()),
+        SortBy::DateAdded => books.sort_by_key(|book| book.created_at.map(u64::from)),
+        SortBy::DateUpdated => books.sort_by_key(|book| book.updated_at.map(u64::from)),
+        SortBy::DateFinished => books.sort_by_key(|book| book.finished_at.map(u64::from)),
+        SortBy::Rating => {
+            books.sort_by_key(|book| match (book.rating, order) {
+                (Some(rating), SortOrder::Asc) => (false, rating as i16),
+                (Some(rating), SortOrder::Desc) => (false, -(rating as i16)),
+                (None, _) => (true, 0),
+            });
+            return;
+        }
+    }
+    if let SortOrder::Desc = order {
+        books.reverse();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{get_context, get_contract, get_sample_book};
+    use near_sdk::testing_env;
+    use near_sdk::MockedBlockchain;
+
+    #[test]
+    fn test_get_books_sort_by_title() {
+        let context = get_context(vec![], false);
+        testing_env!(context);
+        let mut contract = get_contract();
+        contract.add_book(Book {
+            title: "zen and the Art of Motorcycle Maintenance".to_string(),
+            ..get_sample_book()
+        });
+        contract.add_book(Book {
+            title: "Anna Karenina".to_string(),
+            ..get_sample_book()
+        });
+        contract.add_book(get_sample_book());
+
+        let books = contract
+            .get_books(
+                None,
+                None,
+                None,
+                Some(SortBy::Title),
+                Some(SortOrder::Desc),
+                None,
+            )
+            .items;
+        let titles: Vec<&str> = books.iter().map(|book| book.title.as_str()).collect();
+        assert_eq!(
+            titles,
+            vec![
+                "zen and the Art of Motorcycle Maintenance",
+                "Motorcycle Mechanics 101",
+                "Anna Karenina"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_get_books_sort_by_date_finished() {
+        let mut context = get_context(vec![], false);
+        testing_env!(context.clone());
+        let mut contract = get_contract();
+        let first_id = contract.add_book(get_sample_book());
+        let second_id = contract.add_book(get_sample_book());
+
+        context.block_timestamp = 100;
+        context.storage_usage = env::storage_usage();
+        testing_env!(context.clone());
+        contract.update_book(second_id.clone(), Status::Finished, None);
+
+        context.block_timestamp = 200;
+        context.storage_usage = env::storage_usage();
+        testing_env!(context);
+        contract.update_book(first_id.clone(), Status::Finished, None);
+
+        let books = contract
+            .get_books(
+                None,
+                None,
+                None,
+                Some(SortBy::DateFinished),
+                Some(SortOrder::Desc),
+                None,
+            )
+            .items;
+        assert_eq!(books[0].book_id, Some(first_id));
+        assert_eq!(books[0].finished_at, Some(U64(200)));
+        assert_eq!(books[1].book_id, Some(second_id));
+        assert_eq!(books[1].finished_at, Some(U64(100)));
+    }
+
+    #[test]
+    fn test_get_books_sort_by_rating() {
+        let context = get_context(vec![], false);
+        testing_env!(context);
+        let mut contract = get_contract();
+        let unrated_id = contract.add_book(get_sample_book());
+        let low_id = contract.add_book(Book {
+            rating: Some(2),
+            ..get_sample_book()
+        });
+        let high_id = contract.add_book(Book {
+            rating: Some(5),
+            ..get_sample_book()
+        });
+
+        let sorted_ids = |order| -> Vec<Option<BookId>> {
+            contract
+                .get_books(None, None, None, Some(SortBy::Rating), Some(order), None)
+                .items
+                .into_iter()
+                .map(|book| book.book_id)
+                .collect()
+        };
+        assert_eq!(
+            sorted_ids(SortOrder::Desc),
+            vec![
+                Some(high_id.clone()),
+                Some(low_id.clone()),
+                Some(unrated_id.clone())
+            ]
+        );
+        assert_eq!(
+            sorted_ids(SortOrder::Asc),
+            vec![Some(low_id), Some(high_id), Some(unrated_id)]
+        );
+    }
+}
@@ -0,0 +1,66 @@
+use crate::*;
+
+/// NEP-330 source metadata, so explorers can tell which code is deployed.
+#[derive(Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct ContractSourceMetadata {
+    pub version: Option<String>,
+    pub link: Option<String>,
+    pub standards: Vec<Standard>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct Standard {
+    pub standard: String,
+    pub version: String,
+}
+
+const STANDARDS: [(&str, &str); 8] = [
+    ("nep145", "1.0.0"),
+    ("nep171", "1.0.0"),
+    ("nep177", "2.0.0"),
+    ("nep178", "1.0.0"),
+    ("nep181", "1.0.0"),
+    ("nep199", "2.0.0"),
+    ("nep297", "1.0.0"),
+    ("nep330", "1.1.0"),
+];
+
+#[near_bindgen]
+impl Contract {
+    pub fn contract_source_metadata(&self) -> ContractSourceMetadata {
+        ContractSourceMetadata {
+            version: Some(env!("CARGO_PKG_VERSION").to_string()),
+            link: Some(env!("CARGO_PKG_REPOSITORY").to_string()),
+            standards: STANDARDS
+                .iter()
+                .map(|(standard, version)| Standard {
+                    standard: standard.to_string(),
+                    version: version.to_string(),
+                })
+                .collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{get_context, get_contract};
+    use near_sdk::testing_env;
+    use near_sdk::MockedBlockchain;
+
+    #[test]
+    fn test_contract_source_metadata() {
+        let context = get_context(vec![], false);
+        testing_env!(context);
+        let contract = get_contract();
+
+        let metadata = contract.contract_source_metadata();
+        assert_eq!(metadata.version, Some("0.1.0".to_string()));
+        assert!(metadata
+            .standards
+            .iter()
+            .any(|standard| standard.standard == "nep330"));
+    }
+}
@@ -0,0 +1,342 @@
+use crate::*;
+
+const NANOS_PER_DAY: u64 = 24 * 60 * 60 * 1_000_000_000;
+
+/// Running totals kept per account as books are added, updated and removed.
+#[derive(BorshDeserialize, BorshSerialize, Default)]
+pub struct AccountCounters {
+    pub(crate) pages_read: u64,
+    rating_sum: u64,
+    rating_count: u64,
+}
+
+/// Running totals for the books an account finished in one calendar year.
+#[derive(BorshDeserialize, BorshSerialize, Default)]
+pub struct YearCounters {
+    pub(crate) finished: u64,
+    pub(crate) pages_read: u64,
+    rating_sum: u64,
+    rating_count: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct AccountStats {
+    list: u64,
+    read: u64,
+    finished: u64,
+    pages_read: u64,
+    average_rating: Option<f64>,
+    finished_this_year: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct YearInBooks {
+    year: u32,
+    finished: u64,
+    pages_read: u64,
+    average_rating: Option<f64>,
+    longest: Option<Book>,
+    shortest: Option<Book>,
+    books: Page<Book>,
+}
+
+pub(crate) fn average_rating(rating_sum: u64, rating_count: u64) -> Option<f64> {
+    if rating_count > 0 {
+        Some(rating_sum as f64 / rating_count as f64)
+    } else {
+        None
+    }
+}
+
+/// Calendar year (UTC) of a block timestamp in nanoseconds.
+pub(crate) fn year_of(timestamp: u64) -> u32 {
+    // Howard Hinnant's days-to-civil conversion, shifted so years start in March.
+    let z = (timestamp / NANOS_PER_DAY) as i64 + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z - era * 146_097;
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let year = yoe + era * 400;
+    if mp >= 10 {
+        (year + 1) as u32
+    } else {
+        year as u32
+    }
+}
+
+#[near_bindgen]
+impl Contract {
+    pub fn get_stats(&self, account_id: ValidAccountId) -> AccountStats {
+        let account_id: AccountId = account_id.into();
+        let counters = self
+            .counters_by_owner_id
+            .get(&account_id)
+            .unwrap_or_default();
+        let count = |status: Status| {
+            self.books_by_owner_status
+                .get(&(account_id.clone(), status))
+                .map(|book_ids| book_ids.len())
+                .unwrap_or(0)
+        };
+
+        AccountStats {
+            list: count(Status::List),
+            read: count(Status::Read),
+            finished: count(Status::Finished),
+            pages_read: counters.pages_read,
+            average_rating: average_rating(counters.rating_sum, counters.rating_count),
+            finished_this_year: self
+                .finished_by_owner_year
+                .get(&(account_id.clone(), year_of(env::block_timestamp())))
+                .map(|counters| counters.finished)
+                .unwrap_or(0),
+        }
+    }
+
+    /// Reading report for the books an account finished in `year`, with one page of those
+    /// books. Books without a page count are left out of `longest` and `shortest`.
+    pub fn get_year_in_books(
+        &self,
+        account_id: ValidAccountId,
+        year: u32,
+        from_index: Option<u64>,
+        limit: Option<u64>,
+    ) -> YearInBooks {
+        let key = (account_id.into(), year);
+        let counters = self.finished_by_owner_year.get(&key).unwrap_or_default();
+        let books = match self.finished_books_by_owner_year.get(&key) {
+            Some(book_ids) => {
+                Page::from_vector(book_ids.as_vector(), from_index, limit, |book_id| {
+                    self.internal_get_listed_book(&book_id)
+                })
+            }
+            None => Page::empty(),
+        };
+        let by_pages = self.finished_pages_by_owner_year.get(&key);
+        let listed =
+            |((_, book_id), _): ((u64, BookId), ())| self.internal_get_listed_book(&book_id);
+
+        YearInBooks {
+            year,
+            finished: counters.finished,
+            pages_read: counters.pages_read,
+            average_rating: average_rating(counters.rating_sum, counters.rating_count),
+            longest: by_pages
+                .as_ref()
+                .and_then(|by_pages| by_pages.iter_rev().find_map(listed)),
+            shortest: by_pages
+                .as_ref()
+                .and_then(|by_pages| by_pages.iter().find_map(listed)),
+            books,
+        }
+    }
+}
+
+impl Contract {
+    pub(crate) fn internal_add_to_stats(&mut self, account_id: &AccountId, book: &Book) {
+        self.internal_add_community_rating(book);
+        let mut counters = self
+            .counters_by_owner_id
+            .get(account_id)
+            .unwrap_or_default();
+        if let Some(rating) = book.rating {
+            counters.rating_sum += rating as u64;
+            counters.rating_count += 1;
+        }
+        if book.status == Status::Finished {
+            counters.pages_read += book.pages.unwrap_or(0);
+            if let Some(finished_at) = book.finished_at {
+                self.internal_add_to_year(account_id, year_of(finished_at.0), book);
+            }
+        }
+        self.counters_by_owner_id.insert(account_id, &counters);
+        if book.status == Status::Finished {
+            let year = book.finished_at.map(|finished_at| year_of(finished_at.0));
+            self.internal_update_leaderboards(account_id, year);
+        }
+    }
+
+    pub(crate) fn internal_remove_from_stats(&mut self, account_id: &AccountId, book: &Book) {
+        self.internal_remove_community_rating(book);
+        let mut counters = match self.counters_by_owner_id.get(account_id) {
+            Some(counters) => counters,
+            None => return,
+        };
+        if let Some(rating) = book.rating {
+            counters.rating_sum -= rating as u64;
+            counters.rating_count -= 1;
+        }
+        if book.status == Status::Finished {
+            counters.pages_read -= book.pages.unwrap_or(0);
+            if let Some(finished_at) = book.finished_at {
+                self.internal_remove_from_year(account_id, year_of(finished_at.0), book);
+            }
+        }
+        self.counters_by_owner_id.insert(account_id, &counters);
+        if book.status == Status::Finished {
+            let year = book.finished_at.map(|finished_at| year_of(finished_at.0));
+            self.internal_update_leaderboards(account_id, year);
+        }
+    }
+
+    fn internal_add_to_year(&mut self, account_id: &AccountId, year: u32, book: &Book) {
+        let key = (account_id.clone(), year);
+        let mut counters = self.finished_by_owner_year.get(&key).unwrap_or_default();
+        counters.finished += 1;
+        counters.pages_read += book.pages.unwrap_or(0);
+        if let Some(rating) = book.rating {
+            counters.rating_sum += rating as u64;
+            counters.rating_count += 1;
+        }
+        self.finished_by_owner_year.insert(&key, &counters);
+
+        let mut book_ids = self
+            .finished_books_by_owner_year
+            .get(&key)
+            .unwrap_or_else(|| {
+                UnorderedSet::new(StorageKey::FinishedBooksPerOwnerYear {
+                    account_hash: env::sha256(account_id.as_bytes()),
+                    year,
+                })
+            });
+        book_ids.insert(book.book_id.as_ref().unwrap());
+        self.finished_books_by_owner_year.insert(&key, &book_ids);
+
+        if let Some(pages) = book.pages {
+            let mut by_pages = self
+                .finished_pages_by_owner_year
+                .get(&key)
+                .unwrap_or_else(|| {
+                    TreeMap::new(StorageKey::FinishedPagesPerOwnerYear {
+                        account_hash: env::sha256(account_id.as_bytes()),
+                        year,
+                    })
+                });
+            by_pages.insert(&(pages, book.book_id.clone().unwrap()), &());
+            self.finished_pages_by_owner_year.insert(&key, &by_pages);
+        }
+    }
+
+    fn internal_remove_from_year(&mut self, account_id: &AccountId, year: u32, book: &Book) {
+        let key = (account_id.clone(), year);
+        if let Some(mut counters) = self.finished_by_owner_year.get(&key) {
+            if counters.finished <= 1 {
+                self.finished_by_owner_year.remove(&key);
+            } else {
+                counters.finished -= 1;
+                counters.pages_read -= book.pages.unwrap_or(0);
+                if let Some(rating) = book.rating {
+                    counters.rating_sum -= rating as u64;
+                    counters.rating_count -= 1;
+                }
+                self.finished_by_owner_year.insert(&key, &counters);
+            }
+        }
+
+        if let Some(mut book_ids) = self.finished_books_by_owner_year.get(&key) {
+            book_ids.remove(book.book_id.as_ref().unwrap());
+            if book_ids.is_empty() {
+                self.finished_books_by_owner_year.remove(&key);
+            } else {
+                self.finished_books_by_owner_year.insert(&key, &book_ids);
+            }
+        }
+
+        if let (Some(pages), Some(mut by_pages)) =
+            (book.pages, self.finished_pages_by_owner_year.get(&key))
+        {
+            by_pages.remove(&(pages, book.book_id.clone().unwrap()));
+            if by_pages.len() == 0 {
+                self.finished_pages_by_owner_year.remove(&key);
+            } else {
+                self.finished_pages_by_owner_year.insert(&key, &by_pages);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{get_context, get_contract, get_sample_book};
+    use near_sdk::testing_env;
+    use near_sdk::MockedBlockchain;
+    use std::convert::TryFrom;
+
+    // 2024-03-01T00:00:00Z
+    const MARCH_2024: u64 = 1_709_251_200_000_000_000;
+
+    #[test]
+    fn test_year_of() {
+        assert_eq!(year_of(0), 1970);
+        assert_eq!(year_of(MARCH_2024), 2024);
+        assert_eq!(year_of(MARCH_2024 - NANOS_PER_DAY * 61), 2023);
+    }
+
+    #[test]
+    fn test_get_stats() {
+        let mut context = get_context(vec![], false);
+        context.attached_deposit = 1;
+        context.block_timestamp = MARCH_2024;
+        testing_env!(context);
+        let mut contract = get_contract();
+        let finished_id = contract.add_book(get_sample_book());
+        contract.add_book(Book {
+            status: Status::Finished,
+            finished_at: Some(U64(MARCH_2024 - NANOS_PER_DAY * 61)),
+            pages: Some(100),
+            rating: Some(4),
+            ..get_sample_book()
+        });
+        let deleted_id = contract.add_book(get_sample_book());
+        contract.update_book(finished_id.clone(), Status::Finished, None);
+        contract.rate_book(finished_id, 5, None);
+        contract.rate_book(deleted_id.clone(), 1, None);
+        contract.delete_book(deleted_id);
+
+        let stats = contract.get_stats(ValidAccountId::try_from("carol_near").unwrap());
+        assert_eq!(stats.list, 0);
+        assert_eq!(stats.finished, 2);
+        assert_eq!(stats.pages_read, 420);
+        assert_eq!(stats.average_rating, Some(4.5));
+        assert_eq!(stats.finished_this_year, 1);
+    }
+
+    #[test]
+    fn test_get_year_in_books() {
+        let mut context = get_context(vec![], false);
+        context.block_timestamp = MARCH_2024;
+        testing_env!(context);
+        let mut contract = get_contract();
+        let long_id = contract.add_book(get_sample_book());
+        let short_id = contract.add_book(Book {
+            status: Status::Finished,
+            pages: Some(100),
+            rating: Some(3),
+            ..get_sample_book()
+        });
+        contract.add_book(Book {
+            status: Status::Finished,
+            finished_at: Some(U64(MARCH_2024 - NANOS_PER_DAY * 61)),
+            ..get_sample_book()
+        });
+        contract.update_book(long_id.clone(), Status::Finished, None);
+        contract.rate_book(long_id.clone(), 5, None);
+
+        let report = contract.get_year_in_books(
+            ValidAccountId::try_from("carol_near").unwrap(),
+            2024,
+            None,
+            None,
+        );
+        assert_eq!(report.finished, 2);
+        assert_eq!(report.pages_read, 420);
+        assert_eq!(report.average_rating, Some(4.0));
+        assert_eq!(report.longest.unwrap().book_id, Some(long_id));
+        assert_eq!(report.shortest.unwrap().book_id, Some(short_id));
+        assert_eq!(report.books.total, 2);
+    }
+}
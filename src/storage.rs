@@ -0,0 +1,363 @@
+use crate::*;
+use near_sdk::json_types::U128;
+use near_sdk::{assert_one_yocto, Balance, Promise};
+
+/// An account's NEP-145 deposit and the bytes its data currently takes up.
+#[derive(BorshDeserialize, BorshSerialize, Default)]
+pub struct StorageAccount {
+    pub(crate) deposit: Balance,
+    pub(crate) used_bytes: u64,
+    /// Whether other accounts may transfer books in, charging this balance for them.
+    pub(crate) accepts_transfers: bool,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct StorageBalance {
+    pub total: U128,
+    pub available: U128,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct StorageBalanceBounds {
+    pub min: U128,
+    pub max: Option<U128>,
+}
+
+impl StorageAccount {
+    fn locked(&self) -> Balance {
+        self.used_bytes as Balance * env::storage_byte_cost()
+    }
+
+    pub(crate) fn balance(&self) -> StorageBalance {
+        StorageBalance {
+            total: self.deposit.into(),
+            available: self.deposit.saturating_sub(self.locked()).into(),
+        }
+    }
+}
+
+#[near_bindgen]
+impl Contract {
+    /// Adds the attached deposit to `account_id`'s storage balance, the caller's by default.
+    /// With `registration_only`, an already registered account gets the whole deposit back and
+    /// a new one is refunded anything above the minimum.
+    #[payable]
+    pub fn storage_deposit(
+        &mut self,
+        account_id: Option<ValidAccountId>,
+        registration_only: Option<bool>,
+    ) -> StorageBalance {
+        self.assert_can_mutate();
+        self.internal_storage_deposit(account_id, registration_only)
+    }
+
+    /// Adds the attached deposit to another account's storage balance, registering it if
+    /// needed, so apps and libraries can pay for their readers' books. The sponsored account
+    /// owns the balance from then on, like any other deposit.
+    #[payable]
+    pub fn sponsor_storage(&mut self, account_id: ValidAccountId) -> StorageBalance {
+        self.assert_can_mutate();
+        assert_ne!(
+            account_id.as_ref(),
+            &env::predecessor_account_id(),
+            "Use storage_deposit for your own account"
+        );
+        assert!(env::attached_deposit() > 0, "Requires attached deposit");
+        self.internal_storage_deposit(Some(account_id), None)
+    }
+
+    /// Lets other accounts transfer books to the caller, or stops them. Received books are
+    /// charged to the caller's storage balance, so accounts refuse transfers until they opt in.
+    /// Purchases, auctions, swaps and wishlists the caller takes part in don't need it.
+    pub fn set_accept_transfers(&mut self, accept: bool) {
+        self.assert_can_mutate();
+        let account_id = env::predecessor_account_id();
+        let mut storage_account = self
+            .storage_accounts
+            .get(&account_id)
+            .expect("Account is not registered, call storage_deposit first");
+        storage_account.accepts_transfers = accept;
+        self.storage_accounts.insert(&account_id, &storage_account);
+    }
+
+    pub fn accepts_transfers(&self, account_id: ValidAccountId) -> bool {
+        self.storage_accounts
+            .get(account_id.as_ref())
+            .is_some_and(|storage_account| storage_account.accepts_transfers)
+    }
+
+    /// Sends `amount` of the caller's available storage balance back to them, or all of it.
+    #[payable]
+    pub fn storage_withdraw(&mut self, amount: Option<U128>) -> StorageBalance {
+        self.assert_can_mutate();
+        assert_one_yocto();
+        let account_id = env::predecessor_account_id();
+        let mut storage_account = self
+            .storage_accounts
+            .get(&account_id)
+            .expect("Account is not registered");
+
+        let available: Balance = storage_account.balance().available.into();
+        let amount = amount.map(|amount| amount.into()).unwrap_or(available);
+        assert!(
+            amount <= available,
+            "Amount exceeds available storage balance"
+        );
+        storage_account.deposit -= amount;
+        self.storage_accounts.insert(&account_id, &storage_account);
+        if amount > 0 {
+            Promise::new(account_id).transfer(amount);
+        }
+
+        storage_account.balance()
+    }
+
+    /// Closes the caller's storage account and refunds the deposit. Accounts that still have
+    /// books or any other stored data must delete it first; `force` is not supported.
+    #[payable]
+    pub fn storage_unregister(&mut self, force: Option<bool>) -> bool {
+        self.assert_can_mutate();
+        assert_one_yocto();
+        assert!(!force.unwrap_or(false), "Force unregister is not supported");
+        let account_id = env::predecessor_account_id();
+        assert!(
+            self.books_by_owner_id.get(&account_id).is_none(),
+            "Cannot unregister an account that still has books"
+        );
+        let initial_storage = env::storage_usage();
+        if self.writes_by_account_id.remove(&account_id).is_some() {
+            self.internal_charge_storage(&account_id, initial_storage);
+        }
+
+        match self.storage_accounts.get(&account_id) {
+            Some(storage_account) => {
+                assert_eq!(
+                    storage_account.used_bytes, 0,
+                    "Cannot unregister an account that still uses storage"
+                );
+                self.storage_accounts.remove(&account_id);
+                let available: Balance = storage_account.balance().available.into();
+                if available > 0 {
+                    Promise::new(account_id).transfer(available);
+                }
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn storage_balance_bounds(&self) -> StorageBalanceBounds {
+        StorageBalanceBounds {
+            min: self.internal_min_storage_balance().into(),
+            max: None,
+        }
+    }
+
+    pub fn storage_balance_of(&self, account_id: ValidAccountId) -> Option<StorageBalance> {
+        self.storage_accounts
+            .get(account_id.as_ref())
+            .map(|storage_account| storage_account.balance())
+    }
+}
+
+impl Contract {
+    /// The minimum deposit, enough to cover the config's `min_storage_bytes`.
+    fn internal_min_storage_balance(&self) -> Balance {
+        self.internal_config().min_storage_bytes as Balance * env::storage_byte_cost()
+    }
+
+    pub(crate) fn internal_storage_deposit(
+        &mut self,
+        account_id: Option<ValidAccountId>,
+        registration_only: Option<bool>,
+    ) -> StorageBalance {
+        let account_id: AccountId = account_id
+            .map(|account_id| account_id.into())
+            .unwrap_or_else(env::predecessor_account_id);
+        let mut amount = env::attached_deposit();
+        let mut storage_account = self.storage_accounts.get(&account_id);
+
+        if storage_account.is_none() {
+            assert!(
+                amount >= self.internal_min_storage_balance(),
+                "Deposit must be at least {} yoctoNEAR",
+                self.internal_min_storage_balance()
+            );
+        }
+        if registration_only.unwrap_or(false) {
+            let refund = match storage_account {
+                Some(_) => amount,
+                None => amount - self.internal_min_storage_balance(),
+            };
+            if refund > 0 {
+                Promise::new(env::predecessor_account_id()).transfer(refund);
+            }
+            amount -= refund;
+        }
+
+        let storage_account = storage_account.get_or_insert_with(StorageAccount::default);
+        storage_account.deposit += amount;
+        self.storage_accounts.insert(&account_id, storage_account);
+
+        storage_account.balance()
+    }
+
+    /// Checks that `account_id` opted in to being charged for books others send it.
+    pub(crate) fn assert_accepts_transfers(&self, account_id: &AccountId) {
+        assert!(
+            self.storage_accounts
+                .get(account_id)
+                .is_some_and(|storage_account| storage_account.accepts_transfers),
+            "Receiver does not accept transfers, it must call set_accept_transfers first"
+        );
+    }
+
+    /// Settles the storage an account's call used since `initial_storage`: growth is charged
+    /// to its storage balance and freed bytes are released back to it, and either is logged
+    /// with the bytes and balance involved. Freed bytes of an unregistered account are simply
+    /// dropped, so releasing someone's storage never fails.
+    pub(crate) fn internal_charge_storage(&mut self, account_id: &AccountId, initial_storage: u64) {
+        if let Some(change) = self.internal_settle_storage(account_id, initial_storage) {
+            emit_storage_event(account_id, change);
+        }
+    }
+
+    /// Same as `internal_charge_storage` without the storage event, for calls that report the
+    /// change in their book or NFT event instead.
+    pub(crate) fn internal_settle_storage(
+        &mut self,
+        account_id: &AccountId,
+        initial_storage: u64,
+    ) -> Option<StorageChange> {
+        let storage_usage = env::storage_usage();
+        if storage_usage == initial_storage {
+            return None;
+        }
+        let storage_account = self.storage_accounts.get(account_id);
+        if storage_account.is_none() && storage_usage < initial_storage {
+            return None;
+        }
+        let mut storage_account =
+            storage_account.expect("Account is not registered, call storage_deposit first");
+        let bytes = storage_usage.abs_diff(initial_storage);
+        let amount = U128(bytes as Balance * env::storage_byte_cost());
+        let change = if storage_usage > initial_storage {
+            storage_account.used_bytes += bytes;
+            assert!(
+                storage_account.locked() <= storage_account.deposit,
+                "Insufficient storage balance, call storage_deposit first"
+            );
+            StorageChange::Charged { bytes, amount }
+        } else {
+            storage_account.used_bytes = storage_account.used_bytes.saturating_sub(bytes);
+            StorageChange::Released { bytes, amount }
+        };
+        self.storage_accounts.insert(account_id, &storage_account);
+        Some(change)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{get_context, get_new_contract, get_sample_book};
+    use near_sdk::testing_env;
+    use near_sdk::MockedBlockchain;
+    use std::convert::TryFrom;
+
+    #[test]
+    fn test_storage_deposit_and_charge() {
+        let mut context = get_context(vec![], false);
+        testing_env!(context.clone());
+        let mut contract = get_new_contract();
+        let account_id = ValidAccountId::try_from("carol_near").unwrap();
+        assert!(contract.storage_balance_of(account_id.clone()).is_none());
+
+        context.attached_deposit =
+            Config::default().min_storage_bytes as Balance * env::storage_byte_cost() * 10;
+        context.account_balance = context.attached_deposit;
+        testing_env!(context.clone());
+        contract.storage_deposit(None, None);
+
+        context.attached_deposit = 0;
+        context.storage_usage = env::storage_usage();
+        testing_env!(context.clone());
+        contract.add_book(get_sample_book());
+
+        let balance = contract.storage_balance_of(account_id).unwrap();
+        assert_eq!(
+            balance.total.0,
+            Config::default().min_storage_bytes as Balance * env::storage_byte_cost() * 10
+        );
+        assert!(balance.available.0 < balance.total.0);
+
+        context.attached_deposit = 1;
+        context.storage_usage = env::storage_usage();
+        testing_env!(context);
+        let balance = contract.storage_withdraw(None);
+        assert_eq!(balance.available.0, 0);
+    }
+
+    #[test]
+    fn test_sponsor_storage() {
+        let mut context = get_context(vec![], false);
+        context.attached_deposit =
+            Config::default().min_storage_bytes as Balance * env::storage_byte_cost() * 10;
+        testing_env!(context.clone());
+        let mut contract = get_new_contract();
+        let dave = ValidAccountId::try_from("dave_near").unwrap();
+        contract.sponsor_storage(dave.clone());
+
+        context.predecessor_account_id = "dave_near".to_string();
+        context.attached_deposit = 0;
+        context.storage_usage = env::storage_usage();
+        testing_env!(context);
+        contract.add_book(get_sample_book());
+
+        let balance = contract.storage_balance_of(dave).unwrap();
+        assert_eq!(
+            balance.total.0,
+            Config::default().min_storage_bytes as Balance * env::storage_byte_cost() * 10
+        );
+        assert!(contract
+            .storage_balance_of(ValidAccountId::try_from("carol_near").unwrap())
+            .is_none());
+    }
+
+    #[test]
+    #[should_panic(expected = "Cannot unregister an account that still uses storage")]
+    fn test_unregister_with_archived_books() {
+        let mut context = get_context(vec![], false);
+        context.attached_deposit =
+            Config::default().min_storage_bytes as Balance * env::storage_byte_cost() * 10;
+        testing_env!(context.clone());
+        let mut contract = get_new_contract();
+        contract.storage_deposit(None, None);
+
+        context.attached_deposit = 1;
+        context.storage_usage = env::storage_usage();
+        testing_env!(context);
+        let book_id = contract.add_book(get_sample_book());
+        contract.archive_book(book_id);
+        contract.storage_unregister(None);
+    }
+
+    #[test]
+    fn test_release_storage_of_unregistered_account() {
+        let context = get_context(vec![], false);
+        testing_env!(context);
+        let mut contract = get_new_contract();
+        contract.internal_charge_storage(&"dave_near".to_string(), env::storage_usage() + 100);
+    }
+
+    #[test]
+    #[should_panic(expected = "Account is not registered, call storage_deposit first")]
+    fn test_add_book_requires_storage_deposit() {
+        let context = get_context(vec![], false);
+        testing_env!(context);
+        let mut contract = get_new_contract();
+        contract.add_book(get_sample_book());
+    }
+}
@@ -0,0 +1,61 @@
+use crate::*;
+
+#[derive(Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct ContractSummary {
+    total_books: u64,
+    total_owners: u64,
+    total_deleted_books: u64,
+    version: String,
+}
+
+#[near_bindgen]
+impl Contract {
+    pub fn get_contract_summary(&self) -> ContractSummary {
+        ContractSummary {
+            total_books: self.books.len(),
+            total_owners: self.books_by_owner_id.len(),
+            // Every book id ever issued is either live, archived, pending deletion or deleted.
+            total_deleted_books: self.books_len
+                - self.books.len()
+                - self.archived_books.len()
+                - self.pending_deletions.len(),
+            version: env!("CARGO_PKG_VERSION").to_string(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{get_context, get_contract, get_sample_book};
+    use near_sdk::testing_env;
+    use near_sdk::MockedBlockchain;
+
+    #[test]
+    fn test_get_contract_summary() {
+        let mut context = get_context(vec![], false);
+        context.attached_deposit = 1;
+        testing_env!(context.clone());
+        let mut contract = get_contract();
+        let deleted_id = contract.add_book(get_sample_book());
+        let archived_id = contract.add_book(get_sample_book());
+        contract.add_book(get_sample_book());
+        contract.delete_book(deleted_id);
+        contract.archive_book(archived_id);
+
+        let summary = contract.get_contract_summary();
+        assert_eq!(summary.total_books, 1);
+        assert_eq!(summary.total_owners, 1);
+        assert_eq!(summary.total_deleted_books, 0);
+
+        context.block_timestamp = u64::MAX / 2;
+        context.storage_usage = env::storage_usage();
+        testing_env!(context);
+        contract.finalize_actions(None);
+
+        let summary = contract.get_contract_summary();
+        assert_eq!(summary.total_deleted_books, 1);
+        assert_eq!(summary.version, "0.1.0".to_string());
+    }
+}
@@ -0,0 +1,225 @@
+use crate::*;
+use near_sdk::assert_one_yocto;
+
+pub type SwapId = String;
+
+/// An offer to trade one of the proposer's books for one of the counterparty's.
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct Swap {
+    swap_id: SwapId,
+    proposer_id: AccountId,
+    proposer_book_id: BookId,
+    counterparty_id: AccountId,
+    counterparty_book_id: BookId,
+    created_at: U64,
+}
+
+#[near_bindgen]
+impl Contract {
+    /// Offers `my_book_id` in exchange for `their_book_id`, which `counterparty` must own. The
+    /// proposer pays for the offer's storage until it is accepted or cancelled.
+    #[payable]
+    pub fn propose_swap(
+        &mut self,
+        my_book_id: BookId,
+        their_book_id: BookId,
+        counterparty: ValidAccountId,
+    ) -> SwapId {
+        self.assert_can_mutate();
+        assert_one_yocto();
+        let proposer_id = env::predecessor_account_id();
+        let initial_storage = env::storage_usage();
+        let counterparty_id: AccountId = counterparty.into();
+        assert_ne!(proposer_id, counterparty_id, "Cannot swap with yourself");
+        self.assert_book_owner(&proposer_id, &my_book_id);
+        self.assert_book_owner(&counterparty_id, &their_book_id);
+
+        let swap_id = format!("{}", self.swaps_len + 1);
+        self.swaps.insert(
+            &swap_id,
+            &Swap {
+                swap_id: swap_id.clone(),
+                proposer_id: proposer_id.clone(),
+                proposer_book_id: my_book_id,
+                counterparty_id: counterparty_id.clone(),
+                counterparty_book_id: their_book_id,
+                created_at: env::block_timestamp().into(),
+            },
+        );
+        self.internal_index_swap(&proposer_id, &swap_id);
+        self.internal_index_swap(&counterparty_id, &swap_id);
+        self.swaps_len += 1;
+        self.internal_charge_storage(&proposer_id, initial_storage);
+
+        swap_id
+    }
+
+    /// Exchanges the two books in one call. Fails if either side no longer owns its book.
+    #[payable]
+    pub fn accept_swap(&mut self, swap_id: SwapId) {
+        self.assert_can_mutate();
+        assert_one_yocto();
+        let account_id = env::predecessor_account_id();
+        let swap = self.swaps.get(&swap_id).expect("Swap does not exist");
+        assert_eq!(
+            swap.counterparty_id, account_id,
+            "Only the counterparty can accept a swap"
+        );
+        self.internal_remove_swap(&swap);
+
+        self.internal_transfer_book(
+            &swap.proposer_id,
+            &swap.counterparty_id,
+            &swap.proposer_book_id,
+            None,
+            Some(format!("swap {}", swap_id)),
+        );
+        self.internal_transfer_book(
+            &swap.counterparty_id,
+            &swap.proposer_id,
+            &swap.counterparty_book_id,
+            None,
+            Some(format!("swap {}", swap_id)),
+        );
+    }
+
+    /// Withdraws a swap, or declines it when called by the counterparty.
+    pub fn cancel_swap(&mut self, swap_id: SwapId) {
+        self.assert_can_mutate();
+        let account_id = env::predecessor_account_id();
+        let swap = self.swaps.get(&swap_id).expect("Swap does not exist");
+        assert!(
+            account_id == swap.proposer_id || account_id == swap.counterparty_id,
+            "Only the proposer or the counterparty can cancel a swap"
+        );
+        self.internal_remove_swap(&swap);
+    }
+
+    /// Swaps the account proposed or was offered.
+    pub fn get_swaps(
+        &self,
+        account_id: ValidAccountId,
+        from_index: Option<u64>,
+        limit: Option<u64>,
+    ) -> Page<Swap> {
+        match self.swaps_by_account_id.get(account_id.as_ref()) {
+            Some(swap_ids) => {
+                Page::from_vector(swap_ids.as_vector(), from_index, limit, |swap_id| {
+                    self.swaps.get(&swap_id)
+                })
+            }
+            None => Page::empty(),
+        }
+    }
+}
+
+impl Contract {
+    fn internal_index_swap(&mut self, account_id: &AccountId, swap_id: &SwapId) {
+        let mut swap_ids = self.swaps_by_account_id.get(account_id).unwrap_or_else(|| {
+            UnorderedSet::new(StorageKey::SwapsPerAccount {
+                account_hash: env::sha256(account_id.as_bytes()),
+            })
+        });
+        swap_ids.insert(swap_id);
+        self.swaps_by_account_id.insert(account_id, &swap_ids);
+    }
+
+    /// Deletes a swap and releases its storage back to the proposer.
+    fn internal_remove_swap(&mut self, swap: &Swap) {
+        let initial_storage = env::storage_usage();
+        self.swaps.remove(&swap.swap_id);
+        for account_id in [&swap.proposer_id, &swap.counterparty_id] {
+            if let Some(mut swap_ids) = self.swaps_by_account_id.get(account_id) {
+                swap_ids.remove(&swap.swap_id);
+                if swap_ids.is_empty() {
+                    self.swaps_by_account_id.remove(account_id);
+                } else {
+                    self.swaps_by_account_id.insert(account_id, &swap_ids);
+                }
+            }
+        }
+        self.internal_charge_storage(&swap.proposer_id, initial_storage);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{get_context, get_contract, get_sample_book};
+    use near_sdk::testing_env;
+    use near_sdk::MockedBlockchain;
+    use std::convert::TryFrom;
+
+    #[test]
+    fn test_accept_swap() {
+        let mut context = get_context(vec![], false);
+        context.attached_deposit = 1;
+        testing_env!(context.clone());
+        let mut contract = get_contract();
+        let carol_book_id = contract.add_book(get_sample_book());
+
+        context.predecessor_account_id = "dave_near".to_string();
+        context.storage_usage = env::storage_usage();
+        testing_env!(context.clone());
+        let dave_book_id = contract.add_book(get_sample_book());
+
+        context.predecessor_account_id = "carol_near".to_string();
+        context.storage_usage = env::storage_usage();
+        testing_env!(context.clone());
+        let dave = ValidAccountId::try_from("dave_near").unwrap();
+        let swap_id =
+            contract.propose_swap(carol_book_id.clone(), dave_book_id.clone(), dave.clone());
+        assert_eq!(contract.get_swaps(dave.clone(), None, None).total, 1);
+
+        context.predecessor_account_id = "dave_near".to_string();
+        context.storage_usage = env::storage_usage();
+        testing_env!(context);
+        contract.accept_swap(swap_id);
+
+        assert_eq!(
+            contract.get_book(carol_book_id).account_id,
+            Some("dave_near".to_string())
+        );
+        assert_eq!(
+            contract.get_book(dave_book_id).account_id,
+            Some("carol_near".to_string())
+        );
+        assert_eq!(contract.get_swaps(dave, None, None).total, 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "Book does not exist")]
+    fn test_accept_swap_after_book_moved() {
+        let mut context = get_context(vec![], false);
+        context.attached_deposit = 1;
+        testing_env!(context.clone());
+        let mut contract = get_contract();
+        let carol_book_id = contract.add_book(get_sample_book());
+
+        context.predecessor_account_id = "dave_near".to_string();
+        context.storage_usage = env::storage_usage();
+        testing_env!(context.clone());
+        let dave_book_id = contract.add_book(get_sample_book());
+
+        context.predecessor_account_id = "carol_near".to_string();
+        context.storage_usage = env::storage_usage();
+        testing_env!(context.clone());
+        let swap_id = contract.propose_swap(
+            carol_book_id.clone(),
+            dave_book_id,
+            ValidAccountId::try_from("dave_near").unwrap(),
+        );
+        contract.nft_transfer(
+            ValidAccountId::try_from("erin_near").unwrap(),
+            carol_book_id,
+            None,
+            None,
+        );
+
+        context.predecessor_account_id = "dave_near".to_string();
+        context.storage_usage = env::storage_usage();
+        testing_env!(context);
+        contract.accept_swap(swap_id);
+    }
+}
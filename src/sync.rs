@@ -0,0 +1,128 @@
+use crate::*;
+use std::ops::Bound;
+
+/// Index keys are the zero-padded update timestamp followed by the book id, so keys sort by
+/// `updated_at` and books touched in the same block each get their own entry.
+fn updated_key(updated_at: u64, book_id: &BookId) -> String {
+    format!("{:020}\0{}", updated_at, book_id)
+}
+
+#[near_bindgen]
+impl Contract {
+    /// Lists an account's books updated at or after `timestamp`, oldest update first, so clients
+    /// can sync incrementally from the `updated_at` of the last book they saw. The index is
+    /// walked lazily, so `total` counts all of the account's books rather than the matches.
+    pub fn get_books_updated_since(
+        &self,
+        account_id: ValidAccountId,
+        timestamp: U64,
+        from_index: Option<u64>,
+        limit: Option<u64>,
+    ) -> Page<Book> {
+        let updated = match self.updated_by_owner_id.get(account_id.as_ref()) {
+            Some(updated) => updated,
+            None => return Page::empty(),
+        };
+
+        let matching = updated.range((
+            Bound::Included(format!("{:020}", timestamp.0)),
+            Bound::Unbounded,
+        ));
+        Page::from_iter(
+            matching,
+            updated.len(),
+            from_index,
+            limit,
+            |(_, book_id)| self.internal_get_listed_book(&book_id),
+        )
+    }
+}
+
+impl Contract {
+    /// Marks one of the account's books as updated and moves it in the update index. The caller
+    /// still writes the book back to `books` and finishes with `internal_charge_book_update`.
+    pub(crate) fn internal_touch_book(&mut self, account_id: &AccountId, book: &mut Book) {
+        self.internal_unindex_updated(account_id, book);
+        book.touch();
+        self.internal_index_updated(account_id, book);
+    }
+
+    /// Settles the call's storage with the account and emits the `book_update` event for
+    /// `book_id`, carrying the storage change.
+    pub(crate) fn internal_charge_book_update(
+        &mut self,
+        account_id: &AccountId,
+        book_id: &BookId,
+        initial_storage: u64,
+    ) {
+        let storage = self.internal_settle_storage(account_id, initial_storage);
+        emit_book_event(
+            BookEventKind::BookUpdate,
+            account_id,
+            std::slice::from_ref(book_id),
+            storage,
+        );
+    }
+
+    pub(crate) fn internal_index_updated(&mut self, account_id: &AccountId, book: &Book) {
+        let mut updated = self.updated_by_owner_id.get(account_id).unwrap_or_else(|| {
+            TreeMap::new(StorageKey::UpdatedPerOwner {
+                account_hash: env::sha256(account_id.as_bytes()),
+            })
+        });
+        let book_id = book.book_id.as_ref().unwrap();
+        updated.insert(
+            &updated_key(book.updated_at.map_or(0, u64::from), book_id),
+            book_id,
+        );
+        self.updated_by_owner_id.insert(account_id, &updated);
+    }
+
+    pub(crate) fn internal_unindex_updated(&mut self, account_id: &AccountId, book: &Book) {
+        if let Some(mut updated) = self.updated_by_owner_id.get(account_id) {
+            let book_id = book.book_id.as_ref().unwrap();
+            updated.remove(&updated_key(book.updated_at.map_or(0, u64::from), book_id));
+            self.updated_by_owner_id.insert(account_id, &updated);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{get_context, get_contract, get_sample_book};
+    use near_sdk::testing_env;
+    use near_sdk::MockedBlockchain;
+    use std::convert::TryFrom;
+
+    #[test]
+    fn test_get_books_updated_since() {
+        let mut context = get_context(vec![], false);
+        testing_env!(context.clone());
+        let mut contract = get_contract();
+        let first_id = contract.add_book(get_sample_book());
+        let second_id = contract.add_book(get_sample_book());
+
+        context.block_timestamp = 100;
+        context.storage_usage = env::storage_usage();
+        testing_env!(context.clone());
+        contract.rate_book(first_id.clone(), 4, None);
+
+        let account_id = ValidAccountId::try_from("carol_near").unwrap();
+        let page = contract.get_books_updated_since(account_id.clone(), U64(100), None, None);
+        assert_eq!(page.items.len(), 1);
+        assert_eq!(page.items[0].book_id, Some(first_id.clone()));
+        assert!(!page.has_more);
+
+        let page = contract.get_books_updated_since(account_id.clone(), U64(0), None, Some(1));
+        assert_eq!(page.items[0].book_id, Some(second_id.clone()));
+        assert_eq!(page.next_cursor, Some(1));
+        let page = contract.get_books_updated_since(account_id.clone(), U64(0), Some(1), None);
+        assert_eq!(page.items[0].book_id, Some(first_id));
+        assert!(!page.has_more);
+
+        contract.archive_book(second_id);
+        let page = contract.get_books_updated_since(account_id, U64(0), None, None);
+        assert_eq!(page.items.len(), 1);
+    }
+}
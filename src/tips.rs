@@ -0,0 +1,331 @@
+use crate::*;
+use near_sdk::json_types::U128;
+use near_sdk::{ext_contract, is_promise_success, Promise};
+
+const FEE_DENOMINATOR: u32 = 10_000;
+const GAS_FOR_RESOLVE_TIP: Gas = 5_000_000_000_000;
+
+#[ext_contract(ext_tip_resolver)]
+pub trait TipResolver {
+    fn resolve_tip(
+        &mut self,
+        tipper_id: AccountId,
+        amount: U128,
+        fee: U128,
+        reviewer_id: Option<AccountId>,
+    ) -> bool;
+}
+
+#[near_bindgen]
+impl Contract {
+    /// Sets the share of each tip, in basis points, that the contract keeps.
+    pub fn set_tip_fee(&mut self, fee_bps: u32) {
+        self.update_config(ConfigPatch {
+            tip_fee_bps: Some(fee_bps),
+            ..Default::default()
+        });
+    }
+
+    pub fn get_tip_fee(&self) -> u32 {
+        self.internal_config().tip_fee_bps
+    }
+
+    /// Sends the tip fees collected so far to the owner and returns the amount.
+    pub fn withdraw_tip_fees(&mut self) -> U128 {
+        self.assert_owner();
+        let amount = std::mem::take(&mut self.tip_fees);
+        assert!(amount > 0, "No tip fees to withdraw");
+        Promise::new(self.owner_id.clone()).transfer(amount);
+        U128(amount)
+    }
+
+    pub fn get_tip_fees(&self) -> U128 {
+        U128(self.tip_fees)
+    }
+
+    /// Forwards the attached deposit, minus the tip fee, to the book's author account. The
+    /// tipper gets the whole deposit back if the transfer fails.
+    #[payable]
+    pub fn tip_author(&mut self, book_id: BookId) -> Promise {
+        self.assert_can_mutate();
+        let amount = env::attached_deposit();
+        assert!(amount > 0, "Requires attached deposit");
+        let book = self
+            .internal_get_visible_book(&book_id)
+            .expect("Book does not exist");
+        let tipper_id = env::predecessor_account_id();
+        assert_ne!(
+            book.account_id.as_ref(),
+            Some(&tipper_id),
+            "You cannot tip the author of your own book"
+        );
+        let author_account_id = book.author_account_id.expect("Book has no author account");
+        let fee = amount * self.internal_config().tip_fee_bps as u128 / FEE_DENOMINATOR as u128;
+        self.tip_fees += fee;
+
+        emit_tip_event(TipEvent::Sent([TipData {
+            account_id: &tipper_id,
+            counterparty_id: &author_account_id,
+            book_id: &book_id,
+            review_id: None,
+            amount: U128(amount),
+        }]));
+        emit_tip_event(TipEvent::Received([TipData {
+            account_id: &author_account_id,
+            counterparty_id: &tipper_id,
+            book_id: &book_id,
+            review_id: None,
+            amount: U128(amount - fee),
+        }]));
+
+        Promise::new(author_account_id)
+            .transfer(amount - fee)
+            .then(ext_tip_resolver::resolve_tip(
+                tipper_id,
+                U128(amount),
+                U128(fee),
+                None,
+                &env::current_account_id(),
+                0,
+                GAS_FOR_RESOLVE_TIP,
+            ))
+    }
+
+    /// Forwards the attached deposit, minus the tip fee, to the review's author and adds it to
+    /// their lifetime tips. The tipper gets the whole deposit back if the transfer fails.
+    #[payable]
+    pub fn tip_review(&mut self, review_id: ReviewId) -> Promise {
+        self.assert_can_mutate();
+        let amount = env::attached_deposit();
+        assert!(amount > 0, "Requires attached deposit");
+        let review = self
+            .internal_get_visible_review(&review_id)
+            .expect("Review does not exist");
+        let tipper_id = env::predecessor_account_id();
+        assert_ne!(review.reviewer, tipper_id, "You cannot tip your own review");
+        let fee = amount * self.internal_config().tip_fee_bps as u128 / FEE_DENOMINATOR as u128;
+        self.tip_fees += fee;
+
+        let total = self
+            .review_tips_by_account_id
+            .get(&review.reviewer)
+            .unwrap_or(0);
+        self.review_tips_by_account_id
+            .insert(&review.reviewer, &(total + amount - fee));
+
+        emit_tip_event(TipEvent::Sent([TipData {
+            account_id: &tipper_id,
+            counterparty_id: &review.reviewer,
+            book_id: &review.book_id,
+            review_id: Some(&review_id),
+            amount: U128(amount),
+        }]));
+        emit_tip_event(TipEvent::Received([TipData {
+            account_id: &review.reviewer,
+            counterparty_id: &tipper_id,
+            book_id: &review.book_id,
+            review_id: Some(&review_id),
+            amount: U128(amount - fee),
+        }]));
+
+        Promise::new(review.reviewer.clone())
+            .transfer(amount - fee)
+            .then(ext_tip_resolver::resolve_tip(
+                tipper_id,
+                U128(amount),
+                U128(fee),
+                Some(review.reviewer),
+                &env::current_account_id(),
+                0,
+                GAS_FOR_RESOLVE_TIP,
+            ))
+    }
+
+    /// Refunds a tip whose transfer failed and takes back its fee and, for a review tip, the
+    /// reviewer's lifetime total. Returns whether the tip went through.
+    #[private]
+    pub fn resolve_tip(
+        &mut self,
+        tipper_id: AccountId,
+        amount: U128,
+        fee: U128,
+        reviewer_id: Option<AccountId>,
+    ) -> bool {
+        if is_promise_success() {
+            return true;
+        }
+        self.tip_fees -= fee.0;
+        if let Some(reviewer_id) = reviewer_id {
+            let total = self
+                .review_tips_by_account_id
+                .get(&reviewer_id)
+                .unwrap_or(0);
+            self.review_tips_by_account_id
+                .insert(&reviewer_id, &(total - (amount.0 - fee.0)));
+        }
+        Promise::new(tipper_id).transfer(amount.0);
+        false
+    }
+
+    /// Lifetime review tips an account has received, after fees.
+    pub fn get_review_tips(&self, account_id: ValidAccountId) -> U128 {
+        U128(
+            self.review_tips_by_account_id
+                .get(account_id.as_ref())
+                .unwrap_or(0),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{get_context, get_contract, get_sample_book};
+    use near_sdk::test_utils::get_logs;
+    use near_sdk::testing_env;
+    use near_sdk::{MockedBlockchain, PromiseResult};
+    use std::convert::TryFrom;
+
+    #[test]
+    fn test_tip_author() {
+        let mut context = get_context(vec![], false);
+        context.account_balance = 10u128.pow(24);
+        testing_env!(context.clone());
+        let mut contract = get_contract();
+        let mut config = contract.get_config();
+        config.tip_fee_bps = 500;
+        contract.internal_set_config(&config);
+        let book_id = contract.add_book(Book {
+            author_account_id: Some("author_near".to_string()),
+            ..get_sample_book()
+        });
+
+        context.predecessor_account_id = "dave_near".to_string();
+        context.attached_deposit = 1_000;
+        context.storage_usage = env::storage_usage();
+        testing_env!(context);
+        contract.tip_author(book_id);
+        assert_eq!(contract.get_tip_fees(), U128(50));
+
+        assert_eq!(
+            get_logs(),
+            vec![
+                r#"EVENT_JSON:{"standard":"book_tracker","version":"1.0.0","event":"tip_sent","data":[{"account_id":"dave_near","counterparty_id":"author_near","book_id":"1","amount":"1000"}]}"#,
+                r#"EVENT_JSON:{"standard":"book_tracker","version":"1.0.0","event":"tip_received","data":[{"account_id":"author_near","counterparty_id":"dave_near","book_id":"1","amount":"950"}]}"#,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_tip_review() {
+        let mut context = get_context(vec![], false);
+        context.account_balance = 10u128.pow(24);
+        testing_env!(context.clone());
+        let mut contract = get_contract();
+        let book_id = contract.add_book(get_sample_book());
+        let review_id = contract.add_review(book_id, "Clear and practical".to_string(), 4);
+
+        context.predecessor_account_id = "dave_near".to_string();
+        context.attached_deposit = 1_000;
+        context.storage_usage = env::storage_usage();
+        testing_env!(context);
+        contract.tip_review(review_id.clone());
+        contract.tip_review(review_id);
+
+        assert_eq!(
+            contract.get_review_tips(ValidAccountId::try_from("carol_near").unwrap()),
+            U128(2_000)
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "You cannot tip your own review")]
+    fn test_tip_own_review() {
+        let mut context = get_context(vec![], false);
+        testing_env!(context.clone());
+        let mut contract = get_contract();
+        let book_id = contract.add_book(get_sample_book());
+        let review_id = contract.add_review(book_id, "Clear and practical".to_string(), 4);
+
+        context.attached_deposit = 1_000;
+        testing_env!(context);
+        contract.tip_review(review_id);
+    }
+
+    #[test]
+    #[should_panic(expected = "You cannot tip the author of your own book")]
+    fn test_tip_own_book() {
+        let mut context = get_context(vec![], false);
+        testing_env!(context.clone());
+        let mut contract = get_contract();
+        let book_id = contract.add_book(Book {
+            author_account_id: Some("author_near".to_string()),
+            ..get_sample_book()
+        });
+
+        context.attached_deposit = 1_000;
+        testing_env!(context);
+        contract.tip_author(book_id);
+    }
+
+    #[test]
+    fn test_resolve_failed_tip() {
+        let mut context = get_context(vec![], false);
+        context.account_balance = 10u128.pow(24);
+        testing_env!(context.clone());
+        let mut contract = get_contract();
+        contract.tip_fees = 50;
+        contract
+            .review_tips_by_account_id
+            .insert(&"carol_near".to_string(), &950);
+
+        context.predecessor_account_id = "alice_near".to_string();
+        context.storage_usage = env::storage_usage();
+        testing_env!(
+            context,
+            Default::default(),
+            Default::default(),
+            Default::default(),
+            vec![PromiseResult::Failed]
+        );
+        assert!(!contract.resolve_tip(
+            "dave_near".to_string(),
+            U128(1_000),
+            U128(50),
+            Some("carol_near".to_string())
+        ));
+        assert_eq!(contract.get_tip_fees(), U128(0));
+        assert_eq!(
+            contract.get_review_tips(ValidAccountId::try_from("carol_near").unwrap()),
+            U128(0)
+        );
+    }
+
+    #[test]
+    fn test_withdraw_tip_fees() {
+        let mut context = get_context(vec![], false);
+        context.account_balance = 10u128.pow(24);
+        testing_env!(context.clone());
+        let mut contract = get_contract();
+        let mut config = contract.get_config();
+        config.tip_fee_bps = 500;
+        contract.internal_set_config(&config);
+        let book_id = contract.add_book(Book {
+            author_account_id: Some("author_near".to_string()),
+            ..get_sample_book()
+        });
+
+        context.predecessor_account_id = "dave_near".to_string();
+        context.attached_deposit = 1_000;
+        context.storage_usage = env::storage_usage();
+        testing_env!(context.clone());
+        contract.tip_author(book_id);
+
+        context.predecessor_account_id = "alice_near".to_string();
+        context.attached_deposit = 0;
+        context.storage_usage = env::storage_usage();
+        testing_env!(context);
+        assert_eq!(contract.withdraw_tip_fees(), U128(50));
+        assert_eq!(contract.get_tip_fees(), U128(0));
+    }
+}
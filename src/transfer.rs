@@ -0,0 +1,252 @@
+use crate::*;
+
+/// Books moved per call when no `limit` is given, so a library transfer stays within the gas
+/// limit.
+const DEFAULT_TRANSFER_LIMIT: u64 = 50;
+
+#[derive(Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct TransferProgress {
+    pub moved: u64,
+    pub has_more: bool,
+}
+
+#[near_bindgen]
+impl Contract {
+    /// Moves up to `limit` of the caller's books, archived books included, to
+    /// `new_account_id`, e.g. when rotating wallets. Call again while `has_more` is set.
+    /// Approvals and sales are cleared and the storage the books use is released from the
+    /// caller's balance and charged to the new account's, which must already be registered and
+    /// accept transfers.
+    #[payable]
+    pub fn transfer_library(
+        &mut self,
+        new_account_id: ValidAccountId,
+        limit: Option<u64>,
+    ) -> TransferProgress {
+        self.assert_can_mutate();
+        assert_one_yocto();
+        self.internal_transfer_library(
+            &env::predecessor_account_id(),
+            new_account_id.as_ref(),
+            limit,
+        )
+    }
+}
+
+impl Contract {
+    pub(crate) fn internal_transfer_library(
+        &mut self,
+        account_id: &AccountId,
+        new_account_id: &AccountId,
+        limit: Option<u64>,
+    ) -> TransferProgress {
+        assert_ne!(
+            account_id, new_account_id,
+            "Current and next owner must differ"
+        );
+        self.assert_accepts_transfers(new_account_id);
+        let limit = limit.unwrap_or(DEFAULT_TRANSFER_LIMIT) as usize;
+
+        let book_ids: Vec<BookId> = self
+            .books_by_owner_id
+            .get(account_id)
+            .map(|book_ids| book_ids.iter().take(limit).collect())
+            .unwrap_or_default();
+        let mut archived_ids: Vec<BookId> = Vec::new();
+        let mut old_archived = self.archived_books_by_owner_id.get(account_id);
+        if let Some(archived) = &old_archived {
+            archived_ids = archived.iter().take(limit - book_ids.len()).collect();
+        }
+        self.assert_book_quota(new_account_id, book_ids.len() as u64);
+
+        let initial_storage = env::storage_usage();
+        let mut books = Vec::with_capacity(book_ids.len());
+        for book_id in &book_ids {
+            self.assert_not_on_loan(book_id);
+            self.assert_not_at_auction(book_id);
+            let book = self.books.remove(book_id).unwrap();
+            self.internal_remove_book_from_owner(account_id, book_id, &book);
+            self.internal_remove_sale(book_id);
+            self.internal_clear_approvals(book_id);
+            self.internal_clear_shares(book_id);
+            books.push(book);
+        }
+        let mut archived_books = Vec::with_capacity(archived_ids.len());
+        if let Some(archived) = &mut old_archived {
+            for book_id in &archived_ids {
+                archived.remove(book_id);
+                archived_books.push(self.archived_books.remove(book_id).unwrap());
+            }
+            if archived.is_empty() {
+                self.archived_books_by_owner_id.remove(account_id);
+            } else {
+                self.archived_books_by_owner_id.insert(account_id, archived);
+            }
+        }
+        let old_owner_storage = self.internal_settle_storage(account_id, initial_storage);
+
+        let initial_storage = env::storage_usage();
+        for (book_id, mut book) in book_ids.iter().zip(books) {
+            book.account_id = Some(new_account_id.clone());
+            book.touch();
+            self.books.insert(book_id, &book);
+            self.internal_add_book_to_owner(new_account_id, book_id, &book);
+        }
+        if !archived_ids.is_empty() {
+            let mut new_archived_ids = self
+                .archived_books_by_owner_id
+                .get(new_account_id)
+                .unwrap_or_else(|| {
+                    UnorderedSet::new(StorageKey::ArchivedBooksPerOwner {
+                        account_hash: env::sha256(new_account_id.as_bytes()),
+                    })
+                });
+            for (book_id, mut book) in archived_ids.iter().zip(archived_books) {
+                book.account_id = Some(new_account_id.clone());
+                self.archived_books.insert(book_id, &book);
+                new_archived_ids.insert(book_id);
+            }
+            self.archived_books_by_owner_id
+                .insert(new_account_id, &new_archived_ids);
+        }
+        let new_owner_storage = self.internal_settle_storage(new_account_id, initial_storage);
+
+        if !book_ids.is_empty() {
+            emit_nft_event(NftEvent::Transfer([NftTransferData {
+                old_owner_id: account_id,
+                new_owner_id: new_account_id,
+                token_ids: &book_ids,
+                authorized_id: None,
+                memo: None,
+                old_owner_storage,
+                new_owner_storage,
+            }]));
+        } else {
+            // Only archived books moved, so no NFT event carries the storage changes.
+            if let Some(change) = old_owner_storage {
+                emit_storage_event(account_id, change);
+            }
+            if let Some(change) = new_owner_storage {
+                emit_storage_event(new_account_id, change);
+            }
+        }
+
+        let has_more = self.books_by_owner_id.get(account_id).is_some()
+            || self.archived_books_by_owner_id.get(account_id).is_some();
+        TransferProgress {
+            moved: (book_ids.len() + archived_ids.len()) as u64,
+            has_more,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{get_context, get_contract, get_sample_book};
+    use near_sdk::testing_env;
+    use near_sdk::MockedBlockchain;
+    use std::convert::TryFrom;
+
+    #[test]
+    fn test_transfer_library() {
+        let mut context = get_context(vec![], false);
+        testing_env!(context.clone());
+        let mut contract = get_contract();
+        let book_id = contract.add_book(get_sample_book());
+        let archived_id = contract.add_book(get_sample_book());
+        contract.archive_book(archived_id.clone());
+
+        context.attached_deposit = 1;
+        context.storage_usage = env::storage_usage();
+        testing_env!(context);
+        let dave = ValidAccountId::try_from("dave_near").unwrap();
+        let progress = contract.transfer_library(dave.clone(), None);
+        assert_eq!(progress.moved, 2);
+        assert!(!progress.has_more);
+
+        let carol = ValidAccountId::try_from("carol_near").unwrap();
+        assert_eq!(contract.get_books_count(carol.clone()), 0);
+        assert_eq!(contract.get_archived_books(carol, None, None).total, 0);
+        assert_eq!(contract.get_books_count(dave.clone()), 1);
+        assert_eq!(contract.nft_token(book_id).unwrap().owner_id, "dave_near");
+        let archived = contract.get_archived_books(dave, None, None).items;
+        assert_eq!(archived[0].book_id, Some(archived_id));
+        assert_eq!(archived[0].account_id, Some("dave_near".to_string()));
+    }
+
+    #[test]
+    fn test_transfer_library_moves_storage() {
+        let mut context = get_context(vec![], false);
+        testing_env!(context.clone());
+        let mut contract = get_contract();
+        let carol = ValidAccountId::try_from("carol_near").unwrap();
+        let dave = ValidAccountId::try_from("dave_near").unwrap();
+        contract.add_book(get_sample_book());
+        let archived_id = contract.add_book(get_sample_book());
+        contract.archive_book(archived_id);
+        let carol_bytes = contract
+            .storage_accounts
+            .get(carol.as_ref())
+            .unwrap()
+            .used_bytes;
+        let dave_bytes = contract
+            .storage_accounts
+            .get(dave.as_ref())
+            .unwrap()
+            .used_bytes;
+
+        context.attached_deposit = 1;
+        context.storage_usage = env::storage_usage();
+        testing_env!(context);
+        contract.transfer_library(dave.clone(), None);
+
+        let released = carol_bytes
+            - contract
+                .storage_accounts
+                .get(carol.as_ref())
+                .unwrap()
+                .used_bytes;
+        let charged = contract
+            .storage_accounts
+            .get(dave.as_ref())
+            .unwrap()
+            .used_bytes
+            - dave_bytes;
+        assert!(released > 0);
+        assert!(charged >= released);
+    }
+
+    #[test]
+    fn test_transfer_library_in_pages() {
+        let mut context = get_context(vec![], false);
+        testing_env!(context.clone());
+        let mut contract = get_contract();
+        contract.add_book(get_sample_book());
+        contract.add_book(get_sample_book());
+        let archived_id = contract.add_book(get_sample_book());
+        contract.archive_book(archived_id);
+
+        context.attached_deposit = 1;
+        context.storage_usage = env::storage_usage();
+        testing_env!(context);
+        let carol = ValidAccountId::try_from("carol_near").unwrap();
+        let dave = ValidAccountId::try_from("dave_near").unwrap();
+        let progress = contract.transfer_library(dave.clone(), Some(2));
+        assert_eq!(progress.moved, 2);
+        assert!(progress.has_more);
+        assert_eq!(contract.get_books_count(carol.clone()), 0);
+        assert_eq!(
+            contract.get_archived_books(carol.clone(), None, None).total,
+            1
+        );
+
+        let progress = contract.transfer_library(dave.clone(), Some(2));
+        assert_eq!(progress.moved, 1);
+        assert!(!progress.has_more);
+        assert_eq!(contract.get_archived_books(carol, None, None).total, 0);
+        assert_eq!(contract.get_books_count(dave.clone()), 2);
+        assert_eq!(contract.get_archived_books(dave, None, None).total, 1);
+    }
+}
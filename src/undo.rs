@@ -0,0 +1,359 @@
+use crate::*;
+
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct PendingDeletion {
+    account_id: AccountId,
+    book: Book,
+    deleted_at: u64,
+}
+
+/// A book the sender gave away with `nft_transfer` and can still take back.
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct PendingTransfer {
+    receiver_id: AccountId,
+    transferred_at: u64,
+}
+
+/// An entry on an account's undo stack.
+#[derive(BorshDeserialize, BorshSerialize, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub enum PendingAction {
+    Delete(BookId),
+    Transfer(BookId),
+}
+
+#[near_bindgen]
+impl Contract {
+    /// Reverts the caller's most recent deletion or transfer if it is still within the undo
+    /// window, and returns the book. A transfer can only be undone while the receiver still
+    /// holds the book.
+    pub fn undo_last_action(&mut self) -> Book {
+        self.assert_can_mutate();
+        let account_id = self.internal_caller_id();
+        let initial_storage = env::storage_usage();
+
+        let mut actions = self
+            .pending_actions_by_owner_id
+            .get(&account_id)
+            .unwrap_or_default();
+        let action = actions.pop().expect("No action to undo");
+        let acted_at = self.internal_drop_pending_action(&account_id, &action);
+        assert!(
+            env::block_timestamp() < acted_at + self.internal_config().undo_window.0,
+            "Undo window has expired"
+        );
+        self.internal_set_pending_actions(&account_id, &actions);
+        self.assert_book_quota(&account_id, 1);
+
+        let book = match action {
+            PendingAction::Delete(book_id) => {
+                let pending = self.pending_deletions.remove(&book_id).unwrap();
+                let mut book = pending.book;
+                book.touch();
+                self.books.insert(&book_id, &book);
+                self.internal_add_book_to_owner(&account_id, &book_id, &book);
+                let storage = self.internal_settle_storage(&account_id, initial_storage);
+                emit_book_event(
+                    BookEventKind::BookAdd,
+                    &account_id,
+                    std::slice::from_ref(&book_id),
+                    storage,
+                );
+                emit_nft_event(NftEvent::Mint([NftMintData {
+                    owner_id: &account_id,
+                    token_ids: &[book_id],
+                    storage,
+                }]));
+                book
+            }
+            PendingAction::Transfer(book_id) => {
+                let pending = self
+                    .pending_transfers
+                    .remove(&(account_id.clone(), book_id.clone()))
+                    .unwrap();
+                assert!(
+                    self.books
+                        .get(&book_id)
+                        .is_some_and(|book| book.account_id.as_ref() == Some(&pending.receiver_id)),
+                    "The receiver no longer holds this book"
+                );
+                self.internal_charge_storage(&account_id, initial_storage);
+                return self.internal_transfer_book(
+                    &pending.receiver_id,
+                    &account_id,
+                    &book_id,
+                    None,
+                    None,
+                );
+            }
+        };
+
+        book
+    }
+
+    /// Makes up to `limit` deletions and transfers permanent once their undo window has passed,
+    /// oldest first, and returns how many were finalized. Callable by anyone so it can be run
+    /// on a schedule.
+    pub fn finalize_actions(&mut self, limit: Option<u64>) -> u64 {
+        self.assert_can_mutate();
+        let limit = limit.unwrap_or(u64::MAX);
+        let now = env::block_timestamp();
+        let undo_window = self.internal_config().undo_window.0;
+
+        let mut finalized = 0;
+        while finalized < limit {
+            let (account_id, action) = match self.pending_actions_by_deadline.min() {
+                Some((acted_at, account_id, action)) if now >= acted_at + undo_window => {
+                    (account_id, action)
+                }
+                _ => break,
+            };
+            let initial_storage = env::storage_usage();
+            self.internal_drop_pending_action(&account_id, &action);
+            match &action {
+                PendingAction::Delete(book_id) => {
+                    self.pending_deletions.remove(book_id);
+                    self.internal_remove_book_data(book_id);
+                }
+                PendingAction::Transfer(book_id) => {
+                    self.pending_transfers
+                        .remove(&(account_id.clone(), book_id.clone()));
+                }
+            }
+
+            let mut actions = self
+                .pending_actions_by_owner_id
+                .get(&account_id)
+                .unwrap_or_default();
+            actions.retain(|pending| pending != &action);
+            self.internal_set_pending_actions(&account_id, &actions);
+            self.internal_charge_storage(&account_id, initial_storage);
+            finalized += 1;
+        }
+
+        finalized
+    }
+}
+
+impl Contract {
+    /// Drops everything stored alongside a book once it is gone for good.
+    pub(crate) fn internal_remove_book_data(&mut self, book_id: &BookId) {
+        self.bookmarks_by_book_id.remove(book_id);
+        self.curriculum_by_book_id.remove(book_id);
+        self.related_by_book_id.remove(book_id);
+        self.approvals_by_book_id.remove(book_id);
+        self.royalties_by_book_id.remove(book_id);
+        self.featured_book_ids.remove(book_id);
+        self.internal_clear_shares(book_id);
+        self.buddy_read_ids_by_book_id.remove(book_id);
+        self.internal_remove_sale(book_id);
+    }
+
+    pub(crate) fn internal_queue_deletion(
+        &mut self,
+        account_id: &AccountId,
+        book_id: &BookId,
+        book: &Book,
+    ) {
+        let pending = PendingDeletion {
+            account_id: account_id.clone(),
+            book: book.clone(),
+            deleted_at: env::block_timestamp(),
+        };
+        self.pending_deletions.insert(book_id, &pending);
+        self.internal_push_pending_action(account_id, PendingAction::Delete(book_id.clone()));
+    }
+
+    /// Records a transfer the sender can undo. A newer transfer of the same book by the same
+    /// sender replaces the older one.
+    pub(crate) fn internal_queue_transfer(
+        &mut self,
+        sender_id: &AccountId,
+        receiver_id: &AccountId,
+        book_id: &BookId,
+    ) {
+        let action = PendingAction::Transfer(book_id.clone());
+        if self
+            .pending_transfers
+            .get(&(sender_id.clone(), book_id.clone()))
+            .is_some()
+        {
+            self.internal_drop_pending_action(sender_id, &action);
+            let mut actions = self
+                .pending_actions_by_owner_id
+                .get(sender_id)
+                .unwrap_or_default();
+            actions.retain(|pending| pending != &action);
+            self.internal_set_pending_actions(sender_id, &actions);
+        }
+        let pending = PendingTransfer {
+            receiver_id: receiver_id.clone(),
+            transferred_at: env::block_timestamp(),
+        };
+        self.pending_transfers
+            .insert(&(sender_id.clone(), book_id.clone()), &pending);
+        self.internal_push_pending_action(sender_id, action);
+    }
+
+    fn internal_push_pending_action(&mut self, account_id: &AccountId, action: PendingAction) {
+        self.pending_actions_by_deadline.insert(
+            &(env::block_timestamp(), account_id.clone(), action.clone()),
+            &(),
+        );
+        let mut actions = self
+            .pending_actions_by_owner_id
+            .get(account_id)
+            .unwrap_or_default();
+        actions.push(action);
+        self.internal_set_pending_actions(account_id, &actions);
+    }
+
+    /// Takes an action off the finalization queue and returns when it happened. Its record and
+    /// the owner's stack are left to the caller.
+    pub(crate) fn internal_drop_pending_action(
+        &mut self,
+        account_id: &AccountId,
+        action: &PendingAction,
+    ) -> u64 {
+        let acted_at = match action {
+            PendingAction::Delete(book_id) => {
+                self.pending_deletions.get(book_id).unwrap().deleted_at
+            }
+            PendingAction::Transfer(book_id) => {
+                self.pending_transfers
+                    .get(&(account_id.clone(), book_id.clone()))
+                    .unwrap()
+                    .transferred_at
+            }
+        };
+        self.pending_actions_by_deadline
+            .remove(&(acted_at, account_id.clone(), action.clone()));
+        acted_at
+    }
+
+    pub(crate) fn internal_set_pending_actions(
+        &mut self,
+        account_id: &AccountId,
+        actions: &[PendingAction],
+    ) {
+        if actions.is_empty() {
+            self.pending_actions_by_owner_id.remove(account_id);
+        } else {
+            self.pending_actions_by_owner_id
+                .insert(account_id, &actions.to_vec());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{get_context, get_contract, get_sample_book};
+    use near_sdk::testing_env;
+    use near_sdk::MockedBlockchain;
+    use std::convert::TryFrom;
+
+    #[test]
+    fn test_undo_delete_book() {
+        let mut context = get_context(vec![], false);
+        context.attached_deposit = 1;
+        testing_env!(context);
+        let mut contract = get_contract();
+        let book_id = contract.add_book(get_sample_book());
+        contract.add_bookmark(book_id.clone(), "Brakes".to_string(), 42);
+
+        contract.delete_book(book_id.clone());
+        let book = contract.undo_last_action();
+
+        assert_eq!(book.book_id, Some(book_id.clone()));
+        assert_eq!(contract.get_book(book_id.clone()).title, book.title);
+        assert_eq!(contract.get_bookmarks(book_id).len(), 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "No action to undo")]
+    fn test_undo_after_finalize() {
+        let mut context = get_context(vec![], false);
+        context.attached_deposit = 1;
+        testing_env!(context.clone());
+        let mut contract = get_contract();
+        let book_id = contract.add_book(get_sample_book());
+        contract.add_bookmark(book_id.clone(), "Brakes".to_string(), 42);
+        contract.delete_book(book_id.clone());
+        assert_eq!(contract.finalize_actions(None), 0);
+
+        context.block_timestamp = contract.get_config().undo_window.0;
+        context.storage_usage = env::storage_usage();
+        testing_env!(context);
+        assert_eq!(contract.finalize_actions(None), 1);
+        assert_eq!(contract.get_bookmarks(book_id).len(), 0);
+
+        contract.undo_last_action();
+    }
+
+    #[test]
+    fn test_undo_transfer() {
+        let mut context = get_context(vec![], false);
+        context.attached_deposit = 1;
+        testing_env!(context);
+        let mut contract = get_contract();
+        let book_id = contract.add_book(get_sample_book());
+        let dave = ValidAccountId::try_from("dave_near").unwrap();
+        contract.nft_transfer(dave.clone(), book_id.clone(), None, None);
+
+        let book = contract.undo_last_action();
+        assert_eq!(book.account_id, Some("carol_near".to_string()));
+        assert_eq!(contract.get_books_count(dave), 0);
+        assert_eq!(contract.finalize_actions(None), 0);
+    }
+
+    #[test]
+    fn test_finalize_transfers_of_one_book_in_one_block() {
+        let mut context = get_context(vec![], false);
+        context.attached_deposit = 1;
+        testing_env!(context.clone());
+        let mut contract = get_contract();
+        let book_id = contract.add_book(get_sample_book());
+        contract.nft_transfer(
+            ValidAccountId::try_from("dave_near").unwrap(),
+            book_id.clone(),
+            None,
+            None,
+        );
+
+        context.predecessor_account_id = "dave_near".to_string();
+        context.storage_usage = env::storage_usage();
+        testing_env!(context.clone());
+        contract.nft_transfer(
+            ValidAccountId::try_from("erin_near").unwrap(),
+            book_id,
+            None,
+            None,
+        );
+        assert_eq!(contract.pending_actions_by_deadline.len(), 2);
+
+        context.block_timestamp = contract.get_config().undo_window.0;
+        context.storage_usage = env::storage_usage();
+        testing_env!(context);
+        assert_eq!(contract.finalize_actions(None), 2);
+        assert_eq!(contract.pending_transfers.len(), 0);
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Library is full at 1 books, delete some books or ask for more capacity"
+    )]
+    fn test_undo_delete_respects_quota() {
+        let mut context = get_context(vec![], false);
+        context.attached_deposit = 1;
+        testing_env!(context);
+        let mut contract = get_contract();
+        let mut config = contract.get_config();
+        config.max_books_per_account = Some(1);
+        contract.internal_set_config(&config);
+        let book_id = contract.add_book(get_sample_book());
+        contract.delete_book(book_id);
+        contract.add_book(get_sample_book());
+        contract.undo_last_action();
+    }
+}
@@ -0,0 +1,137 @@
+use crate::*;
+
+/// Who can see a book. Unlisted books are left out of listings but can still be fetched by id;
+/// private books only show up in the owner's `get_my_books`. Contract state is public on chain,
+/// so this keeps books off profiles and views, it does not encrypt them.
+#[derive(
+    BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, Copy, PartialEq, Debug, Default,
+)]
+#[serde(crate = "near_sdk::serde")]
+pub enum Visibility {
+    #[default]
+    Public,
+    Unlisted,
+    Private,
+}
+
+impl Book {
+    pub(crate) fn is_listed(&self) -> bool {
+        self.visibility == Visibility::Public
+    }
+}
+
+#[near_bindgen]
+impl Contract {
+    pub fn set_visibility(&mut self, book_id: BookId, visibility: Visibility) -> Book {
+        self.assert_can_mutate();
+        let account_id = self.internal_library_owner(&book_id);
+        let initial_storage = env::storage_usage();
+
+        let mut book = self.books.get(&book_id).unwrap();
+        book.visibility = visibility;
+        self.internal_touch_book(&account_id, &mut book);
+        self.books.insert(&book_id, &book);
+        self.internal_charge_book_update(&account_id, &book_id, initial_storage);
+
+        book
+    }
+
+    /// Lists the caller's books, private ones included. Views cannot tell who is asking, so
+    /// this has to be called as a transaction.
+    pub fn get_my_books(&mut self, from_index: Option<u64>, limit: Option<u64>) -> Page<Book> {
+        let account_id = self.internal_caller_id();
+        match self.books_by_owner_id.get(&account_id) {
+            Some(book_ids) => {
+                Page::from_vector(book_ids.as_vector(), from_index, limit, |book_id| {
+                    self.books.get(&book_id)
+                })
+            }
+            None => Page::empty(),
+        }
+    }
+}
+
+impl Contract {
+    /// A book as views fetching it by id may return it: anything but private or hidden by
+    /// reports.
+    pub(crate) fn internal_get_visible_book(&self, book_id: &BookId) -> Option<Book> {
+        self.books.get(book_id).filter(|book| {
+            book.visibility != Visibility::Private
+                && !self.internal_is_hidden(&ReportTarget::Book(book_id.clone()))
+        })
+    }
+
+    /// A book as listings may return it: public only.
+    pub(crate) fn internal_get_listed_book(&self, book_id: &BookId) -> Option<Book> {
+        self.books
+            .get(book_id)
+            .filter(|book| self.internal_is_listed(book))
+    }
+
+    /// Whether listings may include `book`: public and not hidden by reports.
+    pub(crate) fn internal_is_listed(&self, book: &Book) -> bool {
+        book.is_listed()
+            && !book.book_id.as_ref().is_some_and(|book_id| {
+                self.internal_is_hidden(&ReportTarget::Book(book_id.clone()))
+            })
+    }
+
+    /// Whether data attached to a book, such as its bookmarks, must be kept out of views.
+    pub(crate) fn internal_is_private(&self, book_id: &BookId) -> bool {
+        self.books
+            .get(book_id)
+            .or_else(|| self.archived_books.get(book_id))
+            .is_some_and(|book| book.visibility == Visibility::Private)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{get_context, get_contract, get_sample_book};
+    use near_sdk::testing_env;
+    use near_sdk::MockedBlockchain;
+    use std::convert::TryFrom;
+
+    #[test]
+    fn test_visibility() {
+        let context = get_context(vec![], false);
+        testing_env!(context);
+        let mut contract = get_contract();
+        let public_id = contract.add_book(get_sample_book());
+        let unlisted_id = contract.add_book(Book {
+            visibility: Visibility::Unlisted,
+            ..get_sample_book()
+        });
+        let private_id = contract.add_book(get_sample_book());
+        contract.add_bookmark(private_id.clone(), "Chapter 1".to_string(), 12);
+        contract.set_visibility(private_id.clone(), Visibility::Private);
+
+        let account_id = ValidAccountId::try_from("carol_near").unwrap();
+        let listed = contract
+            .get_books(Some(account_id.clone()), None, None, None, None, None)
+            .items;
+        assert_eq!(listed.len(), 1);
+        assert_eq!(listed[0].book_id, Some(public_id));
+        assert_eq!(
+            contract.get_book(unlisted_id.clone()).book_id,
+            Some(unlisted_id)
+        );
+        assert!(contract.get_books_by_ids(vec![private_id.clone()])[0].is_none());
+        assert!(contract.get_bookmarks(private_id).is_empty());
+        assert_eq!(contract.get_my_books(None, None).items.len(), 3);
+    }
+
+    #[test]
+    #[should_panic(expected = "Book does not exist")]
+    fn test_get_private_book() {
+        let context = get_context(vec![], false);
+        testing_env!(context);
+        let mut contract = get_contract();
+        let book_id = contract.add_book(Book {
+            visibility: Visibility::Private,
+            ..get_sample_book()
+        });
+        contract.get_book(book_id);
+    }
+}
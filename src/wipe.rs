@@ -0,0 +1,155 @@
+use crate::*;
+
+/// Books removed per call when no `limit` is given, so a wipe stays within the gas limit.
+const DEFAULT_WIPE_LIMIT: u64 = 50;
+
+#[derive(Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct WipeProgress {
+    pub deleted: u64,
+    pub has_more: bool,
+}
+
+#[near_bindgen]
+impl Contract {
+    /// Permanently deletes up to `limit` of the caller's books, archived and pending deletions
+    /// included, with everything stored alongside them. Once none are left the caller's shelves,
+    /// indexes, stats and snapshots are dropped too. Call again while `has_more` is set; the
+    /// deletions cannot be undone. Requires one yoctoNEAR.
+    #[payable]
+    pub fn delete_all_my_books(&mut self, limit: Option<u64>) -> WipeProgress {
+        self.assert_can_mutate();
+        assert_one_yocto();
+        let account_id = self.internal_caller_id();
+        let initial_storage = env::storage_usage();
+        let mut remaining = limit.unwrap_or(DEFAULT_WIPE_LIMIT);
+
+        let book_ids: Vec<BookId> = self
+            .books_by_owner_id
+            .get(&account_id)
+            .map(|book_ids| book_ids.iter().take(remaining as usize).collect())
+            .unwrap_or_default();
+        for book_id in &book_ids {
+            self.assert_not_on_loan(book_id);
+            self.assert_not_at_auction(book_id);
+            let book = self.books.remove(book_id).unwrap();
+            self.internal_remove_book_from_owner(&account_id, book_id, &book);
+            self.internal_remove_book_data(book_id);
+        }
+        remaining -= book_ids.len() as u64;
+
+        let mut archived_ids = Vec::new();
+        if let Some(mut archived) = self.archived_books_by_owner_id.get(&account_id) {
+            archived_ids = archived.iter().take(remaining as usize).collect();
+            for book_id in &archived_ids {
+                archived.remove(book_id);
+                self.archived_books.remove(book_id);
+                self.internal_remove_book_data(book_id);
+            }
+            if archived.is_empty() {
+                self.archived_books_by_owner_id.remove(&account_id);
+            } else {
+                self.archived_books_by_owner_id
+                    .insert(&account_id, &archived);
+            }
+        }
+        remaining -= archived_ids.len() as u64;
+
+        let mut pending_actions = self
+            .pending_actions_by_owner_id
+            .get(&account_id)
+            .unwrap_or_default();
+        let finalized_count = pending_actions.len().min(remaining as usize);
+        for action in pending_actions.drain(..finalized_count) {
+            self.internal_drop_pending_action(&account_id, &action);
+            match action {
+                PendingAction::Delete(book_id) => {
+                    self.pending_deletions.remove(&book_id);
+                    self.internal_remove_book_data(&book_id);
+                }
+                PendingAction::Transfer(book_id) => {
+                    self.pending_transfers
+                        .remove(&(account_id.clone(), book_id));
+                }
+            }
+        }
+        self.internal_set_pending_actions(&account_id, &pending_actions);
+
+        let has_more = self.books_by_owner_id.get(&account_id).is_some()
+            || self.archived_books_by_owner_id.get(&account_id).is_some()
+            || !pending_actions.is_empty();
+        if !has_more {
+            self.internal_remove_account_indexes(&account_id);
+        }
+
+        let burned_ids = [book_ids, archived_ids].concat();
+        if burned_ids.is_empty() {
+            self.internal_charge_storage(&account_id, initial_storage);
+        } else {
+            let storage = self.internal_settle_storage(&account_id, initial_storage);
+            emit_book_event(BookEventKind::BookDelete, &account_id, &burned_ids, storage);
+            emit_nft_event(NftEvent::Burn([NftMintData {
+                owner_id: &account_id,
+                token_ids: &burned_ids,
+                storage,
+            }]));
+        }
+
+        WipeProgress {
+            deleted: (burned_ids.len() + finalized_count) as u64,
+            has_more,
+        }
+    }
+}
+
+impl Contract {
+    /// Drops the per-account shelves, indexes and stats left behind once a library is empty.
+    fn internal_remove_account_indexes(&mut self, account_id: &AccountId) {
+        for status in [Status::List, Status::Read, Status::Finished].iter() {
+            self.books_by_owner_status
+                .remove(&(account_id.clone(), *status));
+        }
+        self.titles_by_owner_id.remove(account_id);
+        self.updated_by_owner_id.remove(account_id);
+        self.counters_by_owner_id.remove(account_id);
+        self.snapshots_by_owner_id.remove(account_id);
+        self.activity_by_account_id.remove(account_id);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{get_context, get_contract, get_sample_book};
+    use near_sdk::testing_env;
+    use near_sdk::MockedBlockchain;
+    use std::convert::TryFrom;
+
+    #[test]
+    fn test_delete_all_my_books() {
+        let mut context = get_context(vec![], false);
+        context.attached_deposit = 1;
+        testing_env!(context);
+        let mut contract = get_contract();
+        contract.add_book(get_sample_book());
+        let archived_id = contract.add_book(get_sample_book());
+        contract.archive_book(archived_id);
+        let deleted_id = contract.add_book(get_sample_book());
+        contract.delete_book(deleted_id);
+        contract.snapshot_shelf(Status::List, None);
+
+        let progress = contract.delete_all_my_books(Some(2));
+        assert_eq!(progress.deleted, 2);
+        assert!(progress.has_more);
+
+        let progress = contract.delete_all_my_books(None);
+        assert_eq!(progress.deleted, 1);
+        assert!(!progress.has_more);
+
+        let account_id = ValidAccountId::try_from("carol_near").unwrap();
+        assert_eq!(contract.get_books_count(account_id.clone()), 0);
+        assert_eq!(contract.get_snapshots(account_id, None, None).total, 0);
+        assert!(contract.pending_deletions.is_empty());
+        assert!(contract.archived_books.is_empty());
+    }
+}
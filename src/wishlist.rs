@@ -0,0 +1,226 @@
+use crate::*;
+use near_sdk::json_types::U128;
+use near_sdk::{assert_one_yocto, Promise};
+
+pub type WishlistItemId = String;
+
+/// A book an account would like to own. Anyone can see it and fulfil it for them.
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct WishlistItem {
+    item_id: WishlistItemId,
+    account_id: AccountId,
+    title: String,
+    author: Option<String>,
+    isbn: Option<String>,
+    note: Option<String>,
+    created_at: U64,
+}
+
+#[near_bindgen]
+impl Contract {
+    pub fn add_to_wishlist(
+        &mut self,
+        title: String,
+        author: Option<String>,
+        isbn: Option<String>,
+        note: Option<String>,
+    ) -> WishlistItemId {
+        self.assert_can_mutate();
+        let account_id = self.internal_caller_id();
+        let initial_storage = env::storage_usage();
+        let config = self.internal_config();
+        assert!(!title.is_empty(), "Title cannot be empty");
+        assert!(
+            title.len() <= config.max_title_length as usize,
+            "Title exceeds {} characters",
+            config.max_title_length
+        );
+        if let Some(note) = &note {
+            assert!(
+                note.len() <= config.max_description_length as usize,
+                "Note exceeds {} characters",
+                config.max_description_length
+            );
+        }
+
+        let item_id = format!("{}", self.wishlist_items_len + 1);
+        self.wishlist_items.insert(
+            &item_id,
+            &WishlistItem {
+                item_id: item_id.clone(),
+                account_id: account_id.clone(),
+                title,
+                author,
+                isbn,
+                note,
+                created_at: env::block_timestamp().into(),
+            },
+        );
+        let mut item_ids = self
+            .wishlist_by_account_id
+            .get(&account_id)
+            .unwrap_or_else(|| {
+                UnorderedSet::new(StorageKey::WishlistPerAccount {
+                    account_hash: env::sha256(account_id.as_bytes()),
+                })
+            });
+        item_ids.insert(&item_id);
+        self.wishlist_by_account_id.insert(&account_id, &item_ids);
+
+        self.wishlist_items_len += 1;
+        self.internal_charge_storage(&account_id, initial_storage);
+
+        item_id
+    }
+
+    pub fn remove_from_wishlist(&mut self, item_id: WishlistItemId) {
+        self.assert_can_mutate();
+        let account_id = self.internal_caller_id();
+        self.internal_take_wishlist_item(&account_id, &item_id);
+    }
+
+    /// Fulfils an item on `account_id`'s wishlist by sending them the attached deposit, e.g.
+    /// to buy the book. The item is removed.
+    #[payable]
+    pub fn fulfil_wishlist_item(
+        &mut self,
+        account_id: ValidAccountId,
+        item_id: WishlistItemId,
+    ) -> Promise {
+        self.assert_can_mutate();
+        let amount = env::attached_deposit();
+        assert!(amount > 0, "Requires attached deposit");
+        let wisher_id: AccountId = account_id.into();
+        self.internal_take_wishlist_item(&wisher_id, &item_id);
+
+        emit_wishlist_event(WishlistFulfilledData {
+            account_id: &wisher_id,
+            fulfiller_id: &env::predecessor_account_id(),
+            item_id: &item_id,
+            amount: Some(U128(amount)),
+            book_id: None,
+        });
+        Promise::new(wisher_id).transfer(amount)
+    }
+
+    /// Fulfils an item on `account_id`'s wishlist by transferring one of the caller's books to
+    /// them. Requires one yoctoNEAR, like `nft_transfer`.
+    #[payable]
+    pub fn fulfil_wishlist_item_with_book(
+        &mut self,
+        account_id: ValidAccountId,
+        item_id: WishlistItemId,
+        book_id: BookId,
+    ) {
+        self.assert_can_mutate();
+        assert_one_yocto();
+        let sender_id = env::predecessor_account_id();
+        let wisher_id: AccountId = account_id.into();
+        self.internal_take_wishlist_item(&wisher_id, &item_id);
+        self.internal_transfer_book(
+            &sender_id,
+            &wisher_id,
+            &book_id,
+            None,
+            Some(format!("wishlist {}", item_id)),
+        );
+
+        emit_wishlist_event(WishlistFulfilledData {
+            account_id: &wisher_id,
+            fulfiller_id: &sender_id,
+            item_id: &item_id,
+            amount: None,
+            book_id: Some(&book_id),
+        });
+    }
+
+    pub fn get_wishlist(
+        &self,
+        account_id: ValidAccountId,
+        from_index: Option<u64>,
+        limit: Option<u64>,
+    ) -> Page<WishlistItem> {
+        match self.wishlist_by_account_id.get(account_id.as_ref()) {
+            Some(item_ids) => {
+                Page::from_vector(item_ids.as_vector(), from_index, limit, |item_id| {
+                    self.wishlist_items.get(&item_id)
+                })
+            }
+            None => Page::empty(),
+        }
+    }
+}
+
+impl Contract {
+    /// Removes an item from `account_id`'s wishlist and releases its storage back to them.
+    fn internal_take_wishlist_item(&mut self, account_id: &AccountId, item_id: &WishlistItemId) {
+        let initial_storage = env::storage_usage();
+        let mut item_ids = self
+            .wishlist_by_account_id
+            .get(account_id)
+            .expect("Wishlist item does not exist");
+        assert!(item_ids.remove(item_id), "Wishlist item does not exist");
+        if item_ids.is_empty() {
+            self.wishlist_by_account_id.remove(account_id);
+        } else {
+            self.wishlist_by_account_id.insert(account_id, &item_ids);
+        }
+        self.wishlist_items.remove(item_id);
+        self.internal_charge_storage(account_id, initial_storage);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{get_context, get_contract, get_sample_book};
+    use near_sdk::testing_env;
+    use near_sdk::MockedBlockchain;
+    use std::convert::TryFrom;
+
+    #[test]
+    fn test_fulfil_wishlist_item_with_book() {
+        let mut context = get_context(vec![], false);
+        context.attached_deposit = 1;
+        testing_env!(context.clone());
+        let mut contract = get_contract();
+        let book_id = contract.add_book(get_sample_book());
+
+        context.predecessor_account_id = "dave_near".to_string();
+        context.storage_usage = env::storage_usage();
+        testing_env!(context.clone());
+        let item_id = contract.add_to_wishlist("Motorcycle Mechanics 101".into(), None, None, None);
+        let dave = ValidAccountId::try_from("dave_near").unwrap();
+        assert_eq!(contract.get_wishlist(dave.clone(), None, None).total, 1);
+
+        context.predecessor_account_id = "carol_near".to_string();
+        context.storage_usage = env::storage_usage();
+        testing_env!(context);
+        contract.fulfil_wishlist_item_with_book(dave.clone(), item_id, book_id.clone());
+
+        assert_eq!(
+            contract.get_book(book_id).account_id,
+            Some("dave_near".to_string())
+        );
+        assert_eq!(contract.get_wishlist(dave, None, None).total, 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "Wishlist item does not exist")]
+    fn test_fulfil_wishlist_item_twice() {
+        let mut context = get_context(vec![], false);
+        context.account_balance = 10u128.pow(24);
+        testing_env!(context.clone());
+        let mut contract = get_contract();
+        let item_id = contract.add_to_wishlist("Dune".into(), None, None, None);
+
+        context.predecessor_account_id = "dave_near".to_string();
+        context.attached_deposit = 1_000;
+        context.storage_usage = env::storage_usage();
+        testing_env!(context);
+        let carol = ValidAccountId::try_from("carol_near").unwrap();
+        contract.fulfil_wishlist_item(carol.clone(), item_id.clone());
+        contract.fulfil_wishlist_item(carol, item_id);
+    }
+}